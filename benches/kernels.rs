@@ -0,0 +1,93 @@
+//! Evaluation, flattening and bounding box kernels, run with `cargo bench`
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use macroquad::prelude::*;
+
+use numerical_curves::curve::Curve;
+use numerical_curves::math::*;
+use numerical_curves::point::Point;
+use numerical_curves::segment::CubicSegment;
+
+// Same sample count the editor uses per segment by default
+const SAMPLES: usize = 2000;
+
+fn segment() -> [Point; 4] {
+    [
+        Point::new(vec2(100., 500.), ORANGE),
+        Point::new(vec2(250., 80.), BLUE),
+        Point::new(vec2(520., 620.), RED),
+        Point::new(vec2(700., 150.), PURPLE)
+    ]
+}
+
+fn curve(segments: usize) -> Curve {
+    let mut curve = Curve::default();
+    for i in 0..segments * 3 + 1 {
+        let x = i as f32 * 40.;
+        let y = match i % 3 { 0 => 300., 1 => 100., _ => 500. };
+        curve.control.push(Point::new(vec2(x, y), WHITE));
+    }
+    curve
+}
+
+fn evaluation(c: &mut Criterion) {
+    let points = segment();
+    let ts: Vec<f32> = (0..SAMPLES).map(|i| i as f32 / (SAMPLES - 1) as f32).collect();
+    let mut group = c.benchmark_group("evaluate 2000 samples");
+
+    group.bench_function("de casteljau", |b| b.iter(|| {
+        ts.iter().map(|&t| decasteljau(black_box(&points), t).pos).fold(Vec2::ZERO, |a, p| a + p)
+    }));
+    group.bench_function("bernstein", |b| b.iter(|| {
+        ts.iter().map(|&t| cubic_bezier_powers(t, black_box(&points))).fold(Vec2::ZERO, |a, p| a + p)
+    }));
+    group.bench_function("horner", |b| b.iter(|| {
+        ts.iter().map(|&t| cubic_bezier(t, black_box(&points))).fold(Vec2::ZERO, |a, p| a + p)
+    }));
+    group.bench_function("power basis", |b| b.iter(|| {
+        let coefficients = power_basis(black_box(points));
+        ts.iter().map(|&t| eval_power_basis(&coefficients, t)).fold(Vec2::ZERO, |a, p| a + p)
+    }));
+    // [1 t t^2 t^3] M P multiplied out for every sample, the power basis does M P only once
+    group.bench_function("matrix form", |b| b.iter(|| {
+        let positions = black_box(points).map(|p| p.pos);
+        ts.iter().map(|&t| {
+            let row = [1., t, t * t, t * t * t];
+            let weights: [f32; 4] = std::array::from_fn(|j| (0..4).map(|i| row[i] * BEZIER_MATRIX[i][j]).sum());
+            weights.iter().zip(positions).fold(Vec2::ZERO, |sum, (&w, p)| sum + w * p)
+        }).fold(Vec2::ZERO, |a, p| a + p)
+    }));
+    group.bench_function("forward differencing", |b| b.iter(|| {
+        forward_differences(black_box(points), SAMPLES - 1).into_iter().fold(Vec2::ZERO, |a, p| a + p)
+    }));
+    group.bench_function("streamed forward differencing", |b| b.iter(|| {
+        CubicSegment::new(black_box(points)).iter_samples(SAMPLES).fold(Vec2::ZERO, |a, p| a + p)
+    }));
+    group.finish();
+}
+
+fn flattening(c: &mut Criterion) {
+    let points = segment();
+    let mut group = c.benchmark_group("flatten");
+    for tolerance in [1., 0.25, 0.05] {
+        group.bench_with_input(BenchmarkId::from_parameter(tolerance), &tolerance, |b, &tolerance| {
+            b.iter(|| flatten(black_box(&points), tolerance))
+        });
+    }
+    group.finish();
+}
+
+fn bounding_boxes(c: &mut Criterion) {
+    let points = segment();
+    let curve = curve(16);
+    let mut group = c.benchmark_group("bounding box");
+
+    group.bench_function("control points", |b| b.iter(|| Curve::bounding_box(black_box(&points))));
+    group.bench_function("tight", |b| b.iter(|| Curve::tight_bounding_box(black_box(&points))));
+    group.bench_function("whole curve", |b| b.iter(|| black_box(&curve).bounds()));
+    group.bench_function("oriented", |b| b.iter(|| black_box(&curve).oriented_bounding_box(0.25)));
+    group.finish();
+}
+
+criterion_group!(benches, evaluation, flattening, bounding_boxes);
+criterion_main!(benches);
@@ -0,0 +1,244 @@
+use crate::float::Float;
+use crate::vector::{Vector2, VectorSpace};
+
+/// Cubic bezier basis matrix M, so that B(t) = [1 t t^2 t^3] M P. Row i weighs the control
+/// points into the coefficient of t^i
+pub const BEZIER_MATRIX: [[f32; 4]; 4] = [
+    [1., 0., 0., 0.],
+    [-3., 3., 0., 0.],
+    [3., -6., 3., 0.],
+    [-1., 3., -3., 1.]
+];
+
+// Closest point scan before Newton's method
+const SCAN_STEPS: usize = 32;
+
+// A constant of the formulas in the scalar
+fn k<F: Float>(x: f64) -> F {
+    F::from_f64(x)
+}
+
+/// B(t) in Bernstein's form, nested Horner style in s = 1 - t and t so each term reuses the
+/// products of the one before instead of taking powers
+pub fn eval<F: Float, P: VectorSpace<F>>(points: [P; 4], t: F) -> P {
+    let [p0, p1, p2, p3] = points;
+    let s = F::ONE - t;
+    let t2 = t * t;
+    ((p0 * s + p1 * (k::<F>(3.) * t)) * s + p2 * (k::<F>(3.) * t2)) * s + p3 * (t2 * t)
+}
+
+/// B'(t), the tangent scaled by the speed
+pub fn velocity<F: Float, P: VectorSpace<F>>(points: [P; 4], t: F) -> P {
+    let [p0, p1, p2, p3] = points;
+    let s = F::ONE - t;
+    (((p1 - p0) * s + (p2 - p1) * (k::<F>(2.) * t)) * s + (p3 - p2) * (t * t)) * k(3.)
+}
+
+/// B''(t)
+pub fn acceleration<F: Float, P: VectorSpace<F>>(points: [P; 4], t: F) -> P {
+    let [p0, p1, p2, p3] = points;
+    ((p0 - p1 * k(2.) + p2) * (F::ONE - t) + (p1 - p2 * k(2.) + p3) * t) * k(6.)
+}
+
+/// B'''(t), the same all along a cubic
+pub fn jerk<F: Float, P: VectorSpace<F>>(points: [P; 4]) -> P {
+    let [p0, p1, p2, p3] = points;
+    (p3 - p2 * k(3.) + p1 * k(3.) - p0) * k(6.)
+}
+
+/// Signed curvature k(t) = (B' x B'') / |B'|^3, zero where the speed vanishes
+pub fn curvature<F: Float>(points: [Vector2<F>; 4], t: F) -> F {
+    let vel = velocity(points, t);
+    let acc = acceleration(points, t);
+
+    let speed = vel.length();
+    if speed < F::EPSILON { return F::ZERO };
+
+    vel.perp_dot(acc) / (speed * speed * speed)
+}
+
+/// B(t) by De Casteljau's repeated lerps
+pub fn decasteljau<F: Float, P: VectorSpace<F>>(points: [P; 4], t: F) -> P {
+    split(points, t).0[3]
+}
+
+/// Both halves of the segment split at `t`, with De Casteljau's algorithm
+pub fn split<F: Float, P: VectorSpace<F>>(points: [P; 4], t: F) -> ([P; 4], [P; 4]) {
+    let [a, b, c, d] = points;
+    let lerp = |p: P, q: P| p + (q - p) * t;
+
+    let ab = lerp(a, b);
+    let bc = lerp(b, c);
+    let cd = lerp(c, d);
+
+    let abc = lerp(ab, bc);
+    let bcd = lerp(bc, cd);
+
+    let abcd = lerp(abc, bcd);
+
+    ([a, ab, abc, abcd], [abcd, bcd, cd, d])
+}
+
+/// Power basis coefficients [c0, c1, c2, c3] so that B(t) = c0 + c1 t + c2 t^2 + c3 t^3,
+/// the product M P of the basis matrix with the control points
+pub fn power_basis<F: Float, P: VectorSpace<F>>(points: [P; 4]) -> [P; 4] {
+    BEZIER_MATRIX.map(|row| {
+        row.iter().zip(points).fold(points[0] * F::ZERO, |sum, (&weight, p)| sum + p * k(weight as f64))
+    })
+}
+
+/// B(t) from the coefficients of `power_basis`, the row [1 t t^2 t^3] times M P. With them
+/// worked out once per segment each sample is the two powers and three fused multiply-adds
+pub fn eval_power_basis<F: Float>(coefficients: &[Vector2<F>; 4], t: F) -> Vector2<F> {
+    let [c0, c1, c2, c3] = *coefficients;
+    let (t2, t3) = (t * t, t * t * t);
+    c3.mul_add(t3, c2.mul_add(t2, c1.mul_add(t, c0)))
+}
+
+/// Parameter of the point on the segment closest to `target`, along with that point.
+/// A coarse scan picks the starting guess then Newton's method refines the root of
+/// (B(t) - target) . B'(t)
+pub fn closest_param<F: Float>(points: [Vector2<F>; 4], target: Vector2<F>) -> (F, Vector2<F>) {
+    let scan = (0..=SCAN_STEPS).map(|i| F::from_usize(i) / F::from_usize(SCAN_STEPS));
+    let mut t = scan.fold((F::ZERO, None), |(best, best_distance), t| {
+        let distance = eval(points, t).distance_squared(target);
+        match best_distance.is_some_and(|best_distance| best_distance <= distance) {
+            true => (best, best_distance),
+            false => (t, Some(distance))
+        }
+    }).0;
+
+    for _ in 0..8 {
+        let diff = eval(points, t) - target;
+        let vel = velocity(points, t);
+        let acc = acceleration(points, t);
+
+        let numerator = diff.dot(vel);
+        let denominator = vel.dot(vel) + diff.dot(acc);
+        if denominator.abs() < F::EPSILON { break };
+
+        t = (t - numerator / denominator).clamp(F::ZERO, F::ONE);
+    }
+
+    (t, eval(points, t))
+}
+
+/// Pieces needed so that joining uniformly spaced samples of the segment with straight lines
+/// never strays more than `tolerance` from it. Wang's formula bounds the deviation by the
+/// second differences of the control points, which bound |B''(t)|
+pub fn flattening_steps<F: Float>(points: [Vector2<F>; 4], tolerance: F) -> usize {
+    let [p0, p1, p2, p3] = points;
+    let second_difference = (p0 - p1 * k(2.) + p2).length().max((p1 - p2 * k(2.) + p3).length());
+
+    // n(n - 1) / 8 with n = 3
+    let steps = (k::<F>(0.75) * second_difference / tolerance.max(F::EPSILON)).sqrt().ceil();
+    (steps.to_f64() as usize).max(1)
+}
+
+/// Min and max corners of the smallest axis aligned box around the segment, from its ends
+/// and the roots of each axis' derivative
+pub fn tight_bounds<F: Float>(points: [Vector2<F>; 4]) -> [Vector2<F>; 2] {
+    let [p0, p1, p2, p3] = points;
+    let (mut min, mut max) = (p0.min(p3), p0.max(p3));
+
+    // B'(t) / 3 = a t^2 + b t + c on each axis
+    let a = -p0 + p1 * k(3.) - p2 * k(3.) + p3;
+    let b = (p0 - p1 * k(2.) + p2) * k(2.);
+    let c = p1 - p0;
+    let mut extremum = |t: F| {
+        if t > F::ZERO && t < F::ONE {
+            let p = eval(points, t);
+            (min, max) = (min.min(p), max.max(p));
+        }
+    };
+    for (a, b, c) in [(a.x, b.x, c.x), (a.y, b.y, c.y)] {
+        if a.abs() < k::<F>(1e-6) * (b.abs() + c.abs()) {
+            if b != F::ZERO { extremum(-c / b) };
+            continue;
+        }
+        let delta = b * b - k::<F>(4.) * a * c;
+        if delta >= F::ZERO {
+            extremum((-b + delta.sqrt()) / (k::<F>(2.) * a));
+            extremum((-b - delta.sqrt()) / (k::<F>(2.) * a));
+        }
+    }
+
+    [min, max]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segment(points: [(f64, f64); 4]) -> [Vector2<f64>; 4] {
+        points.map(|(x, y)| Vector2::new(x, y))
+    }
+
+    fn wavy() -> [Vector2<f64>; 4] {
+        segment([(0., 0.), (20., 60.), (70., -30.), (90., 20.)])
+    }
+
+    fn ts() -> impl Iterator<Item = f64> {
+        (0..=20).map(|i| i as f64 / 20.)
+    }
+
+    #[test]
+    fn evaluations_agree() {
+        let points = wavy();
+        let coefficients = power_basis(points);
+        for t in ts() {
+            let p = eval(points, t);
+            assert!(p.distance(decasteljau(points, t)) < 1e-9);
+            assert!(p.distance(eval_power_basis(&coefficients, t)) < 1e-9);
+        }
+        assert_eq!(eval(points, 0.), points[0]);
+        assert_eq!(eval(points, 1.), points[3]);
+
+        // Anything that adds and scales, here 3D positions in f32
+        let points = [glam::Vec3::ZERO, glam::Vec3::X, glam::Vec3::Y, glam::Vec3::ONE];
+        assert!((eval(points, 0.3) - decasteljau(points, 0.3)).length() < 1e-6);
+    }
+
+    #[test]
+    fn split_halves_trace_the_segment() {
+        let points = wavy();
+        let t = 0.3;
+        let (left, right) = split(points, t);
+        assert_eq!(left[3], right[0]);
+        assert!(left[3].distance(eval(points, t)) < 1e-9);
+        for u in ts() {
+            assert!(eval(left, u).distance(eval(points, u * t)) < 1e-9);
+            assert!(eval(right, u).distance(eval(points, t + u * (1. - t))) < 1e-9);
+        }
+    }
+
+    #[test]
+    fn tight_bounds_touch_the_curve() {
+        // A symmetric arch peaks at 3/4 of its handles' height
+        let [min, max] = tight_bounds(segment([(0., 0.), (0., 1.), (1., 1.), (1., 0.)]));
+        assert!(min.distance(Vector2::new(0., 0.)) < 1e-9 && max.distance(Vector2::new(1., 0.75)) < 1e-9);
+
+        let points = wavy();
+        let [min, max] = tight_bounds(points);
+        let samples = (0..=1000).map(|i| eval(points, i as f64 / 1000.));
+        let (mut low, mut high) = (points[0], points[0]);
+        for p in samples {
+            assert!(p.x >= min.x - 1e-9 && p.y >= min.y - 1e-9 && p.x <= max.x + 1e-9 && p.y <= max.y + 1e-9);
+            (low, high) = (low.min(p), high.max(p));
+        }
+        assert!(low.distance(min) < 1e-3 && high.distance(max) < 1e-3);
+
+        // Straight, with the handles on the line
+        let [min, max] = tight_bounds(segment([(0., 0.), (1., 1.), (2., 2.), (3., 3.)]));
+        assert_eq!((min, max), (Vector2::new(0., 0.), Vector2::new(3., 3.)));
+    }
+
+    #[test]
+    fn closest_point_on_the_segment() {
+        let points = wavy();
+        let t = 0.65;
+        let target = eval(points, t);
+        let (found, p) = closest_param(points, target);
+        assert!((found - t).abs() < 1e-6 && p.distance(target) < 1e-6);
+    }
+}
@@ -0,0 +1,85 @@
+use core::ops::{Add, AddAssign, Div, Mul, Neg, Sub, SubAssign};
+
+/// Scalar the curve math is generic over. Implemented for f32 and f64 with libm, a fixed
+/// point type needs the arithmetic, the conversions and these few functions
+pub trait Float:
+    Copy + PartialOrd + Default
+    + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self> + Div<Output = Self> + Neg<Output = Self>
+    + AddAssign + SubAssign
+{
+    const ZERO: Self;
+    const ONE: Self;
+    const EPSILON: Self;
+    const PI: Self;
+
+    /// Constants in the code are written as f64 and rounded to the scalar
+    fn from_f64(x: f64) -> Self;
+    fn to_f64(self) -> f64;
+
+    fn sqrt(self) -> Self;
+    fn cbrt(self) -> Self;
+    fn cos(self) -> Self;
+    fn acos(self) -> Self;
+    fn ceil(self) -> Self;
+    fn abs(self) -> Self;
+    /// self * a + b, fused where the scalar has it
+    fn mul_add(self, a: Self, b: Self) -> Self;
+
+    fn from_usize(n: usize) -> Self {
+        Self::from_f64(n as f64)
+    }
+
+    fn min(self, other: Self) -> Self {
+        match other < self {
+            true => other,
+            false => self
+        }
+    }
+
+    fn max(self, other: Self) -> Self {
+        match other > self {
+            true => other,
+            false => self
+        }
+    }
+
+    fn clamp(self, min: Self, max: Self) -> Self {
+        self.max(min).min(max)
+    }
+}
+
+impl Float for f32 {
+    const ZERO: Self = 0.;
+    const ONE: Self = 1.;
+    const EPSILON: Self = f32::EPSILON;
+    const PI: Self = core::f32::consts::PI;
+
+    fn from_f64(x: f64) -> Self { x as f32 }
+    fn to_f64(self) -> f64 { self as f64 }
+
+    fn sqrt(self) -> Self { libm::sqrtf(self) }
+    fn cbrt(self) -> Self { libm::cbrtf(self) }
+    fn cos(self) -> Self { libm::cosf(self) }
+    fn acos(self) -> Self { libm::acosf(self) }
+    fn ceil(self) -> Self { libm::ceilf(self) }
+    fn abs(self) -> Self { libm::fabsf(self) }
+    fn mul_add(self, a: Self, b: Self) -> Self { libm::fmaf(self, a, b) }
+}
+
+impl Float for f64 {
+    const ZERO: Self = 0.;
+    const ONE: Self = 1.;
+    const EPSILON: Self = f64::EPSILON;
+    const PI: Self = core::f64::consts::PI;
+
+    fn from_f64(x: f64) -> Self { x }
+    fn to_f64(self) -> f64 { self }
+
+    fn sqrt(self) -> Self { libm::sqrt(self) }
+    fn cbrt(self) -> Self { libm::cbrt(self) }
+    fn cos(self) -> Self { libm::cos(self) }
+    fn acos(self) -> Self { libm::acos(self) }
+    fn ceil(self) -> Self { libm::ceil(self) }
+    fn abs(self) -> Self { libm::fabs(self) }
+    fn mul_add(self, a: Self, b: Self) -> Self { libm::fma(self, a, b) }
+}
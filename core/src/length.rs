@@ -0,0 +1,96 @@
+use crate::bezier::velocity;
+use crate::float::Float;
+use crate::vector::Vector2;
+
+/// Nodes and weights of the 5-point Gauss-Legendre rule on [-1, 1]
+pub const GAUSS_LEGENDRE_5: [(f64, f64); 5] = [
+    (0.0, 0.568_888_888_888_888_9),
+    (-0.538_469_310_105_683_1, 0.478_628_670_499_366_5),
+    (0.538_469_310_105_683_1, 0.478_628_670_499_366_5),
+    (-0.906_179_845_938_664, 0.236_926_885_056_189_1),
+    (0.906_179_845_938_664, 0.236_926_885_056_189_1)
+];
+
+/// Arc length between t0 and t1, integrating |B'(t)| with composite Gauss-Legendre quadrature
+pub fn arc_length_between<F: Float>(points: [Vector2<F>; 4], t0: F, t1: F) -> F {
+    const INTERVALS: usize = 8;
+    let h = (t1 - t0) / F::from_usize(INTERVALS);
+    let half = F::from_f64(0.5);
+
+    (0..INTERVALS).fold(F::ZERO, |length, i| {
+        let mid = t0 + h * (F::from_usize(i) + half);
+        let sum = GAUSS_LEGENDRE_5.iter().fold(F::ZERO, |sum, &(x, w)| {
+            sum + F::from_f64(w) * velocity(points, mid + F::from_f64(x) * h * half).length()
+        });
+        length + sum * h * half
+    })
+}
+
+/// Arc length of the whole segment
+pub fn arc_length<F: Float>(points: [Vector2<F>; 4]) -> F {
+    arc_length_between(points, F::ZERO, F::ONE)
+}
+
+/// Parameter where the arc length measured from t = 0 reaches `length`. Newton's method on
+/// s(t) - length, since s'(t) is the speed, falling back to bisection when a step leaves the bracket
+pub fn param_at_length<F: Float>(points: [Vector2<F>; 4], length: F) -> F {
+    let total = arc_length(points);
+    if length <= F::ZERO { return F::ZERO };
+    if length >= total { return F::ONE };
+
+    let (mut low, mut high) = (F::ZERO, F::ONE);
+    let mut t = length / total;
+    for _ in 0..16 {
+        let error = arc_length_between(points, F::ZERO, t) - length;
+        if error.abs() < F::from_f64(1e-3) { break };
+        if error > F::ZERO { high = t } else { low = t };
+
+        let speed = velocity(points, t).length();
+        let next = t - error / speed;
+        t = if speed > F::EPSILON && next > low && next < high { next } else { (low + high) * F::from_f64(0.5) };
+    }
+
+    t
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bezier::eval;
+
+    fn segment(points: [(f64, f64); 4]) -> [Vector2<f64>; 4] {
+        points.map(|(x, y)| Vector2::new(x, y))
+    }
+
+    #[test]
+    fn straight_segment_length() {
+        // Uneven handles change the speed, not the length
+        let points = segment([(0., 0.), (1., 0.), (2., 0.), (10., 0.)]);
+        assert!((arc_length(points) - 10.).abs() < 1e-6);
+        assert!((arc_length_between(points, 0.25, 0.75) - (eval(points, 0.75).x - eval(points, 0.25).x)).abs() < 1e-6);
+    }
+
+    #[test]
+    fn quarter_circle_length() {
+        let k = 0.552_284_749_8;
+        let points = segment([(1., 0.), (1., k), (k, 1.), (0., 1.)]);
+        assert!((arc_length(points) / core::f64::consts::FRAC_PI_2 - 1.).abs() < 1e-3);
+    }
+
+    #[test]
+    fn param_at_length_inverts_arc_length() {
+        let points = segment([(0., 0.), (20., 60.), (70., -30.), (90., 20.)]);
+        let total = arc_length(points);
+        for i in 1..10 {
+            let length = total * i as f64 / 10.;
+            let t = param_at_length(points, length);
+            assert!((arc_length_between(points, 0., t) - length).abs() < 1e-3);
+        }
+        assert_eq!(param_at_length(points, -1.), 0.);
+        assert_eq!(param_at_length(points, total + 1.), 1.);
+
+        // Evenly spaced handles move at constant speed
+        let line = segment([(0., 0.), (1., 0.), (2., 0.), (3., 0.)]);
+        assert!((param_at_length(line, 1.2) - 0.4).abs() < 1e-3);
+    }
+}
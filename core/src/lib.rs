@@ -0,0 +1,15 @@
+//! Evaluation and geometry of cubic bezier segments given as their four control points,
+//! `no_std` with libm for the float functions. Generic over the scalar through `Float`, and
+//! evaluation over the control points through `VectorSpace`, so the same code runs on 2D and
+//! 3D positions or colors. The editor's math is built on it in f32, so curves drawn there
+//! evaluate the same on a microcontroller
+#![no_std]
+
+pub mod bezier;
+pub mod float;
+pub mod length;
+pub mod roots;
+pub mod vector;
+
+pub use float::Float;
+pub use vector::{Vector2, VectorSpace};
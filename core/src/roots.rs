@@ -0,0 +1,128 @@
+use crate::float::Float;
+
+/// Up to three real roots, without allocating
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Roots<F> {
+    values: [F; 3],
+    len: usize
+}
+
+impl<F: Float> Roots<F> {
+    fn of(roots: &[F]) -> Self {
+        let mut values = [F::ZERO; 3];
+        values[..roots.len()].copy_from_slice(roots);
+        Self { values, len: roots.len() }
+    }
+
+    pub fn as_slice(&self) -> &[F] {
+        &self.values[..self.len]
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+impl<F> IntoIterator for Roots<F> {
+    type Item = F;
+    type IntoIter = core::iter::Take<core::array::IntoIter<F, 3>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.values.into_iter().take(self.len)
+    }
+}
+
+/// Real roots of c0 + c1 t + c2 t^2 + c3 t^3, falling back to lower degrees when the
+/// leading coefficients vanish. Uses the trigonometric method for three real roots. The
+/// cubic formula loses a lot to rounding, callers in f32 are better off passing f64
+pub fn cubic_roots<F: Float>(coefficients: [F; 4]) -> Roots<F> {
+    let [d, c, b, a] = coefficients;
+    let k = F::from_f64;
+    let scale = coefficients.iter().fold(F::ZERO, |m, c| m.max(c.abs()));
+    let tiny = k(1e-9) * scale.max(F::ONE);
+
+    if a.abs() < tiny {
+        if b.abs() < tiny {
+            if c.abs() < tiny { return Roots::default() };
+            return Roots::of(&[-d / c]);
+        }
+
+        let delta = c * c - k(4.) * b * d;
+        if delta < F::ZERO { return Roots::default() };
+        let root = delta.sqrt();
+        return Roots::of(&[(-c + root) / (k(2.) * b), (-c - root) / (k(2.) * b)]);
+    }
+
+    // Depressed cubic u^3 + pu + q with t = u - b/3a
+    let (b, c, d) = (b / a, c / a, d / a);
+    let p = c - b * b / k(3.);
+    let q = k(2.) * b * b * b / k(27.) - b * c / k(3.) + d;
+    let shift = -b / k(3.);
+    let (half_q, third_p) = (q / k(2.), p / k(3.));
+    let discriminant = half_q * half_q + third_p * third_p * third_p;
+
+    if discriminant > F::ZERO {
+        let root = discriminant.sqrt();
+        let u = (-half_q + root).cbrt() + (-half_q - root).cbrt();
+        Roots::of(&[u + shift])
+    } else if p.abs() < k(1e-12) {
+        Roots::of(&[shift])
+    } else {
+        let r = (-third_p).sqrt();
+        let phi = (-q / (k(2.) * r * r * r)).clamp(-F::ONE, F::ONE).acos();
+        let root = |i: f64| k(2.) * r * ((phi - k(2. * i) * F::PI) / k(3.)).cos() + shift;
+        Roots::of(&[root(0.), root(1.), root(2.)])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Every expected root is found, and nothing else
+    fn assert_roots(roots: Roots<f64>, expected: &[f64]) {
+        for &root in roots.as_slice() {
+            assert!(expected.iter().any(|e| (root - e).abs() < 1e-6), "unexpected root {}", root);
+        }
+        for e in expected {
+            assert!(roots.as_slice().iter().any(|root| (root - e).abs() < 1e-6), "missing root {}", e);
+        }
+    }
+
+    #[test]
+    fn three_real_roots() {
+        // (t - 0.25)(t - 0.5)(t - 2)
+        assert_roots(cubic_roots([-0.25, 1.625, -2.75, 1.]), &[0.25, 0.5, 2.]);
+    }
+
+    #[test]
+    fn one_real_root() {
+        // (t - 1)(t^2 + 1)
+        assert_roots(cubic_roots([-1., 1., -1., 1.]), &[1.]);
+    }
+
+    #[test]
+    fn double_root() {
+        // (t - 1)^2 (t - 2)
+        assert_roots(cubic_roots([-2., 5., -4., 1.]), &[1., 2.]);
+        // (t - 0.5)^3
+        assert_roots(cubic_roots([-0.125, 0.75, -1.5, 1.]), &[0.5]);
+    }
+
+    #[test]
+    fn vanishing_cubic_term() {
+        // (t - 1)(t - 2) with a leftover cubic term
+        assert_roots(cubic_roots([2., -3., 1., 1e-12]), &[1., 2.]);
+        assert_roots(cubic_roots([1., 0., 1., 0.]), &[]);
+    }
+
+    #[test]
+    fn vanishing_quadratic_term() {
+        assert_roots(cubic_roots([-1., 2., 1e-12, 1e-12]), &[0.5]);
+        assert!(cubic_roots([1., 0., 0., 0.]).is_empty());
+    }
+}
@@ -0,0 +1,128 @@
+use core::ops::{Add, AddAssign, Div, Mul, Neg, Sub};
+
+use glam::Vec2;
+
+use crate::float::Float;
+
+/// What a bezier can be made of: positions in any dimension, colors, single channels, any
+/// copyable value that adds, subtracts and scales by `F`. Every such type is one
+pub trait VectorSpace<F>: Copy + Add<Output = Self> + Sub<Output = Self> + Mul<F, Output = Self> {}
+
+impl<F, T: Copy + Add<Output = T> + Sub<Output = T> + Mul<F, Output = T>> VectorSpace<F> for T {}
+
+/// 2D vector over any `Float`, converts to and from glam's `Vec2` for f32
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Vector2<F> {
+    pub x: F,
+    pub y: F
+}
+
+impl<F: Float> Vector2<F> {
+    pub const fn new(x: F, y: F) -> Self {
+        Self { x, y }
+    }
+
+    pub fn zero() -> Self {
+        Self::new(F::ZERO, F::ZERO)
+    }
+
+    pub fn dot(self, other: Self) -> F {
+        self.x * other.x + self.y * other.y
+    }
+
+    /// z of the 3D cross product, positive when `other` is counterclockwise from `self`
+    pub fn perp_dot(self, other: Self) -> F {
+        self.x * other.y - self.y * other.x
+    }
+
+    pub fn length_squared(self) -> F {
+        self.dot(self)
+    }
+
+    pub fn length(self) -> F {
+        self.length_squared().sqrt()
+    }
+
+    pub fn distance_squared(self, other: Self) -> F {
+        (self - other).length_squared()
+    }
+
+    pub fn distance(self, other: Self) -> F {
+        (self - other).length()
+    }
+
+    pub fn lerp(self, other: Self, t: F) -> Self {
+        self + (other - self) * t
+    }
+
+    pub fn min(self, other: Self) -> Self {
+        Self::new(self.x.min(other.x), self.y.min(other.y))
+    }
+
+    pub fn max(self, other: Self) -> Self {
+        Self::new(self.x.max(other.x), self.y.max(other.y))
+    }
+
+    /// self * k + other on both axes, fused where the scalar has it
+    pub fn mul_add(self, k: F, other: Self) -> Self {
+        Self::new(self.x.mul_add(k, other.x), self.y.mul_add(k, other.y))
+    }
+}
+
+impl<F: Float> Add for Vector2<F> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl<F: Float> AddAssign for Vector2<F> {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+impl<F: Float> Sub for Vector2<F> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl<F: Float> Neg for Vector2<F> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::new(-self.x, -self.y)
+    }
+}
+
+impl<F: Float> Mul<F> for Vector2<F> {
+    type Output = Self;
+
+    fn mul(self, k: F) -> Self {
+        Self::new(self.x * k, self.y * k)
+    }
+}
+
+impl<F: Float> Div<F> for Vector2<F> {
+    type Output = Self;
+
+    fn div(self, k: F) -> Self {
+        Self::new(self.x / k, self.y / k)
+    }
+}
+
+impl From<Vec2> for Vector2<f32> {
+    fn from(v: Vec2) -> Self {
+        Self::new(v.x, v.y)
+    }
+}
+
+impl From<Vector2<f32>> for Vec2 {
+    fn from(v: Vector2<f32>) -> Self {
+        Vec2::new(v.x, v.y)
+    }
+}
@@ -0,0 +1,236 @@
+use macroquad::prelude::*;
+
+use crate::math::{cubic_bezier, cubic_roots, power_basis, velocity};
+use crate::point::Point;
+
+// Polynomials in t as coefficients of increasing power. f64 since the moment
+// integrands reach coordinates to the 3rd power, which f32 can't hold accurately
+type Poly = Vec<f64>;
+
+fn poly_mul(a: &[f64], b: &[f64]) -> Poly {
+    let mut out = vec![0.; a.len() + b.len() - 1];
+    for (i, x) in a.iter().enumerate() {
+        for (j, y) in b.iter().enumerate() {
+            out[i + j] += x * y;
+        }
+    }
+
+    out
+}
+
+fn poly_derivative(a: &[f64]) -> Poly {
+    a.iter().enumerate().skip(1).map(|(i, c)| i as f64 * c).collect()
+}
+
+// Exact integral over t in [0, 1]
+fn poly_integral(a: &[f64]) -> f64 {
+    a.iter().enumerate().map(|(i, c)| c / (i + 1) as f64).sum()
+}
+
+fn axis_polys(points: [Point; 4]) -> (Poly, Poly) {
+    let coefficients = power_basis(points);
+    let x = coefficients.iter().map(|c| c.x as f64).collect();
+    let y = coefficients.iter().map(|c| c.y as f64).collect();
+
+    (x, y)
+}
+
+/// Area, centroid and second moments of a closed path, from Green's theorem applied to each segment
+#[derive(Clone, Copy, Debug)]
+pub struct ShapeProperties {
+    /// Positive when the path runs clockwise on screen (counter-clockwise with y up)
+    pub signed_area: f32,
+    pub centroid: Vec2,
+    /// ∫∫ y² dA about the centroid, resistance to bending around the horizontal axis
+    pub ixx: f32,
+    /// ∫∫ x² dA about the centroid
+    pub iyy: f32,
+    /// ∫∫ xy dA about the centroid, zero when either axis is a symmetry axis
+    pub ixy: f32
+}
+
+impl ShapeProperties {
+    /// Polar moment of inertia about the centroid
+    pub fn polar_moment(&self) -> f32 {
+        self.ixx + self.iyy
+    }
+}
+
+/// Green's theorem turns the area integrals into line integrals over the boundary:
+/// A = 1/2 ∮ x dy - y dx, ∫∫ x dA = 1/2 ∮ x² dy and ∫∫ y dA = -1/2 ∮ y² dx. Each segment is
+/// a polynomial in t, so these integrals are exact. Returns None for degenerate shapes
+pub fn shape_properties(segments: &[[Point; 4]]) -> Option<ShapeProperties> {
+    let (mut area, mut mx, mut my) = (0., 0., 0.);
+
+    for &segment in segments {
+        let (x, y) = axis_polys(segment);
+        let (dx, dy) = (poly_derivative(&x), poly_derivative(&y));
+
+        area += 0.5 * (poly_integral(&poly_mul(&x, &dy)) - poly_integral(&poly_mul(&y, &dx)));
+        mx += 0.5 * poly_integral(&poly_mul(&poly_mul(&x, &x), &dy));
+        my -= 0.5 * poly_integral(&poly_mul(&poly_mul(&y, &y), &dx));
+    }
+
+    if area.abs() < 1e-9 { return None };
+    let (cx, cy) = (mx / area, my / area);
+
+    // Second moments in a frame centered on the centroid: ∫∫ x² dA = 1/3 ∮ x³ dy,
+    // ∫∫ y² dA = -1/3 ∮ y³ dx and ∫∫ xy dA = 1/2 ∮ x² y dy
+    let (mut ixx, mut iyy, mut ixy) = (0., 0., 0.);
+    for &segment in segments {
+        let (mut x, mut y) = axis_polys(segment);
+        x[0] -= cx;
+        y[0] -= cy;
+        let (dx, dy) = (poly_derivative(&x), poly_derivative(&y));
+
+        let (xx, yy) = (poly_mul(&x, &x), poly_mul(&y, &y));
+        iyy += poly_integral(&poly_mul(&poly_mul(&xx, &x), &dy)) / 3.;
+        ixx -= poly_integral(&poly_mul(&poly_mul(&yy, &y), &dx)) / 3.;
+        ixy += 0.5 * poly_integral(&poly_mul(&poly_mul(&xx, &y), &dy));
+    }
+
+    // The moments carry the orientation's sign like the area does, report them as positive
+    let orientation = area.signum();
+
+    Some(ShapeProperties {
+        signed_area: area as f32,
+        centroid: vec2(cx as f32, cy as f32),
+        ixx: (ixx * orientation) as f32,
+        iyy: (iyy * orientation) as f32,
+        ixy: (ixy * orientation) as f32
+    })
+}
+
+/// How many times the closed path winds around `p`, counting crossings of the
+/// horizontal ray going right from `p` signed by the direction the path crosses it
+pub fn winding_number(segments: &[[Point; 4]], p: Vec2) -> i32 {
+    let mut winding = 0;
+
+    for &segment in segments {
+        let [pmin, pmax] = segment.iter().fold([Vec2::splat(f32::MAX), Vec2::splat(f32::MIN)], |[lo, hi], c| {
+            [lo.min(c.pos), hi.max(c.pos)]
+        });
+        if p.y < pmin.y || p.y > pmax.y || p.x > pmax.x { continue };
+
+        let coefficients = power_basis(segment);
+        let ys = [coefficients[0].y - p.y, coefficients[1].y, coefficients[2].y, coefficients[3].y];
+
+        // Downward crossings count on [0, 1) and upward ones on (0, 1], so a crossing through
+        // a shared anchor is counted once and touching an anchor from one side isn't counted
+        for t in cubic_roots(ys) {
+            let dy = velocity(segment, t).y;
+            let counted = match dy {
+                dy if dy > 0. => (-1e-6..1. - 1e-6).contains(&t),
+                dy if dy < 0. => t > 1e-6 && t <= 1. + 1e-6,
+                _ => false
+            };
+
+            if counted && cubic_bezier(t, &segment).x > p.x {
+                winding += if dy > 0. { 1 } else { -1 };
+            }
+        }
+    }
+
+    winding
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Straight cubics through the corners, closed back to the first
+    fn polygon(corners: &[Vec2]) -> Vec<[Point; 4]> {
+        (0..corners.len()).map(|k| {
+            let (a, b) = (corners[k], corners[(k + 1) % corners.len()]);
+            [a, a.lerp(b, 1. / 3.), a.lerp(b, 2. / 3.), b].map(|pos| Point::new(pos, WHITE))
+        }).collect()
+    }
+
+    fn reversed(segments: &[[Point; 4]]) -> Vec<[Point; 4]> {
+        segments.iter().rev().map(|&[a, b, c, d]| [d, c, b, a]).collect()
+    }
+
+    #[test]
+    fn inside_and_outside() {
+        let square = polygon(&[vec2(0., 0.), vec2(10., 0.), vec2(10., 10.), vec2(0., 10.)]);
+        let inside = winding_number(&square, vec2(3., 4.));
+        assert_eq!(inside.abs(), 1);
+        assert_eq!(winding_number(&reversed(&square), vec2(3., 4.)), -inside);
+
+        for p in [vec2(-3., 4.), vec2(13., 4.), vec2(3., -4.), vec2(3., 14.)] {
+            assert_eq!(winding_number(&square, p), 0);
+        }
+    }
+
+    #[test]
+    fn rays_through_anchors() {
+        // The ray from the middle leaves through the right corner, shared by two segments
+        let diamond = polygon(&[vec2(0., -10.), vec2(10., 0.), vec2(0., 10.), vec2(-10., 0.)]);
+        assert_eq!(winding_number(&diamond, vec2(0., 0.)).abs(), 1);
+        // From the left it only touches the left and right corners
+        assert_eq!(winding_number(&diamond, vec2(-20., 0.)), 0);
+
+        // Level with the square's top edge, which runs along the ray
+        let square = polygon(&[vec2(0., 0.), vec2(10., 0.), vec2(10., 10.), vec2(0., 10.)]);
+        assert_eq!(winding_number(&square, vec2(-5., 0.)), 0);
+    }
+
+    #[test]
+    fn on_the_boundary() {
+        // Points on the path may count as either side, but never twice
+        let square = polygon(&[vec2(0., 0.), vec2(10., 0.), vec2(10., 10.), vec2(0., 10.)]);
+        for p in [vec2(0., 5.), vec2(10., 5.), vec2(5., 0.), vec2(5., 10.), vec2(0., 0.), vec2(10., 10.)] {
+            assert!(winding_number(&square, p).abs() <= 1);
+        }
+    }
+
+    fn assert_close(a: f32, b: f32) {
+        assert!((a - b).abs() <= 1e-3 * b.abs().max(1.), "{} != {}", a, b);
+    }
+
+    // Same numbers either way around, except for the sign of the area
+    fn assert_properties(segments: &[[Point; 4]], area: f32, centroid: Vec2, [ixx, iyy, ixy]: [f32; 3]) {
+        for (path, sign) in [(segments.to_vec(), 1.), (reversed(segments), -1.)] {
+            let properties = shape_properties(&path).unwrap();
+            assert_close(properties.signed_area, sign * area);
+            assert!(properties.centroid.distance(centroid) < 1e-3);
+            assert_close(properties.ixx, ixx);
+            assert_close(properties.iyy, iyy);
+            assert_close(properties.ixy, ixy);
+        }
+    }
+
+    #[test]
+    fn rectangle_properties() {
+        // Clockwise on screen, w h³ / 12 about the horizontal axis
+        let rectangle = polygon(&[vec2(5., 5.), vec2(25., 5.), vec2(25., 15.), vec2(5., 15.)]);
+        assert_properties(&rectangle, 200., vec2(15., 10.), [20. * 1000. / 12., 10. * 8000. / 12., 0.]);
+    }
+
+    #[test]
+    fn right_triangle_properties() {
+        // Legs a = 30 and b = 60, the product moment is -a²b²/72
+        let triangle = polygon(&[vec2(0., 0.), vec2(30., 0.), vec2(0., 60.)]);
+        assert_properties(&triangle, 900., vec2(10., 20.), [30. * 216000. / 36., 60. * 27000. / 36., -45000.]);
+    }
+
+    #[test]
+    fn circle_properties() {
+        // Four quarter arcs, the bezier circle is within 0.03% of the radius
+        let (center, r) = (vec2(40., -20.), 10.);
+        let k = 0.552_284_7 * r;
+        let quarter = std::f32::consts::FRAC_PI_2;
+        let circle: Vec<[Point; 4]> = (0..4).map(|i| {
+            let (a, b) = (Vec2::from_angle(i as f32 * quarter), Vec2::from_angle((i + 1) as f32 * quarter));
+            [center + r * a, center + r * a + k * a.perp(), center + r * b - k * b.perp(), center + r * b].map(|pos| Point::new(pos, WHITE))
+        }).collect();
+
+        let properties = shape_properties(&circle).unwrap();
+        let pi = std::f32::consts::PI;
+        assert!((properties.signed_area / (pi * r * r) - 1.).abs() < 1e-3);
+        assert!(properties.centroid.distance(center) < 1e-3);
+        assert!((properties.ixx / (pi * r.powi(4) / 4.) - 1.).abs() < 2e-3);
+        assert!((properties.iyy - properties.ixx).abs() < 1e-2 && properties.ixy.abs() < 1e-2);
+        assert!(shape_properties(&reversed(&circle)).unwrap().signed_area < 0.);
+    }
+}
@@ -0,0 +1,97 @@
+use macroquad::prelude::*;
+
+use crate::curve::Curve;
+use crate::math::arc_length;
+
+/// Bone counts shift + the bones key cycles through
+pub const BONE_COUNTS: [usize; 5] = [2, 3, 5, 8, 12];
+// The curve is followed within this many world units while walking it
+const WALK_TOLERANCE: f32 = 0.25;
+const BISECTION_STEPS: usize = 24;
+
+// Where the line from `a` to `b` first gets `length` away from `center`, as a fraction of the
+// way from `a`. `a` is closer than `length`
+fn leave_circle(center: Vec2, length: f32, a: Vec2, b: Vec2) -> Option<f32> {
+    let (d, f) = (b - a, a - center);
+    let (qa, qb, qc) = (d.dot(d), 2. * f.dot(d), f.dot(f) - length * length);
+    if qa <= 0. { return None };
+    let discriminant = qb * qb - 4. * qa * qc;
+    if discriminant < 0. { return None };
+    let t = (-qb + discriminant.sqrt()) / (2. * qa);
+    (0. ..=1.).contains(&t).then_some(t)
+}
+
+/// Joints of a chain of `count` bones posed along `curve`, all of the same length and with
+/// every joint on the curve, starting at its start. Each joint is the next point a bone's
+/// length away walking the curve by arc length, and the bones are as long as they can be
+/// for the whole chain to fit. Empty without a complete segment
+pub fn pose_chain(curve: &Curve, count: usize) -> Vec<Vec2> {
+    let path: Vec<Vec2> = curve.flatten(WALK_TOLERANCE).iter().map(|p| p.pos).collect();
+    let total: f32 = curve.segments().into_iter().map(arc_length).sum();
+    if path.len() < 2 || count == 0 || total <= 0. { return Vec::new() };
+
+    // Chords are shorter than the arcs they cut, so the arc length share may not fit. The
+    // longest bones that still do are found by bisection
+    let fits = |length: f32| walk(&path, count, length).len() == count + 1;
+    let (mut short, mut long) = (0., total / count as f32);
+    if fits(long) { return walk(&path, count, long) };
+    for _ in 0..BISECTION_STEPS {
+        let middle = (short + long) / 2.;
+        match fits(middle) {
+            true => short = middle,
+            false => long = middle
+        }
+    }
+
+    walk(&path, count, short)
+}
+
+// Joints `length` apart along the polyline, as many as fit up to `count` bones
+fn walk(path: &[Vec2], count: usize, length: f32) -> Vec<Vec2> {
+    let mut joints = vec![path[0]];
+    // The joint lies on the line from path[line] to path[line + 1]
+    let mut line = 0;
+    let mut from = path[0];
+    while joints.len() <= count {
+        let joint = joints[joints.len() - 1];
+        let mut found = None;
+        while line + 1 < path.len() {
+            if let Some(t) = leave_circle(joint, length, from, path[line + 1]) {
+                found = Some(from.lerp(path[line + 1], t));
+                break;
+            }
+            line += 1;
+            from = path[line];
+        }
+        let Some(next) = found else { break };
+        from = next;
+        joints.push(next);
+    }
+
+    joints
+}
+
+/// Every bone as a diamond from one joint to the next and the joints as circles, with the
+/// world camera set
+pub fn draw_chain(joints: &[Vec2], line_width: f32, color: Color) {
+    for pair in joints.windows(2) {
+        let (head, tail) = (pair[0], pair[1]);
+        let along = tail - head;
+        // Widest a fifth of the way in, like the bones of 3D packages
+        let (waist, side) = (head + along * 0.2, along.perp() * 0.1);
+        let outline = [head, waist + side, tail, waist - side];
+        draw_triangle(head, waist + side, waist - side, Color { a: 0.35, ..color });
+        draw_triangle(tail, waist + side, waist - side, Color { a: 0.35, ..color });
+        for i in 0..4 {
+            let (a, b) = (outline[i], outline[(i + 1) % 4]);
+            draw_line(a.x, a.y, b.x, b.y, line_width, color);
+        }
+    }
+    for (i, joint) in joints.iter().enumerate() {
+        let radius = match i {
+            0 => 4. * line_width,
+            _ => 2.5 * line_width
+        };
+        draw_circle_lines(joint.x, joint.y, radius, line_width, color);
+    }
+}
@@ -0,0 +1,219 @@
+use macroquad::prelude::*;
+
+use crate::analysis::{shape_properties, winding_number};
+use crate::curve::Curve;
+use crate::math::*;
+use crate::point::Point;
+
+const INTERSECTION_TOLERANCE: f32 = 0.01;
+// How far apart two piece endpoints can be and still be chained together
+const CHAIN_TOLERANCE: f32 = 0.5;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BooleanOp {
+    Union,
+    Intersection,
+    /// First operand minus the second
+    Difference
+}
+
+fn reversed(segment: [Point; 4]) -> [Point; 4] {
+    let [a, b, c, d] = segment;
+    [d, c, b, a]
+}
+
+/// Segments of a closed curve oriented so its signed area is positive
+fn oriented_segments(curve: &Curve) -> Vec<[Point; 4]> {
+    let segments = curve.segments();
+    match shape_properties(&segments) {
+        Some(shape) if shape.signed_area < 0. => segments.into_iter().rev().map(reversed).collect(),
+        _ => segments
+    }
+}
+
+/// Cut each shape wherever it crosses the other one
+fn cut(a: &[[Point; 4]], b: &[[Point; 4]]) -> (Vec<[Point; 4]>, Vec<[Point; 4]>) {
+    let mut a_params = vec![Vec::new(); a.len()];
+    let mut b_params = vec![Vec::new(); b.len()];
+
+    for (i, sa) in a.iter().enumerate() {
+        for (j, sb) in b.iter().enumerate() {
+            for (ta, tb) in intersections(sa, sb, INTERSECTION_TOLERANCE) {
+                a_params[i].push(ta);
+                b_params[j].push(tb);
+            }
+        }
+    }
+
+    let pieces = |segments: &[[Point; 4]], params: &mut [Vec<f32>]| -> Vec<[Point; 4]> {
+        segments.iter().zip(params.iter_mut()).flat_map(|(&segment, ts)| {
+            // Crossings right at an anchor, or bunched up where the curves touch, would leave
+            // degenerate pieces behind
+            let at = |t: f32| cubic_bezier(t, &segment);
+            ts.retain(|&t| at(t).distance(segment[0].pos) >= CHAIN_TOLERANCE && at(t).distance(segment[3].pos) >= CHAIN_TOLERANCE);
+            ts.sort_by(f32::total_cmp);
+            ts.dedup_by(|a, b| at(*a).distance(at(*b)) < CHAIN_TOLERANCE);
+            split_at_params(segment, ts)
+        }).collect()
+    };
+
+    (pieces(a, &mut a_params), pieces(b, &mut b_params))
+}
+
+/// Chain directed pieces into closed curves by matching end points to start points. Chains
+/// that can't be closed or enclose nothing, which only happens on degenerate input, are dropped
+fn chain(mut pieces: Vec<[Point; 4]>) -> Vec<Curve> {
+    let mut curves = Vec::new();
+
+    while let Some(first) = pieces.pop() {
+        let mut control = first.to_vec();
+
+        let closed = loop {
+            let end = control[control.len() - 1].pos;
+            if control.len() > 4 && end.distance(control[0].pos) < CHAIN_TOLERANCE {
+                let last = control.len() - 1;
+                control[last].pos = control[0].pos;
+                break true;
+            }
+
+            let next = pieces.iter().position(|p| p[0].pos.distance(end) < CHAIN_TOLERANCE);
+            match next {
+                Some(id) => control.extend_from_slice(&pieces.swap_remove(id)[1..]),
+                None => break false
+            }
+        };
+
+        let segments: Vec<[Point; 4]> = control.windows(4).step_by(3).map(|w| [w[0], w[1], w[2], w[3]]).collect();
+        let area = shape_properties(&segments).map_or(0., |shape| shape.signed_area.abs());
+        if closed && area >= CHAIN_TOLERANCE * CHAIN_TOLERANCE {
+            curves.push(Curve { control, closed: true, modified: true, ..Default::default() });
+        }
+    }
+
+    curves
+}
+
+/// Boolean operation between two closed curves. Both get cut at their crossings, then each
+/// piece is kept or dropped depending on whether its midpoint is inside the other shape
+/// (nonzero winding), and the kept pieces are chained back into closed curves
+pub fn boolean(a: &Curve, b: &Curve, op: BooleanOp) -> Vec<Curve> {
+    if !a.closed || !b.closed { return Vec::new() };
+
+    let (a_segments, b_segments) = (oriented_segments(a), oriented_segments(b));
+    let (a_pieces, b_pieces) = cut(&a_segments, &b_segments);
+
+    let inside = |piece: &[Point; 4], other: &[[Point; 4]]| {
+        winding_number(other, cubic_bezier(0.5, piece)) != 0
+    };
+
+    let mut kept = Vec::new();
+    for piece in a_pieces {
+        let keep = match op {
+            BooleanOp::Union | BooleanOp::Difference => !inside(&piece, &b_segments),
+            BooleanOp::Intersection => inside(&piece, &b_segments)
+        };
+        if keep { kept.push(piece) };
+    }
+
+    for piece in b_pieces {
+        match op {
+            BooleanOp::Union if !inside(&piece, &a_segments) => kept.push(piece),
+            BooleanOp::Intersection if inside(&piece, &a_segments) => kept.push(piece),
+            // Whatever of b is inside a becomes a hole in a, so it runs backwards
+            BooleanOp::Difference if inside(&piece, &a_segments) => kept.push(reversed(piece)),
+            _ => ()
+        }
+    }
+
+    chain(kept)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Four quarter arcs, close to a circle of `radius` hundred pixels
+    fn circle(center: Vec2, radius: f32) -> Curve {
+        let (center, radius) = (center * 100., radius * 100.);
+        let k = 0.5523 * radius;
+        let mut control = Vec::new();
+        for quarter in 0..4 {
+            let angle = std::f32::consts::FRAC_PI_2 * quarter as f32;
+            let (from, to) = (Vec2::from_angle(angle), Vec2::from_angle(angle + std::f32::consts::FRAC_PI_2));
+            if quarter == 0 { control.push(center + from * radius) };
+            control.extend([center + from * radius + from.perp() * k, center + to * radius - to.perp() * k, center + to * radius]);
+        }
+        let control = control.into_iter().map(|pos| Point::new(pos, WHITE)).collect();
+        Curve { control, closed: true, ..Curve::default() }
+    }
+
+    // Total area of the results in hundreds of pixels squared, holes counting against it
+    fn area(curves: &[Curve]) -> f32 {
+        curves.iter().map(|curve| shape_properties(&curve.segments()).unwrap().signed_area).sum::<f32>() / 1e4
+    }
+
+    fn assert_near(value: f32, expected: f32) {
+        assert!((value - expected).abs() < 0.01 * expected.abs().max(1.), "{} isn't close to {}", value, expected);
+    }
+
+    #[test]
+    fn overlapping_circles() {
+        let (a, b) = (circle(Vec2::ZERO, 1.), circle(vec2(1., 0.), 1.));
+        let lens = 2. * 0.5f32.acos() - 0.75f32.sqrt();
+        let union = boolean(&a, &b, BooleanOp::Union);
+        let intersection = boolean(&a, &b, BooleanOp::Intersection);
+        let difference = boolean(&a, &b, BooleanOp::Difference);
+
+        assert_eq!((union.len(), intersection.len(), difference.len()), (1, 1, 1));
+        assert_near(area(&union), 2. * std::f32::consts::PI - lens);
+        assert_near(area(&intersection), lens);
+        assert_near(area(&difference), std::f32::consts::PI - lens);
+    }
+
+    #[test]
+    fn nested_circles() {
+        let (outer, inner) = (circle(Vec2::ZERO, 2.), circle(vec2(0.5, 0.), 1.));
+        let pi = std::f32::consts::PI;
+
+        assert_near(area(&boolean(&outer, &inner, BooleanOp::Union)), 4. * pi);
+        assert_near(area(&boolean(&outer, &inner, BooleanOp::Intersection)), pi);
+        let ring = boolean(&outer, &inner, BooleanOp::Difference);
+        assert_eq!(ring.len(), 2);
+        assert_near(area(&ring), 3. * pi);
+        assert!(boolean(&inner, &outer, BooleanOp::Difference).is_empty());
+    }
+
+    #[test]
+    fn disjoint_circles() {
+        let (a, b) = (circle(Vec2::ZERO, 1.), circle(vec2(5., 0.), 1.));
+        assert_eq!(boolean(&a, &b, BooleanOp::Union).len(), 2);
+        assert!(boolean(&a, &b, BooleanOp::Intersection).is_empty());
+        let difference = boolean(&a, &b, BooleanOp::Difference);
+        assert_eq!(difference.len(), 1);
+        assert_near(area(&difference), std::f32::consts::PI);
+    }
+
+    #[test]
+    fn touching_circles() {
+        let (a, b) = (circle(Vec2::ZERO, 1.), circle(vec2(2., 0.), 1.));
+        let pi = std::f32::consts::PI;
+        let union = boolean(&a, &b, BooleanOp::Union);
+        assert!(union.iter().all(|curve| curve.control[0].pos == curve.control[curve.control.len() - 1].pos));
+        assert_near(area(&union), 2. * pi);
+        assert_near(area(&boolean(&a, &b, BooleanOp::Intersection)), 0.);
+        assert_near(area(&boolean(&a, &b, BooleanOp::Difference)), pi);
+    }
+
+    #[test]
+    fn open_curves_have_no_booleans() {
+        let mut a = circle(Vec2::ZERO, 1.);
+        a.closed = false;
+        assert!(boolean(&a, &circle(vec2(1., 0.), 1.), BooleanOp::Union).is_empty());
+    }
+
+    #[test]
+    fn unclosable_chains_are_dropped() {
+        let piece = [vec2(0., 0.), vec2(1., 1.), vec2(2., 1.), vec2(3., 0.)].map(|pos| Point::new(pos, WHITE));
+        assert!(chain(vec![piece]).is_empty());
+    }
+}
@@ -0,0 +1,328 @@
+use macroquad::prelude::*;
+
+use crate::point::Point;
+
+/// Non-uniform B-spline of any degree. `knots` has `control.len() + degree + 1`
+/// non-decreasing values and the curve is defined for u in `domain()`
+#[derive(Clone)]
+pub struct BSpline {
+    pub degree: usize,
+    pub control: Vec<Point>,
+    pub knots: Vec<f32>
+}
+
+impl BSpline {
+    /// None when the knot count doesn't match or the knots decrease somewhere
+    pub fn new(degree: usize, control: Vec<Point>, knots: Vec<f32>) -> Option<Self> {
+        let valid = control.len() > degree
+            && knots.len() == control.len() + degree + 1
+            && knots.windows(2).all(|k| k[0] <= k[1]);
+
+        valid.then_some(Self { degree, control, knots })
+    }
+
+    /// Uniform knots on [0, 1] repeated degree + 1 times at each end, so the curve
+    /// starts at the first control point and ends at the last one
+    pub fn clamped_uniform(degree: usize, control: Vec<Point>) -> Option<Self> {
+        if control.len() <= degree { return None };
+
+        let spans = control.len() - degree;
+        let mut knots = vec![0.; degree];
+        knots.extend((0..=spans).map(|i| i as f32 / spans as f32));
+        knots.extend(std::iter::repeat_n(1., degree));
+
+        Self::new(degree, control, knots)
+    }
+
+    /// Evenly spaced knots on [0, 1] without end repetition, the curve doesn't touch the
+    /// first and last control points
+    pub fn unclamped_uniform(degree: usize, control: Vec<Point>) -> Option<Self> {
+        let count = control.len() + degree + 1;
+        let knots = (0..count).map(|i| i as f32 / (count - 1) as f32).collect();
+
+        Self::new(degree, control, knots)
+    }
+
+    /// Whether both ends have their knot repeated degree + 1 times
+    pub fn is_clamped(&self) -> bool {
+        let p = self.degree;
+        let (first, last) = (self.knots[0], self.knots[self.knots.len() - 1]);
+
+        self.knots[..=p].iter().all(|&k| k == first) && self.knots[self.knots.len() - p - 1..].iter().all(|&k| k == last)
+    }
+
+    pub fn domain(&self) -> (f32, f32) {
+        (self.knots[self.degree], self.knots[self.control.len()])
+    }
+
+    /// Index k of the knot span [knots[k], knots[k + 1]) holding u, clamped to the domain
+    pub fn span(&self, u: f32) -> usize {
+        let (p, n) = (self.degree, self.control.len());
+        if u >= self.knots[n] { return n - 1 };
+
+        let mut k = p;
+        while k < n - 1 && self.knots[k + 1] <= u {
+            k += 1;
+        }
+
+        k
+    }
+
+    /// Evaluate C(u) with De Boor's algorithm, which is De Casteljau's repeated lerps
+    /// generalized to uneven knots
+    pub fn eval(&self, u: f32) -> Point {
+        let p = self.degree;
+        let (start, end) = self.domain();
+        let u = u.clamp(start, end);
+        let k = self.span(u);
+
+        let mut d: Vec<Point> = self.control[k - p..=k].to_vec();
+        for r in 1..=p {
+            for j in (r..=p).rev() {
+                let i = j + k - p;
+                let denominator = self.knots[i + p + 1 - r] - self.knots[i];
+                let alpha = if denominator > 0. { (u - self.knots[i]) / denominator } else { 0. };
+                d[j] = d[j - 1].lerp(&d[j], alpha);
+            }
+        }
+
+        d[p]
+    }
+
+    /// Boehm's algorithm - insert u once into the knot vector, adding one control point
+    /// while keeping the curve's shape exactly the same. Returns false for u outside the
+    /// domain or on the first or last knot, where clamped ends would get p + 2 repeats
+    pub fn insert_knot(&mut self, u: f32) -> bool {
+        let p = self.degree;
+        let (start, end) = self.domain();
+        let (first, last) = (self.knots[0], self.knots[self.knots.len() - 1]);
+        if !(start..=end).contains(&u) || u <= first || u >= last { return false };
+        let k = self.span(u);
+
+        let mut control = Vec::with_capacity(self.control.len() + 1);
+        control.extend_from_slice(&self.control[..=k - p]);
+        for i in k - p + 1..=k {
+            let alpha = (u - self.knots[i]) / (self.knots[i + p] - self.knots[i]);
+            control.push(self.control[i - 1].lerp(&self.control[i], alpha));
+        }
+        control.extend_from_slice(&self.control[k..]);
+
+        self.control = control;
+        self.knots.insert(k + 1, u);
+
+        true
+    }
+
+    fn multiplicity(&self, u: f32) -> usize {
+        self.knots.iter().filter(|&&k| k == u).count()
+    }
+
+    /// Split a cubic B-spline into bezier segments by inserting every knot in the domain
+    /// until it has multiplicity 3, after which each non-empty span's 4 control points
+    /// are exactly that span's bezier control points
+    pub fn to_bezier(&self) -> Vec<[Point; 4]> {
+        if self.degree != 3 { return Vec::new() };
+        let p = self.degree;
+
+        let mut spline = self.clone();
+        let (start, end) = self.domain();
+        let mut values: Vec<f32> = self.knots.iter().copied().filter(|k| (start..=end).contains(k)).collect();
+        values.dedup();
+
+        for u in values {
+            while spline.multiplicity(u) < p && spline.insert_knot(u) {}
+        }
+
+        (p..spline.control.len())
+            .filter(|&k| spline.knots[k] < spline.knots[k + 1])
+            .map(|k| [spline.control[k - 3], spline.control[k - 2], spline.control[k - 1], spline.control[k]])
+            .collect()
+    }
+
+    /// Try removing the knot at index r once (the NURBS book's RemoveCurveKnot), keeping
+    /// the change only if the curve moves less than `tolerance`
+    fn remove_knot(&mut self, r: usize, tolerance: f32) -> bool {
+        let p = self.degree;
+        let u = self.knots[r];
+        let s = self.multiplicity(u);
+        if r < p + 1 || r + 1 > self.control.len() { return false };
+
+        // a * wa + b * wb, for both position and color
+        let combine = |a: Point, wa: f32, b: Point, wb: f32| Point {
+            pos: a.pos * wa + b.pos * wb,
+            color: Color::from_vec(a.color.to_vec() * wa + b.color.to_vec() * wb),
+            locked: false
+        };
+
+        let (first, last) = (r - p, r - s);
+        let off = first - 1;
+        let mut temp = vec![self.control[off]; last + 2 - off];
+        temp[last + 1 - off] = self.control[last + 1];
+
+        let (mut i, mut j) = (first, last);
+        let (mut ii, mut jj) = (1, last - off);
+        while j > i {
+            let alpha_i = (u - self.knots[i]) / (self.knots[i + p + 1] - self.knots[i]);
+            let alpha_j = (u - self.knots[j]) / (self.knots[j + p + 1] - self.knots[j]);
+            temp[ii] = combine(self.control[i], 1. / alpha_i, temp[ii - 1], -(1. - alpha_i) / alpha_i);
+            temp[jj] = combine(self.control[j], 1. / (1. - alpha_j), temp[jj + 1], -alpha_j / (1. - alpha_j));
+            i += 1;
+            ii += 1;
+            j -= 1;
+            jj -= 1;
+        }
+
+        let error = if j < i {
+            temp[ii - 1].pos.distance(temp[jj + 1].pos)
+        } else {
+            let alpha_i = (u - self.knots[i]) / (self.knots[i + p + 1] - self.knots[i]);
+            let rebuilt = combine(temp[ii + 1], alpha_i, temp[ii - 1], 1. - alpha_i);
+            self.control[i].pos.distance(rebuilt.pos)
+        };
+        if error > tolerance { return false };
+
+        let (mut i, mut j) = (first, last);
+        while j > i {
+            self.control[i] = temp[i - off];
+            self.control[j] = temp[j - off];
+            i += 1;
+            j -= 1;
+        }
+
+        self.knots.remove(r);
+        self.control.remove((2 * r - s - p) / 2);
+
+        true
+    }
+
+    /// Merge a chain of cubic beziers into one B-spline. The chain is first written exactly
+    /// with triple interior knots (only C0 at the joins), then each interior knot is removed
+    /// for as long as the shape moves less than `tolerance`, which happens at C1/C2 joins
+    pub fn from_bezier(segments: &[[Point; 4]], tolerance: f32) -> Option<Self> {
+        let count = segments.len();
+        if count == 0 { return None };
+
+        let mut control = segments[0].to_vec();
+        for segment in &segments[1..] {
+            control.extend_from_slice(&segment[1..]);
+        }
+
+        let mut knots = vec![0.; 4];
+        for i in 1..count {
+            knots.extend([i as f32 / count as f32; 3]);
+        }
+        knots.extend([1.; 4]);
+
+        let mut spline = Self::new(3, control, knots)?;
+        for i in 1..count {
+            let u = i as f32 / count as f32;
+            while let Some(r) = spline.knots.iter().rposition(|&k| k == u) {
+                if !spline.remove_knot(r, tolerance) { break };
+            }
+        }
+
+        Some(spline)
+    }
+
+    /// Parameter closest to `target` and its distance, by densely sampling the domain
+    pub fn closest_param(&self, target: Vec2) -> (f32, f32) {
+        let (start, end) = self.domain();
+        let samples = 200 * (self.control.len() - self.degree);
+
+        (0..=samples)
+            .map(|i| start + (end - start) * i as f32 / samples as f32)
+            .map(|u| (u, self.eval(u).pos.distance(target)))
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .unwrap_or((start, f32::MAX))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spline(knots: Vec<f32>) -> BSpline {
+        let positions = [vec2(0., 0.), vec2(10., 30.), vec2(30., 40.), vec2(50., -10.), vec2(70., 20.), vec2(90., 0.)];
+        let control = positions.iter().map(|&pos| Point::new(pos, WHITE)).collect();
+        BSpline::new(3, control, knots).unwrap()
+    }
+
+    fn clamped() -> BSpline {
+        spline(vec![0., 0., 0., 0., 0.3, 0.6, 1., 1., 1., 1.])
+    }
+
+    fn assert_same_curve(a: &BSpline, b: &BSpline) {
+        for i in 0..=100 {
+            let u = i as f32 / 100.;
+            assert!(a.eval(u).pos.distance(b.eval(u).pos) < 1e-3, "curves differ at {}", u);
+        }
+    }
+
+    #[test]
+    fn insertion_keeps_the_shape() {
+        for u in [0.1, 0.3, 0.45, 0.9] {
+            let mut inserted = clamped();
+            assert!(inserted.insert_knot(u));
+            assert_eq!(inserted.control.len(), 7);
+            assert_eq!(inserted.knots.len(), 11);
+            assert_same_curve(&inserted, &clamped());
+        }
+    }
+
+    #[test]
+    fn insertion_at_or_past_the_ends_is_rejected() {
+        for u in [-0.5, 0., 1., 1.5] {
+            let mut spline = clamped();
+            assert!(!spline.insert_knot(u));
+            assert_eq!(spline.knots, clamped().knots);
+        }
+
+        // Unclamped, the domain ends are inside the knots and can be inserted on
+        let mut unclamped = BSpline::unclamped_uniform(3, clamped().control).unwrap();
+        let (start, end) = unclamped.domain();
+        assert!(unclamped.insert_knot(start) && unclamped.insert_knot(end));
+        assert!(!unclamped.insert_knot(0.) && !unclamped.insert_knot(1.));
+    }
+
+    fn assert_same_control(a: &BSpline, b: &BSpline) {
+        assert_eq!(a.knots, b.knots);
+        assert_eq!(a.control.len(), b.control.len());
+        for (p, q) in a.control.iter().zip(&b.control) {
+            assert!(p.pos.distance(q.pos) < 1e-3);
+        }
+    }
+
+    #[test]
+    fn removal_undoes_insertion() {
+        let mut spline = clamped();
+        assert!(spline.insert_knot(0.45));
+        let r = spline.knots.iter().position(|&k| k == 0.45).unwrap();
+        assert!(spline.remove_knot(r, 1e-3));
+        assert_same_control(&spline, &clamped());
+    }
+
+    #[test]
+    fn knots_that_shape_the_curve_stay() {
+        // 0.3 is a C2 join of a general spline, removing it would move the curve
+        let mut spline = clamped();
+        assert!(!spline.remove_knot(4, 1e-3));
+        // Knots of the clamped ends can't be removed at all
+        assert!(!spline.remove_knot(0, f32::MAX) && !spline.remove_knot(9, f32::MAX));
+        assert_same_control(&spline, &clamped());
+    }
+
+    #[test]
+    fn bezier_round_trip() {
+        // from_bezier spaces the joins evenly, so the knots have to be uniform to match
+        let uniform = BSpline::clamped_uniform(3, clamped().control).unwrap();
+        let merged = BSpline::from_bezier(&uniform.to_bezier(), 1e-3).unwrap();
+        assert_same_control(&merged, &uniform);
+
+        // A corner between two segments keeps its triple knot
+        let segments = uniform.to_bezier();
+        let mut corner = segments[..2].to_vec();
+        corner[1][1].pos += vec2(0., 20.);
+        let merged = BSpline::from_bezier(&corner, 1e-3).unwrap();
+        assert_eq!(merged.knots.iter().filter(|&&k| k == 0.5).count(), 3);
+    }
+}
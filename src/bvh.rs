@@ -0,0 +1,349 @@
+use std::collections::HashMap;
+
+use macroquad::prelude::*;
+
+use crate::curve::Curve;
+use crate::math::*;
+use crate::point::Point;
+
+const LEAF_SIZE: usize = 4;
+// Crossings within this many intersection tolerances of the anchor two segments of a curve
+// share are where they meet
+const SHARED_ANCHOR_TOLERANCES: f32 = 10.;
+
+/// Which segment of which curve a query result refers to
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct SegmentRef {
+    pub curve: usize,
+    pub segment: usize
+}
+
+/// Point on a segment returned by the closest-point and picking queries
+#[derive(Clone, Copy, Debug)]
+pub struct SegmentHit {
+    pub segment: SegmentRef,
+    pub t: f32,
+    pub pos: Vec2,
+    pub distance: f32
+}
+
+/// Where a ray crosses a segment. `distance` is how far along the ray in multiples of its
+/// direction, so the crossing is at origin + distance * dir, and `t` the segment's parameter
+#[derive(Clone, Copy, Debug)]
+pub struct RayHit {
+    pub distance: f32,
+    pub segment: SegmentRef,
+    pub t: f32,
+    pub pos: Vec2
+}
+
+enum Node {
+    Leaf { bounds: [Vec2; 2], first: usize, count: usize },
+    Branch { bounds: [Vec2; 2], left: usize, right: usize }
+}
+
+impl Node {
+    fn bounds(&self) -> [Vec2; 2] {
+        match self {
+            Node::Leaf { bounds, .. } | Node::Branch { bounds, .. } => *bounds
+        }
+    }
+}
+
+/// Bounding volume hierarchy over the tight boxes of every segment, so queries only
+/// look at segments whose boxes can matter instead of scanning all of them
+pub struct Bvh {
+    nodes: Vec<Node>,
+    segments: Vec<(SegmentRef, [Point; 4], [Vec2; 2])>,
+    /// Where each segment ended up in `segments` once they were sorted into the nodes
+    slots: HashMap<SegmentRef, usize>
+}
+
+fn union([amin, amax]: [Vec2; 2], [bmin, bmax]: [Vec2; 2]) -> [Vec2; 2] {
+    [amin.min(bmin), amax.max(bmax)]
+}
+
+fn box_distance([pmin, pmax]: [Vec2; 2], p: Vec2) -> f32 {
+    p.clamp(pmin, pmax).distance(p)
+}
+
+// Slab test, whether the ray reaches the box at some distance of at least zero
+fn ray_hits_box([bmin, bmax]: [Vec2; 2], origin: Vec2, dir: Vec2) -> bool {
+    let (mut near, mut far) = (0f32, f32::MAX);
+    for axis in 0..2 {
+        match dir[axis] == 0. {
+            true if origin[axis] < bmin[axis] || origin[axis] > bmax[axis] => return false,
+            true => (),
+            false => {
+                let (a, b) = ((bmin[axis] - origin[axis]) / dir[axis], (bmax[axis] - origin[axis]) / dir[axis]);
+                near = near.max(a.min(b));
+                far = far.min(a.max(b));
+            }
+        }
+    }
+    near <= far
+}
+
+impl Bvh {
+    pub fn new(curves: &[Curve]) -> Self {
+        let mut segments = Vec::new();
+        for (curve_id, curve) in curves.iter().enumerate() {
+            for (segment_id, points) in curve.segments().into_iter().enumerate() {
+                let segment = SegmentRef { curve: curve_id, segment: segment_id };
+                segments.push((segment, points, Curve::tight_bounding_box(&points)));
+            }
+        }
+
+        let mut bvh = Self { nodes: Vec::new(), segments, slots: HashMap::new() };
+        if !bvh.segments.is_empty() {
+            bvh.build(0, bvh.segments.len());
+        }
+        bvh.slots = bvh.segments.iter().enumerate().map(|(slot, s)| (s.0, slot)).collect();
+
+        bvh
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.segments.is_empty()
+    }
+
+    /// Builds the node for segments[first..first + count], splitting at the median
+    /// centroid along the longest axis. Returns the index of the created node
+    fn build(&mut self, first: usize, count: usize) -> usize {
+        let slice = &mut self.segments[first..first + count];
+        let bounds = slice.iter().map(|s| s.2).reduce(union).unwrap();
+
+        if count <= LEAF_SIZE {
+            self.nodes.push(Node::Leaf { bounds, first, count });
+            return self.nodes.len() - 1;
+        }
+
+        let size = bounds[1] - bounds[0];
+        let axis = if size.x >= size.y { 0 } else { 1 };
+        let centroid = |b: &[Vec2; 2]| (b[0][axis] + b[1][axis]) / 2.;
+        slice.sort_by(|a, b| centroid(&a.2).total_cmp(&centroid(&b.2)));
+
+        // Reserve this node's slot before the children so the root stays at index 0
+        let id = self.nodes.len();
+        self.nodes.push(Node::Leaf { bounds, first, count });
+
+        let half = count / 2;
+        let left = self.build(first, half);
+        let right = self.build(first + half, count - half);
+        self.nodes[id] = Node::Branch { bounds, left, right };
+
+        id
+    }
+
+    /// Closest point to `p` over every segment
+    pub fn closest(&self, p: Vec2) -> Option<SegmentHit> {
+        if self.is_empty() { return None };
+
+        let mut best: Option<SegmentHit> = None;
+        let mut stack = vec![0];
+        while let Some(id) = stack.pop() {
+            let node = &self.nodes[id];
+            let best_distance = best.map_or(f32::MAX, |hit| hit.distance);
+            if box_distance(node.bounds(), p) > best_distance { continue };
+
+            match *node {
+                Node::Leaf { first, count, .. } => {
+                    for (segment, points, _) in &self.segments[first..first + count] {
+                        let (t, pos) = closest_param(points, p);
+                        let distance = pos.distance(p);
+                        if best.is_none_or(|hit| distance < hit.distance) {
+                            best = Some(SegmentHit { segment: *segment, t, pos, distance });
+                        }
+                    }
+                },
+                Node::Branch { left, right, .. } => {
+                    // Visit the nearer child first (it's pushed last) so the other gets pruned more often
+                    let dl = box_distance(self.nodes[left].bounds(), p);
+                    let dr = box_distance(self.nodes[right].bounds(), p);
+                    if dl < dr { stack.extend([right, left]) } else { stack.extend([left, right]) }
+                }
+            }
+        }
+
+        best
+    }
+
+    /// Closest segment point within `radius` of `p`, used to pick curves with the mouse
+    pub fn pick(&self, p: Vec2, radius: f32) -> Option<SegmentHit> {
+        self.closest(p).filter(|hit| hit.distance <= radius)
+    }
+
+    // Indices into `segments` of every segment whose tight box overlaps `bounds`
+    fn query_slots(&self, bounds: [Vec2; 2]) -> Vec<usize> {
+        if self.is_empty() { return Vec::new() };
+
+        let mut found = Vec::new();
+        let mut stack = vec![0];
+        while let Some(id) = stack.pop() {
+            let node = &self.nodes[id];
+            if !boxes_overlap(node.bounds(), bounds) { continue };
+
+            match *node {
+                Node::Leaf { first, count, .. } => {
+                    found.extend((first..first + count).filter(|&slot| boxes_overlap(self.segments[slot].2, bounds)));
+                },
+                Node::Branch { left, right, .. } => stack.extend([left, right])
+            }
+        }
+
+        found
+    }
+
+    /// Every segment whose tight box overlaps `bounds`
+    pub fn query_box(&self, bounds: [Vec2; 2]) -> Vec<SegmentRef> {
+        self.query_slots(bounds).into_iter().map(|slot| self.segments[slot].0).collect()
+    }
+
+    /// Crossings between segment `query` and every other segment in the hierarchy, as
+    /// (segment hit, parameter on `query`) pairs. Neighbouring segments of a curve touching
+    /// at the anchor they share don't cross there
+    pub fn intersections(&self, query: SegmentRef, tolerance: f32) -> Vec<(SegmentHit, f32)> {
+        let Some(&slot) = self.slots.get(&query) else { return Vec::new() };
+        let (_, points, bounds) = self.segments[slot];
+
+        let mut hits = Vec::new();
+        for other_slot in self.query_slots(bounds).into_iter().filter(|&other| other != slot) {
+            let (segment, other, _) = self.segments[other_slot];
+            let shared = [points[0].pos, points[3].pos].into_iter()
+                .filter(|&end| segment.curve == query.curve && (end == other[0].pos || end == other[3].pos))
+                .collect::<Vec<_>>();
+
+            for (t_query, t) in intersections(&points, &other, tolerance) {
+                let pos = cubic_bezier(t, &other);
+                if shared.iter().any(|anchor| anchor.distance(pos) < SHARED_ANCHOR_TOLERANCES * tolerance) { continue };
+                hits.push((SegmentHit { segment, t, pos, distance: 0. }, t_query));
+            }
+        }
+
+        hits
+    }
+
+    /// Every crossing of the ray from `origin` along `dir` with the segments, nearest first.
+    /// A crossing through the anchor two segments of a curve share is reported once
+    pub fn raycast(&self, origin: Vec2, dir: Vec2) -> Vec<RayHit> {
+        if self.is_empty() || dir == Vec2::ZERO { return Vec::new() };
+
+        let mut hits = Vec::new();
+        let mut stack = vec![0];
+        while let Some(id) = stack.pop() {
+            let node = &self.nodes[id];
+            if !ray_hits_box(node.bounds(), origin, dir) { continue };
+
+            match *node {
+                Node::Leaf { first, count, .. } => {
+                    for (segment, points, _) in &self.segments[first..first + count] {
+                        for t in line_crossings(*points, origin, dir) {
+                            let pos = cubic_bezier(t, points);
+                            let distance = (pos - origin).dot(dir) / dir.length_squared();
+                            if distance >= 0. {
+                                hits.push(RayHit { distance, segment: *segment, t, pos });
+                            }
+                        }
+                    }
+                },
+                Node::Branch { left, right, .. } => stack.extend([left, right])
+            }
+        }
+
+        hits.sort_by(|a, b| a.distance.total_cmp(&b.distance));
+        hits.dedup_by(|b, a| a.segment.curve == b.segment.curve && a.pos.distance(b.pos) < 1e-4);
+        hits
+    }
+
+    /// Control points of a segment stored in the hierarchy
+    pub fn segment(&self, segment: SegmentRef) -> Option<[Point; 4]> {
+        self.slots.get(&segment).map(|&slot| self.segments[slot].1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn curve(positions: &[Vec2]) -> Curve {
+        Curve { control: positions.iter().map(|&pos| Point::new(pos, WHITE)).collect(), ..Curve::default() }
+    }
+
+    #[test]
+    fn finds_segments_by_reference() {
+        let curves = [
+            curve(&[vec2(0., 0.), vec2(1., 0.), vec2(2., 0.), vec2(3., 0.), vec2(4., 1.), vec2(5., 1.), vec2(6., 0.)]),
+            curve(&[vec2(0., 5.), vec2(1., 5.), vec2(2., 5.), vec2(3., 5.)])
+        ];
+        let bvh = Bvh::new(&curves);
+        for (i, c) in curves.iter().enumerate() {
+            for (j, points) in c.segments().into_iter().enumerate() {
+                let found = bvh.segment(SegmentRef { curve: i, segment: j }).unwrap();
+                assert!(found.iter().zip(&points).all(|(a, b)| a.pos == b.pos));
+            }
+        }
+        assert!(bvh.segment(SegmentRef { curve: 1, segment: 1 }).is_none());
+    }
+
+    #[test]
+    fn neighbours_dont_cross_at_their_shared_anchor() {
+        // Two segments of one curve meeting at (30, 0), the second one looping back across the first
+        let curves = [curve(&[
+            vec2(0., 0.), vec2(10., 10.), vec2(20., 10.), vec2(30., 0.),
+            vec2(20., 40.), vec2(10., 40.), vec2(0., -10.)
+        ])];
+        let bvh = Bvh::new(&curves);
+        let hits = bvh.intersections(SegmentRef { curve: 0, segment: 0 }, 0.01);
+        assert!(!hits.is_empty());
+        assert!(hits.iter().all(|(hit, _)| hit.pos.distance(vec2(30., 0.)) > 1.));
+    }
+
+    // Straight segments through the corners, handles at the thirds
+    fn polyline(corners: &[Vec2]) -> Curve {
+        let mut positions = vec![corners[0]];
+        for pair in corners.windows(2) {
+            positions.extend([pair[0].lerp(pair[1], 1. / 3.), pair[0].lerp(pair[1], 2. / 3.), pair[1]]);
+        }
+        curve(&positions)
+    }
+
+    fn posts() -> Vec<Curve> {
+        [50., 10., 30.].iter().map(|&x| polyline(&[vec2(x, -10.), vec2(x, 10.)])).collect()
+    }
+
+    #[test]
+    fn ray_hits_nearest_first() {
+        let bvh = Bvh::new(&posts());
+        let hits = bvh.raycast(vec2(0., 0.), vec2(2., 0.));
+        let curves: Vec<usize> = hits.iter().map(|hit| hit.segment.curve).collect();
+        assert_eq!(curves, [1, 2, 0]);
+        // In multiples of `dir`
+        let distances: Vec<f32> = hits.iter().map(|hit| hit.distance).collect();
+        assert!(distances.iter().zip([5., 15., 25.]).all(|(d, e)| (d - e).abs() < 1e-3));
+        assert!(hits[1].pos.distance(vec2(30., 0.)) < 1e-3);
+    }
+
+    #[test]
+    fn ray_skips_what_is_behind_it() {
+        let bvh = Bvh::new(&posts());
+        let hits = bvh.raycast(vec2(40., 0.), vec2(1., 0.));
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].segment.curve, 0);
+        assert!(bvh.raycast(vec2(60., 0.), vec2(1., 0.)).is_empty());
+    }
+
+    #[test]
+    fn ray_through_a_shared_anchor_hits_once() {
+        let bvh = Bvh::new(&[polyline(&[vec2(10., -10.), vec2(10., 0.), vec2(10., 10.)])]);
+        let hits = bvh.raycast(vec2(0., 0.), vec2(1., 0.));
+        assert_eq!(hits.len(), 1);
+        assert!(hits[0].pos.distance(vec2(10., 0.)) < 1e-3);
+    }
+
+    #[test]
+    fn ray_without_a_direction() {
+        let bvh = Bvh::new(&posts());
+        assert!(bvh.raycast(vec2(0., 0.), Vec2::ZERO).is_empty());
+        assert!(Bvh::new(&[]).raycast(vec2(0., 0.), Vec2::X).is_empty());
+    }
+}
@@ -0,0 +1,96 @@
+use macroquad::prelude::*;
+use serde::Serialize;
+
+use crate::coords::CoordinateSystem;
+use crate::curve::Curve;
+use crate::math::arc_length;
+
+/// Suggested name for exported camera tracks
+pub const CAMERA_PATH_FILE: &str = "camera_path.json";
+// Keyframes written per second of the track
+const KEYFRAME_RATE: f32 = 10.;
+// Units per second the camera covers on average
+const CAMERA_SPEED: f32 = 2.;
+// Even short tracks take this many seconds
+const MIN_DURATION: f32 = 1.;
+
+/// Slow at both ends and fastest halfway, t² (3 - 2t)
+pub fn ease_in_out(t: f32) -> f32 {
+    let t = t.clamp(0., 1.);
+    t * t * (3. - 2. * t)
+}
+
+/// A curve used as a 2D camera track. The camera eases along it by arc length, showing
+/// a window-sized viewport at a fixed zoom
+pub struct CameraTrack {
+    /// Index of the curve followed in the document
+    pub curve: usize,
+    /// Screen pixels per world unit, like the view's scale
+    pub zoom: f32,
+    /// Seconds into the preview
+    pub time: f32
+}
+
+#[derive(Serialize)]
+struct TrackFile {
+    duration: f32,
+    keyframes: Vec<KeyframeFile>
+}
+
+#[derive(Serialize)]
+struct KeyframeFile {
+    time: f32,
+    position: [f32; 2],
+    zoom: f32
+}
+
+impl CameraTrack {
+    pub fn new(curve: usize, zoom: f32) -> Self {
+        Self { curve, zoom, time: 0. }
+    }
+
+    /// Seconds the camera takes along the whole of `curve`
+    pub fn duration(&self, curve: &Curve, coords: &CoordinateSystem) -> f32 {
+        let length: f32 = curve.segments().into_iter().map(arc_length).sum();
+        (coords.length(length) / CAMERA_SPEED).max(MIN_DURATION)
+    }
+
+    /// World position of the camera `time` seconds in, None without a complete segment
+    pub fn position(&self, curve: &Curve, coords: &CoordinateSystem, time: f32) -> Option<Vec2> {
+        let length: f32 = curve.segments().into_iter().map(arc_length).sum();
+        let progress = ease_in_out(time / self.duration(curve, coords));
+        curve.point_at_length(length * progress).map(|(pos, _)| pos)
+    }
+
+    /// Play `dt` more seconds, starting over after the end
+    pub fn advance(&mut self, curve: &Curve, coords: &CoordinateSystem, dt: f32) {
+        self.time = (self.time + dt) % self.duration(curve, coords);
+    }
+
+    /// Keyframes of the whole track as JSON for cutscenes, positions in the user's `coords`
+    /// and zoom in screen pixels per unit
+    pub fn keyframes_json(&self, curve: &Curve, coords: &CoordinateSystem) -> String {
+        let duration = self.duration(curve, coords);
+        let count = (duration * KEYFRAME_RATE).ceil() as usize;
+        let keyframes = (0..=count)
+            .filter_map(|i| {
+                let time = (i as f32 / KEYFRAME_RATE).min(duration);
+                let pos = coords.to_math(self.position(curve, coords, time)?);
+                Some(KeyframeFile { time, position: [pos.x, pos.y], zoom: self.zoom * coords.pixels_per_unit })
+            })
+            .collect();
+
+        let file = TrackFile { duration, keyframes };
+        serde_json::to_string_pretty(&file).expect("camera track should serialize")
+    }
+}
+
+/// Outline of what a `screen`-sized camera at `zoom` centered on `center` shows, with the
+/// world camera set
+pub fn draw_viewport(center: Vec2, screen: Vec2, zoom: f32, line_width: f32, color: Color) {
+    let size = screen / zoom;
+    let corner = center - size / 2.;
+    draw_rectangle_lines(corner.x, corner.y, size.x, size.y, line_width, color);
+    draw_line(center.x - size.x * 0.05, center.y, center.x + size.x * 0.05, center.y, line_width, color);
+    draw_line(center.x, center.y - size.x * 0.05, center.x, center.y + size.x * 0.05, line_width, color);
+}
@@ -0,0 +1,233 @@
+use macroquad::prelude::*;
+use numerical_curves_core::bezier;
+
+/// Control points of a cubic bezier segment in 3D
+pub type Segment3 = [Vec3; 4];
+
+// Slowest the cart goes, past the crests energy alone can't climb a chain lift takes it over
+const MIN_CART_SPEED: f32 = 1e-3;
+
+pub fn point3(segment: Segment3, t: f32) -> Vec3 {
+    bezier::eval(segment, t)
+}
+
+pub fn velocity3(segment: Segment3, t: f32) -> Vec3 {
+    bezier::velocity(segment, t)
+}
+
+pub fn acceleration3(segment: Segment3, t: f32) -> Vec3 {
+    bezier::acceleration(segment, t)
+}
+
+pub fn jerk3(segment: Segment3) -> Vec3 {
+    bezier::jerk::<f32, _>(segment)
+}
+
+/// Curvature |B' x B''| / |B'|^3, unsigned in 3D, zero where the speed vanishes
+pub fn curvature3(segment: Segment3, t: f32) -> f32 {
+    let vel = velocity3(segment, t);
+    let speed = vel.length();
+    if speed < f32::EPSILON { return 0. };
+    vel.cross(acceleration3(segment, t)).length() / speed.powi(3)
+}
+
+/// Torsion (B' x B'') . B''' / |B' x B''|^2, how fast the curve twists out of its osculating
+/// plane. Zero on straight stretches, where that plane isn't defined
+pub fn torsion3(segment: Segment3, t: f32) -> f32 {
+    let cross = velocity3(segment, t).cross(acceleration3(segment, t));
+    let area = cross.length_squared();
+    if area < f32::EPSILON { return 0. };
+    cross.dot(jerk3(segment)) / area
+}
+
+/// Curvature and torsion combs at `samples` + 1 parameters of every segment, with a 3D
+/// camera set: teeth along the principal normal `scale` times the curvature long and along
+/// the binormal `scale` times the torsion long, their tips joined
+pub fn draw_frenet_combs(segments: &[Segment3], samples: usize, scale: f32, curvature_color: Color, torsion_color: Color) {
+    let samples = samples.max(1);
+    for &segment in segments {
+        let mut tips: Option<(Vec3, Vec3)> = None;
+        for i in 0..=samples {
+            let t = i as f32 / samples as f32;
+            let (pos, vel, acc) = (point3(segment, t), velocity3(segment, t), acceleration3(segment, t));
+            let binormal = vel.cross(acc).normalize_or_zero();
+            let normal = binormal.cross(vel).normalize_or_zero();
+            let curvature_tip = pos + normal * curvature3(segment, t) * scale;
+            let torsion_tip = pos + binormal * torsion3(segment, t) * scale;
+            draw_line_3d(pos, curvature_tip, Color { a: 0.5, ..curvature_color });
+            draw_line_3d(pos, torsion_tip, Color { a: 0.5, ..torsion_color });
+            if let Some((last_curvature, last_torsion)) = tips {
+                draw_line_3d(last_curvature, curvature_tip, curvature_color);
+                draw_line_3d(last_torsion, torsion_tip, torsion_color);
+            }
+            tips = Some((curvature_tip, torsion_tip));
+        }
+    }
+}
+
+/// Orthonormal frame on a track, `normal` is the track's up and `binormal` points to its
+/// right hand side
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Frame {
+    pub pos: Vec3,
+    pub tangent: Vec3,
+    pub normal: Vec3,
+    pub binormal: Vec3
+}
+
+// Perpendicular to `tangent` and as close to `up` as possible, any perpendicular when they're parallel
+fn perpendicular_towards(tangent: Vec3, up: Vec3) -> Vec3 {
+    let normal = up - tangent * up.dot(tangent);
+    normal.try_normalize().unwrap_or_else(|| tangent.any_orthonormal_vector())
+}
+
+/// Frames at `samples` + 1 evenly spaced parameters of every segment that twist as little as
+/// possible, by the double reflection method of Wang, Jüttler, Zheng and Liu. The first
+/// normal is the one closest to `up`, so a flat track starts out level
+pub fn rotation_minimizing_frames(segments: &[Segment3], samples: usize, up: Vec3) -> Vec<Frame> {
+    let samples = samples.max(1);
+    let points: Vec<(Vec3, Vec3)> = segments.iter()
+        .enumerate()
+        .flat_map(|(i, &segment)| {
+            // Consecutive segments share their joint
+            let first = match i {
+                0 => 0,
+                _ => 1
+            };
+            (first..=samples).map(move |k| {
+                let t = k as f32 / samples as f32;
+                (point3(segment, t), velocity3(segment, t).normalize_or_zero())
+            })
+        })
+        .collect();
+
+    let mut frames: Vec<Frame> = Vec::with_capacity(points.len());
+    for (pos, tangent) in points {
+        let normal = match frames.last() {
+            None => perpendicular_towards(tangent, up),
+            Some(previous) => {
+                // Reflect across the plane bisecting the two positions, then across the one
+                // taking the reflected tangent onto the new tangent
+                let v1 = pos - previous.pos;
+                let c1 = v1.dot(v1);
+                if c1 < f32::EPSILON {
+                    perpendicular_towards(tangent, previous.normal)
+                } else {
+                    let normal_l = previous.normal - v1 * (2. / c1 * v1.dot(previous.normal));
+                    let tangent_l = previous.tangent - v1 * (2. / c1 * v1.dot(previous.tangent));
+                    let v2 = tangent - tangent_l;
+                    let c2 = v2.dot(v2);
+                    match c2 < f32::EPSILON {
+                        true => normal_l,
+                        false => normal_l - v2 * (2. / c2 * v2.dot(normal_l))
+                    }.normalize_or_zero()
+                }
+            }
+        };
+        frames.push(Frame { pos, tangent, normal, binormal: tangent.cross(normal) });
+    }
+
+    frames
+}
+
+/// Rails and ties swept along a track's frames
+pub struct TrackMesh {
+    pub left: Vec<Vec3>,
+    pub right: Vec<Vec3>,
+    /// Each tie from the left rail to the right one
+    pub ties: Vec<[Vec3; 2]>
+}
+
+/// Rails `gauge` apart on both sides of the frames, with a tie every `tie_spacing` along the track
+pub fn sweep_track(frames: &[Frame], gauge: f32, tie_spacing: f32) -> TrackMesh {
+    let half = gauge / 2.;
+    let left: Vec<Vec3> = frames.iter().map(|frame| frame.pos - frame.binormal * half).collect();
+    let right: Vec<Vec3> = frames.iter().map(|frame| frame.pos + frame.binormal * half).collect();
+
+    let mut ties = Vec::new();
+    let mut since_tie = tie_spacing;
+    for (i, frame) in frames.iter().enumerate() {
+        if i > 0 {
+            since_tie += frame.pos.distance(frames[i - 1].pos);
+        }
+        if since_tie >= tie_spacing {
+            ties.push([left[i], right[i]]);
+            since_tie = 0.;
+        }
+    }
+
+    TrackMesh { left, right, ties }
+}
+
+/// Speed of a frictionless cart at every frame from the height profile, by conservation of
+/// energy: v² = v0² + 2 g (h0 - h) with heights along `up`
+pub fn cart_speeds(frames: &[Frame], start_speed: f32, gravity: f32, up: Vec3) -> Vec<f32> {
+    let Some(start) = frames.first() else { return Vec::new() };
+    let h0 = start.pos.dot(up);
+    frames.iter()
+        .map(|frame| (start_speed * start_speed + 2. * gravity * (h0 - frame.pos.dot(up))).max(0.).sqrt().max(MIN_CART_SPEED))
+        .collect()
+}
+
+/// A cart riding a track, starting over at its end
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Cart {
+    /// Distance from the start of the track
+    pub travelled: f32
+}
+
+impl Cart {
+    /// Ride `dt` seconds at the speeds of `cart_speeds`, returns where the cart is then
+    pub fn advance(&mut self, frames: &[Frame], speeds: &[f32], dt: f32) -> Option<Frame> {
+        let lengths: Vec<f32> = frames.windows(2).map(|pair| pair[0].pos.distance(pair[1].pos)).collect();
+        let total: f32 = lengths.iter().sum();
+        if total <= 0. || speeds.len() != frames.len() { return None };
+
+        let mut along = 0.;
+        for (i, &length) in lengths.iter().enumerate() {
+            if self.travelled <= along + length {
+                let u = match length > 0. {
+                    true => (self.travelled - along) / length,
+                    false => 0.
+                };
+                let speed = speeds[i] + (speeds[i + 1] - speeds[i]) * u;
+                self.travelled = (self.travelled + speed * dt) % total;
+                let (a, b) = (frames[i], frames[i + 1]);
+                let tangent = a.tangent.lerp(b.tangent, u).normalize_or_zero();
+                let normal = perpendicular_towards(tangent, a.normal.lerp(b.normal, u));
+                return Some(Frame { pos: a.pos.lerp(b.pos, u), tangent, normal, binormal: tangent.cross(normal) });
+            }
+            along += length;
+        }
+
+        self.travelled = 0.;
+        frames.first().copied()
+    }
+}
+
+/// Rails as lines and ties across them, with a 3D camera set
+pub fn draw_track(mesh: &TrackMesh, rail: Color, tie: Color) {
+    for rail_points in [&mesh.left, &mesh.right] {
+        for pair in rail_points.windows(2) {
+            draw_line_3d(pair[0], pair[1], rail);
+        }
+    }
+    for [a, b] in &mesh.ties {
+        draw_line_3d(*a, *b, tie);
+    }
+}
+
+/// The cart as a box sitting on the track at `frame`, `size` long
+pub fn draw_cart(frame: &Frame, size: f32, color: Color) {
+    let (forward, up, side) = (frame.tangent * size / 2., frame.normal * size / 4., frame.binormal * size / 3.);
+    let base = frame.pos + up;
+    let corners = [
+        base - forward - side, base + forward - side, base + forward + side, base - forward + side
+    ];
+    for i in 0..4 {
+        let (a, b) = (corners[i], corners[(i + 1) % 4]);
+        draw_line_3d(a, b, color);
+        draw_line_3d(a + up, b + up, color);
+        draw_line_3d(a, a + up, color);
+    }
+}
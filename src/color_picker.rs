@@ -0,0 +1,153 @@
+use macroquad::prelude::*;
+use macroquad::color::{hsl_to_rgb, rgb_to_hsl};
+
+use crate::curve::Curve;
+
+const PANEL_WIDTH: f32 = 240.0;
+const PANEL_MARGIN: f32 = 10.0;
+const SLIDER_HEIGHT: f32 = 14.0;
+const SLIDER_GAP: f32 = 26.0;
+const SWATCH_SIZE: f32 = 22.0;
+const PALETTE: [Color; 8] = [ORANGE, BLUE, RED, PURPLE, LIME, YELLOW, PINK, WHITE];
+const SLIDER_LABELS: [&str; 3] = ["hue", "saturation", "lightness"];
+
+/// What the picker recolors on the active curve
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PickerTarget {
+    Point(usize),
+    Curve
+}
+
+/// Popup on the right side with hue, saturation and lightness sliders over a palette of
+/// swatches. Changes are applied to the target right away
+#[derive(Default)]
+pub struct ColorPicker {
+    target: Option<PickerTarget>,
+    hsl: [f32; 3],
+    // Index of the slider being dragged
+    dragging: Option<usize>
+}
+
+fn origin() -> Vec2 {
+    vec2(screen_width() - PANEL_WIDTH - PANEL_MARGIN, 60.)
+}
+
+fn panel_size() -> Vec2 {
+    vec2(PANEL_WIDTH, 30. + SLIDER_GAP * 3. + SWATCH_SIZE + 2. * PANEL_MARGIN)
+}
+
+fn slider_rect(i: usize) -> Rect {
+    let o = origin();
+    Rect::new(o.x + PANEL_MARGIN, o.y + 30. + SLIDER_GAP * i as f32, PANEL_WIDTH - 2. * PANEL_MARGIN, SLIDER_HEIGHT)
+}
+
+fn swatch_rect(i: usize) -> Rect {
+    let o = origin();
+    let y = o.y + 30. + SLIDER_GAP * 3. + PANEL_MARGIN / 2.;
+    Rect::new(o.x + PANEL_MARGIN + i as f32 * (SWATCH_SIZE + 6.), y, SWATCH_SIZE, SWATCH_SIZE)
+}
+
+impl ColorPicker {
+    pub fn open(&mut self, target: PickerTarget, color: Color) {
+        let (h, s, l) = rgb_to_hsl(color);
+        self.target = Some(target);
+        self.hsl = [h, s, l];
+    }
+
+    pub fn close(&mut self) {
+        self.target = None;
+        self.dragging = None;
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.target.is_some()
+    }
+
+    /// Whether the mouse is over the open popup, clicks there shouldn't reach the canvas
+    pub fn hovered(&self) -> bool {
+        let (o, size) = (origin(), panel_size());
+        self.is_open() && Rect::new(o.x, o.y, size.x, size.y).contains(mouse_position().into())
+    }
+
+    pub fn color(&self) -> Color {
+        hsl_to_rgb(self.hsl[0], self.hsl[1], self.hsl[2])
+    }
+
+    /// Handle the sliders and swatches, returns true while the picker is using the mouse
+    pub fn update(&mut self, curve: &mut Curve) -> bool {
+        let Some(target) = self.target else { return false };
+        if let PickerTarget::Point(id) = target {
+            if id >= curve.control.len() {
+                self.close();
+                return false;
+            }
+        }
+
+        let mouse: Vec2 = mouse_position().into();
+        if !is_mouse_button_down(MouseButton::Left) {
+            self.dragging = None;
+        }
+
+        let mut changed = false;
+        if is_mouse_button_pressed(MouseButton::Left) {
+            self.dragging = (0..3).find(|&i| slider_rect(i).contains(mouse));
+            if let Some(swatch) = (0..PALETTE.len()).find(|&i| swatch_rect(i).contains(mouse)) {
+                let (h, s, l) = rgb_to_hsl(PALETTE[swatch]);
+                self.hsl = [h, s, l];
+                changed = true;
+            }
+        }
+
+        if let Some(i) = self.dragging {
+            let rect = slider_rect(i);
+            self.hsl[i] = ((mouse.x - rect.x) / rect.w).clamp(0., 1.);
+            changed = true;
+        }
+
+        if changed {
+            let color = self.color();
+            match target {
+                PickerTarget::Point(id) => curve.control[id].color = color,
+                PickerTarget::Curve => for point in &mut curve.control {
+                    point.color = color;
+                }
+            }
+            curve.modified = true;
+        }
+
+        self.dragging.is_some() || self.hovered()
+    }
+
+    pub fn draw(&self) {
+        let Some(target) = self.target else { return };
+        let (o, size) = (origin(), panel_size());
+        draw_rectangle(o.x, o.y, size.x, size.y, Color::new(0., 0., 0., 0.7));
+
+        let title = match target {
+            PickerTarget::Point(id) => format!("point #{}", id),
+            PickerTarget::Curve => "whole curve".to_string()
+        };
+        draw_text(&title, o.x + PANEL_MARGIN, o.y + 20., 20., WHITE);
+        draw_rectangle(o.x + PANEL_WIDTH - PANEL_MARGIN - 40., o.y + 6., 40., 18., self.color());
+
+        for (i, label) in SLIDER_LABELS.iter().enumerate() {
+            let rect = slider_rect(i);
+            // Each slider shows the colors it would pick with the other two kept
+            let steps = 24;
+            for step in 0..steps {
+                let mut hsl = self.hsl;
+                hsl[i] = (step as f32 + 0.5) / steps as f32;
+                let w = rect.w / steps as f32;
+                draw_rectangle(rect.x + step as f32 * w, rect.y, w + 0.5, rect.h, hsl_to_rgb(hsl[0], hsl[1], hsl[2]));
+            }
+            let x = rect.x + self.hsl[i] * rect.w;
+            draw_rectangle_lines(x - 3., rect.y - 2., 6., rect.h + 4., 2., WHITE);
+            draw_text(label, rect.x, rect.y - 1., 14., GRAY);
+        }
+
+        for (i, color) in PALETTE.iter().enumerate() {
+            let rect = swatch_rect(i);
+            draw_rectangle(rect.x, rect.y, rect.w, rect.h, *color);
+        }
+    }
+}
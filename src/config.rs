@@ -0,0 +1,499 @@
+use std::collections::HashMap;
+use std::{fmt, fs, io};
+
+use macroquad::prelude::*;
+use serde::Deserialize;
+
+use crate::export::{ChainWinding, ColliderOptions};
+use crate::snap::DEFAULT_SNAP_RADIUS;
+use crate::theme::Theme;
+use crate::vehicle::VehicleOptions;
+
+/// Read from the working directory at startup, everything in it is optional
+pub const CONFIG_PATH: &str = "config.toml";
+pub const DEFAULT_POINT_COLORS: [Color; 4] = [ORANGE, BLUE, RED, PURPLE];
+
+/// Every rebindable action with its default key
+const DEFAULT_BINDINGS: &[(&str, KeyCode)] = &[
+    ("lock_point", KeyCode::X),
+    ("edit_coordinates", KeyCode::Enter),
+    ("color_picker", KeyCode::R),
+    ("close_popup", KeyCode::Escape),
+    ("fewer_pieces", KeyCode::Minus),
+    ("more_pieces", KeyCode::Equal),
+    ("split_segment", KeyCode::S),
+    ("cycle_continuity", KeyCode::J),
+    ("bounding_boxes", KeyCode::B),
+    ("convex_hull", KeyCode::H),
+    ("carry_handles", KeyCode::T),
+    ("pen_mode", KeyCode::E),
+    ("profiler", KeyCode::P),
+    ("rulers", KeyCode::V),
+    ("grid", KeyCode::G),
+    ("finer_grid", KeyCode::Semicolon),
+    ("coarser_grid", KeyCode::Apostrophe),
+    ("auto_grid", KeyCode::Backslash),
+    ("info", KeyCode::I),
+    ("winding", KeyCode::W),
+    ("distance_field", KeyCode::F5),
+    ("fill", KeyCode::F7),
+    ("close_curve", KeyCode::C),
+    ("toggle_bspline", KeyCode::K),
+    ("auto_smooth", KeyCode::A),
+    ("toggle_clamped", KeyCode::U),
+    ("boolean_operand", KeyCode::O),
+    ("union", KeyCode::Key1),
+    ("intersection", KeyCode::Key2),
+    ("difference", KeyCode::Key3),
+    ("new_curve", KeyCode::N),
+    ("next_curve", KeyCode::Tab),
+    ("level_of_detail", KeyCode::Z),
+    ("zoom_to_fit", KeyCode::F),
+    ("toggle_algorithm", KeyCode::M),
+    ("flatten", KeyCode::L),
+    ("antialias", KeyCode::Q),
+    ("thinner_lines", KeyCode::LeftBracket),
+    ("thicker_lines", KeyCode::RightBracket),
+    ("set_origin", KeyCode::Home),
+    ("halve_unit", KeyCode::Comma),
+    ("double_unit", KeyCode::Period),
+    ("fewer_samples", KeyCode::PageDown),
+    ("more_samples", KeyCode::PageUp),
+    ("save_project", KeyCode::F2),
+    ("open_project", KeyCode::F3),
+    ("copy_svg", KeyCode::D),
+    ("copy_code", KeyCode::Y),
+    ("lyon", KeyCode::F4),
+    ("export_sdf", KeyCode::F6),
+    ("timeline", KeyCode::F8),
+    ("history_back", KeyCode::Left),
+    ("history_forward", KeyCode::Right),
+    ("layers", KeyCode::F9),
+    ("profile", KeyCode::Backspace),
+    ("elevation", KeyCode::Delete),
+    ("bring_to_front", KeyCode::End),
+    ("group", KeyCode::Key4),
+    ("stamp_pattern", KeyCode::Key5),
+    ("reference", KeyCode::Key6),
+    ("trace", KeyCode::Key7),
+    ("physics", KeyCode::Key8),
+    ("rope", KeyCode::Key9),
+    ("export_collider", KeyCode::Key0),
+    ("obstacles", KeyCode::Slash),
+    ("vehicle", KeyCode::GraveAccent),
+    ("camera_path", KeyCode::F1),
+    ("bones", KeyCode::Space),
+    ("evolute", KeyCode::Kp1),
+    ("involute", KeyCode::Kp2),
+    ("osculating_circle", KeyCode::Kp3),
+    ("offsets", KeyCode::Kp4),
+    ("medial_axis", KeyCode::Kp5),
+    ("interval_boxes", KeyCode::Kp6),
+    ("verify_exact", KeyCode::Kp7),
+    ("smart_guides", KeyCode::Kp8),
+    ("measure", KeyCode::Kp9),
+    ("select", KeyCode::Kp0),
+    ("even_handles", KeyCode::KpDecimal),
+    ("flip", KeyCode::KpSubtract),
+    ("rotate_90", KeyCode::KpMultiply),
+    ("numeric_transform", KeyCode::KpDivide),
+    ("duplicate", KeyCode::Insert),
+    ("symmetry", KeyCode::F10),
+    ("radial_symmetry", KeyCode::F11),
+    ("path_text", KeyCode::F12),
+    ("raise_text", KeyCode::Up),
+    ("lower_text", KeyCode::Down)
+];
+
+/// Which key triggers each action
+#[derive(Clone, Debug)]
+pub struct Keymap {
+    bindings: HashMap<&'static str, KeyCode>
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self { bindings: DEFAULT_BINDINGS.iter().copied().collect() }
+    }
+}
+
+impl Keymap {
+    /// Key bound to `action`, `Unknown` (never pressed) for actions that don't exist
+    pub fn key(&self, action: &str) -> KeyCode {
+        self.bindings.get(action).copied().unwrap_or(KeyCode::Unknown)
+    }
+
+    /// Name of the key bound to `action`, for hints that tell which key to press
+    pub fn label(&self, action: &str) -> String {
+        key_name(self.key(action)).unwrap_or_else(|| "?".to_string())
+    }
+
+    /// Bind `action` to the key called `key`, like "b", "5", "tab" or "pageup"
+    pub fn bind(&mut self, action: &str, key: &str) -> Result<(), ConfigError> {
+        let Some(&(name, _)) = DEFAULT_BINDINGS.iter().find(|(name, _)| *name == action) else {
+            return Err(ConfigError::UnknownAction(action.to_string()));
+        };
+        let code = parse_key(key).ok_or_else(|| ConfigError::UnknownKey(key.to_string()))?;
+        self.bindings.insert(name, code);
+        Ok(())
+    }
+}
+
+const LETTERS: [KeyCode; 26] = [
+    KeyCode::A, KeyCode::B, KeyCode::C, KeyCode::D, KeyCode::E, KeyCode::F, KeyCode::G,
+    KeyCode::H, KeyCode::I, KeyCode::J, KeyCode::K, KeyCode::L, KeyCode::M, KeyCode::N,
+    KeyCode::O, KeyCode::P, KeyCode::Q, KeyCode::R, KeyCode::S, KeyCode::T, KeyCode::U,
+    KeyCode::V, KeyCode::W, KeyCode::X, KeyCode::Y, KeyCode::Z
+];
+const DIGITS: [KeyCode; 10] = [
+    KeyCode::Key0, KeyCode::Key1, KeyCode::Key2, KeyCode::Key3, KeyCode::Key4,
+    KeyCode::Key5, KeyCode::Key6, KeyCode::Key7, KeyCode::Key8, KeyCode::Key9
+];
+const KEYPAD: [KeyCode; 10] = [
+    KeyCode::Kp0, KeyCode::Kp1, KeyCode::Kp2, KeyCode::Kp3, KeyCode::Kp4,
+    KeyCode::Kp5, KeyCode::Kp6, KeyCode::Kp7, KeyCode::Kp8, KeyCode::Kp9
+];
+const FUNCTIONS: [KeyCode; 12] = [
+    KeyCode::F1, KeyCode::F2, KeyCode::F3, KeyCode::F4, KeyCode::F5, KeyCode::F6,
+    KeyCode::F7, KeyCode::F8, KeyCode::F9, KeyCode::F10, KeyCode::F11, KeyCode::F12
+];
+
+// Every other key by name, the first name of each key is the one hints show
+const NAMED_KEYS: &[(&str, KeyCode)] = &[
+    ("enter", KeyCode::Enter),
+    ("esc", KeyCode::Escape),
+    ("escape", KeyCode::Escape),
+    ("tab", KeyCode::Tab),
+    ("space", KeyCode::Space),
+    ("backspace", KeyCode::Backspace),
+    ("delete", KeyCode::Delete),
+    ("insert", KeyCode::Insert),
+    ("home", KeyCode::Home),
+    ("end", KeyCode::End),
+    ("pageup", KeyCode::PageUp),
+    ("pagedown", KeyCode::PageDown),
+    ("up", KeyCode::Up),
+    ("down", KeyCode::Down),
+    ("left", KeyCode::Left),
+    ("right", KeyCode::Right),
+    ("-", KeyCode::Minus),
+    ("minus", KeyCode::Minus),
+    ("=", KeyCode::Equal),
+    ("equal", KeyCode::Equal),
+    ("[", KeyCode::LeftBracket),
+    ("leftbracket", KeyCode::LeftBracket),
+    ("]", KeyCode::RightBracket),
+    ("rightbracket", KeyCode::RightBracket),
+    (";", KeyCode::Semicolon),
+    ("semicolon", KeyCode::Semicolon),
+    ("'", KeyCode::Apostrophe),
+    ("apostrophe", KeyCode::Apostrophe),
+    ("\\", KeyCode::Backslash),
+    ("backslash", KeyCode::Backslash),
+    ("/", KeyCode::Slash),
+    ("slash", KeyCode::Slash),
+    (",", KeyCode::Comma),
+    ("comma", KeyCode::Comma),
+    (".", KeyCode::Period),
+    ("period", KeyCode::Period),
+    ("`", KeyCode::GraveAccent),
+    ("graveaccent", KeyCode::GraveAccent),
+    ("kpdecimal", KeyCode::KpDecimal),
+    ("kpsubtract", KeyCode::KpSubtract),
+    ("kpmultiply", KeyCode::KpMultiply),
+    ("kpdivide", KeyCode::KpDivide)
+];
+
+fn parse_key(name: &str) -> Option<KeyCode> {
+    let name = name.to_lowercase();
+
+    let mut chars = name.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        match c {
+            'a'..='z' => return Some(LETTERS[c as usize - 'a' as usize]),
+            '0'..='9' => return Some(DIGITS[c as usize - '0' as usize]),
+            _ => ()
+        }
+    }
+    if let Some(n) = name.strip_prefix('f').and_then(|n| n.parse::<usize>().ok()) {
+        return FUNCTIONS.get(n.wrapping_sub(1)).copied();
+    }
+    if let Some(n) = name.strip_prefix("kp").and_then(|n| n.parse::<usize>().ok()) {
+        return KEYPAD.get(n).copied();
+    }
+
+    NAMED_KEYS.iter().find(|&&(key, _)| key == name).map(|&(_, code)| code)
+}
+
+/// Name of `code` the way `parse_key` reads it
+fn key_name(code: KeyCode) -> Option<String> {
+    let position = |keys: &[KeyCode]| keys.iter().position(|&k| k == code);
+    if let Some(i) = position(&LETTERS) { return Some(((b'a' + i as u8) as char).to_string()) };
+    if let Some(i) = position(&DIGITS) { return Some(i.to_string()) };
+    if let Some(i) = position(&FUNCTIONS) { return Some(format!("f{}", i + 1)) };
+    if let Some(i) = position(&KEYPAD) { return Some(format!("kp{}", i)) };
+    NAMED_KEYS.iter().find(|&&(_, key)| key == code).map(|&(name, _)| name.to_string())
+}
+
+/// "#rrggbb" or "#rrggbbaa"
+fn parse_color(text: &str) -> Option<Color> {
+    let hex = text.strip_prefix('#')?;
+    if !(hex.len() == 6 || hex.len() == 8) || !hex.is_ascii() { return None };
+
+    let channels: Vec<u8> = (0..hex.len()).step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).ok())
+        .collect::<Option<_>>()?;
+    let alpha = channels.get(3).copied().unwrap_or(255);
+    Some(Color::from_rgba(channels[0], channels[1], channels[2], alpha))
+}
+
+/// Toggles the editor starts with
+#[derive(Clone, Copy, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Toggles {
+    pub grid: bool,
+    pub rulers: bool,
+    pub info: bool,
+    pub winding: bool,
+    pub bounding_boxes: bool,
+    pub convex_hull: bool,
+    pub antialias: bool,
+    pub level_of_detail: bool,
+    pub flatten: bool,
+    pub casteljau: bool,
+    pub smart_guides: bool
+}
+
+// The file as written, checked and converted by `Config::parse`
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct ConfigFile {
+    theme: Option<String>,
+    colors: HashMap<String, String>,
+    point_colors: Vec<String>,
+    toggles: Toggles,
+    keys: HashMap<String, String>,
+    collider: ColliderSection,
+    vehicle: VehicleSection,
+    snap: SnapSection
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct ColliderSection {
+    tolerance: Option<f32>,
+    winding: Option<String>
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct VehicleSection {
+    wheelbase: Option<f32>,
+    min_turn_radius: Option<f32>,
+    speed: Option<f32>
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct SnapSection {
+    radius: Option<f32>
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Io(io::Error),
+    Parse(toml::de::Error),
+    UnknownTheme(String),
+    UnknownColor(String),
+    BadColor(String),
+    UnknownAction(String),
+    UnknownKey(String),
+    /// A setting that has to be above zero, by name
+    NotPositive(&'static str, f32),
+    UnknownWinding(String)
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConfigError::Io(error) => write!(f, "couldn't read the config: {}", error),
+            ConfigError::Parse(error) => write!(f, "invalid config: {}", error),
+            ConfigError::UnknownTheme(name) => write!(f, "unknown theme \"{}\", use dark or light", name),
+            ConfigError::UnknownColor(name) => write!(f, "unknown color \"{}\"", name),
+            ConfigError::BadColor(value) => write!(f, "bad color \"{}\", expected #rrggbb or #rrggbbaa", value),
+            ConfigError::UnknownAction(name) => write!(f, "unknown action \"{}\"", name),
+            ConfigError::UnknownKey(name) => write!(f, "unknown key \"{}\"", name),
+            ConfigError::NotPositive(name, value) => write!(f, "bad {} {}, it should be above 0", name, value),
+            ConfigError::UnknownWinding(name) => write!(f, "unknown winding \"{}\", use ccw, cw or as_drawn", name)
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+/// Theme, starting toggles, keybindings, and export, vehicle and snapping settings
+#[derive(Clone, Debug)]
+pub struct Config {
+    pub theme: Theme,
+    /// New points cycle through these
+    pub point_colors: Vec<Color>,
+    pub toggles: Toggles,
+    pub keys: Keymap,
+    pub collider: ColliderOptions,
+    pub vehicle: VehicleOptions,
+    /// Pixels from the cursor within which a point dragged with ctrl snaps
+    pub snap_radius: f32
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            theme: Theme::default(),
+            point_colors: DEFAULT_POINT_COLORS.to_vec(),
+            toggles: Toggles::default(),
+            keys: Keymap::default(),
+            collider: ColliderOptions::default(),
+            vehicle: VehicleOptions::default(),
+            snap_radius: DEFAULT_SNAP_RADIUS
+        }
+    }
+}
+
+impl Config {
+    /// Config from the file at `path`, the defaults when there's no such file or no
+    /// filesystem at all, like in the browser
+    pub fn load(path: &str) -> Result<Self, ConfigError> {
+        match fs::read_to_string(path) {
+            Ok(text) => Self::parse(&text),
+            Err(error) if matches!(error.kind(), io::ErrorKind::NotFound | io::ErrorKind::Unsupported) => Ok(Self::default()),
+            Err(error) => Err(ConfigError::Io(error))
+        }
+    }
+
+    pub fn parse(text: &str) -> Result<Self, ConfigError> {
+        let file: ConfigFile = toml::from_str(text).map_err(ConfigError::Parse)?;
+        let mut config = Config::default();
+
+        if let Some(name) = file.theme {
+            config.theme = Theme::named(&name).ok_or(ConfigError::UnknownTheme(name))?;
+        }
+
+        let color = |value: &String| parse_color(value).ok_or_else(|| ConfigError::BadColor(value.clone()));
+        for (name, value) in &file.colors {
+            let color = color(value)?;
+            let theme = &mut config.theme;
+            let slot = match name.as_str() {
+                "background" => &mut theme.background,
+                "grid_major" => &mut theme.grid_major,
+                "grid_minor" => &mut theme.grid_minor,
+                "axis" => &mut theme.axis,
+                "bounding_box" => &mut theme.bounding_box,
+                "tight_box" => &mut theme.tight_box,
+                "oriented_box" => &mut theme.oriented_box,
+                "convex_hull" => &mut theme.convex_hull,
+                _ => return Err(ConfigError::UnknownColor(name.clone()))
+            };
+            *slot = color;
+        }
+
+        if !file.point_colors.is_empty() {
+            config.point_colors = file.point_colors.iter().map(color).collect::<Result<_, _>>()?;
+        }
+
+        config.toggles = file.toggles;
+        for (action, key) in &file.keys {
+            config.keys.bind(action, key)?;
+        }
+
+        let positive = |name: &'static str, value: f32| match value > 0. {
+            true => Ok(value),
+            false => Err(ConfigError::NotPositive(name, value))
+        };
+        if let Some(tolerance) = file.collider.tolerance {
+            config.collider.tolerance = positive("collider tolerance", tolerance)?;
+        }
+        if let Some(name) = file.collider.winding {
+            config.collider.winding = ChainWinding::named(&name).ok_or(ConfigError::UnknownWinding(name))?;
+        }
+        let vehicle = &mut config.vehicle;
+        for (name, value, slot) in [
+            ("vehicle wheelbase", file.vehicle.wheelbase, &mut vehicle.wheelbase),
+            ("vehicle min_turn_radius", file.vehicle.min_turn_radius, &mut vehicle.min_turn_radius),
+            ("vehicle speed", file.vehicle.speed, &mut vehicle.speed)
+        ] {
+            if let Some(value) = value {
+                *slot = positive(name, value)?;
+            }
+        }
+        if let Some(radius) = file.snap.radius {
+            config.snap_radius = positive("snap radius", radius)?;
+        }
+
+        Ok(config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn key_names() {
+        assert_eq!(parse_key("f1"), Some(KeyCode::F1));
+        assert_eq!(parse_key("F12"), Some(KeyCode::F12));
+        assert_eq!(parse_key("kp5"), Some(KeyCode::Kp5));
+        assert_eq!(parse_key(","), Some(KeyCode::Comma));
+        assert_eq!(parse_key("comma"), Some(KeyCode::Comma));
+        assert_eq!(parse_key("b"), Some(KeyCode::B));
+        for name in ["f0", "f13", "kp10", "hyper", ""] {
+            assert_eq!(parse_key(name), None);
+        }
+
+        // Hints name every key so it reads back as the same one
+        for &(_, code) in DEFAULT_BINDINGS {
+            assert_eq!(key_name(code).and_then(|name| parse_key(&name)), Some(code));
+        }
+    }
+
+    #[test]
+    fn bindings() {
+        let config = Config::parse("[keys]\ngrid = \"kp5\"\nhalve_unit = \"f1\"").unwrap();
+        assert_eq!(config.keys.key("grid"), KeyCode::Kp5);
+        assert_eq!(config.keys.label("halve_unit"), "f1");
+        assert_eq!(config.keys.key("info"), KeyCode::I);
+
+        assert!(matches!(Config::parse("[keys]\ngrid = \"hyper\""), Err(ConfigError::UnknownKey(key)) if key == "hyper"));
+        assert!(matches!(Config::parse("[keys]\nfly = \"g\""), Err(ConfigError::UnknownAction(action)) if action == "fly"));
+    }
+
+    #[test]
+    fn colors() {
+        assert_eq!(parse_color("#ff8000"), Some(Color::from_rgba(255, 128, 0, 255)));
+        assert_eq!(parse_color("#ff800040"), Some(Color::from_rgba(255, 128, 0, 64)));
+        for bad in ["ff8000", "#ff80", "#ff80001", "#gg8000", "#ff8000ééé"] {
+            assert_eq!(parse_color(bad), None);
+        }
+
+        let config = Config::parse("point_colors = [\"#000000\"]\n[colors]\naxis = \"#ffffff80\"").unwrap();
+        assert_eq!(config.point_colors, [Color::from_rgba(0, 0, 0, 255)]);
+        assert_eq!(config.theme.axis, Color::from_rgba(255, 255, 255, 128));
+        assert!(matches!(Config::parse("[colors]\naxis = \"red\""), Err(ConfigError::BadColor(_))));
+        assert!(matches!(Config::parse("[colors]\nsky = \"#ffffff\""), Err(ConfigError::UnknownColor(_))));
+    }
+
+    #[test]
+    fn settings_have_to_be_positive() {
+        let config = Config::parse("[snap]\nradius = 4.5").unwrap();
+        assert_eq!(config.snap_radius, 4.5);
+        assert!(matches!(Config::parse("[snap]\nradius = 0.0"), Err(ConfigError::NotPositive("snap radius", _))));
+        assert!(matches!(Config::parse("[vehicle]\nspeed = -2.0"), Err(ConfigError::NotPositive("vehicle speed", _))));
+    }
+
+    #[test]
+    fn unknown_fields_are_rejected() {
+        assert!(Config::parse("").is_ok());
+        assert!(matches!(Config::parse("colour = \"#ffffff\""), Err(ConfigError::Parse(_))));
+        assert!(matches!(Config::parse("[toggles]\ngird = true"), Err(ConfigError::Parse(_))));
+        assert!(matches!(Config::parse("[snap]\nradius = 4.0\nstrength = 1.0"), Err(ConfigError::Parse(_))));
+    }
+}
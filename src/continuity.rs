@@ -0,0 +1,118 @@
+use macroquad::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::point::Point;
+
+/// Smoothness class of the join between two bezier segments
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Continuity {
+    /// Only the anchor is shared
+    #[default]
+    C0,
+    /// Both handles are collinear with the anchor, same tangent direction
+    G1,
+    /// Handles mirror each other, same first derivative
+    C1,
+    /// Same first and second derivatives
+    C2
+}
+
+impl Continuity {
+    pub fn next(self) -> Self {
+        match self {
+            Continuity::C0 => Continuity::G1,
+            Continuity::G1 => Continuity::C1,
+            Continuity::C1 => Continuity::C2,
+            Continuity::C2 => Continuity::C0
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Continuity::C0 => "C0",
+            Continuity::G1 => "G1",
+            Continuity::C1 => "C1",
+            Continuity::C2 => "C2"
+        }
+    }
+}
+
+/// Highest class the join at `control[anchor]` currently satisfies. `anchor` must be
+/// an interior anchor (a multiple of 3 with a segment on each side)
+pub fn measure(control: &[Point], anchor: usize) -> Continuity {
+    let p = |i: usize| control[i].pos;
+    let (incoming, outgoing) = (p(anchor) - p(anchor - 1), p(anchor + 1) - p(anchor));
+
+    let collinear = incoming.normalize_or_zero().perp_dot(outgoing.normalize_or_zero()).abs() < 1e-3
+        && incoming.dot(outgoing) > 0.;
+    if !collinear { return Continuity::C0 };
+    if incoming.distance(outgoing) > 0.5 { return Continuity::G1 };
+
+    let left = p(anchor - 2) - 2. * p(anchor - 1) + p(anchor);
+    let right = p(anchor) - 2. * p(anchor + 1) + p(anchor + 2);
+    if left.distance(right) > 1. { return Continuity::C1 };
+
+    Continuity::C2
+}
+
+/// Move the handles on one side of the join at `control[anchor]` so it satisfies
+/// `continuity`. `fix_right` picks which side follows, the other one is kept as is. Locked
+/// points on the following side stay where they are, leaving the join short of `continuity`
+pub fn enforce(control: &mut [Point], anchor: usize, continuity: Continuity, fix_right: bool) {
+    // Mirror the index around the anchor so both sides share the same code
+    let side = |offset: usize, right: bool| if right { anchor + offset } else { anchor - offset };
+    let (driver, follower) = (!fix_right, fix_right);
+    let a = control[anchor].pos;
+    let driver_handle = control[side(1, driver)].pos;
+    let set = |control: &mut [Point], i: usize, pos: Vec2| if !control[i].locked { control[i].pos = pos };
+
+    match continuity {
+        Continuity::C0 => (),
+        Continuity::G1 => {
+            let direction = (a - driver_handle).normalize_or_zero();
+            let length = control[side(1, follower)].pos.distance(a);
+            if direction != Vec2::ZERO {
+                set(control, side(1, follower), a + direction * length);
+            }
+        },
+        Continuity::C1 | Continuity::C2 => {
+            let mirrored = 2. * a - driver_handle;
+            set(control, side(1, follower), mirrored);
+
+            // Matching B'' at the join: P[-2] - 2P[-1] + P[0] = P[0] - 2P[1] + P[2]
+            if continuity == Continuity::C2 {
+                let far = control[side(2, driver)].pos;
+                set(control, side(2, follower), far - 4. * driver_handle + 4. * a);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn points(positions: &[Vec2]) -> Vec<Point> {
+        positions.iter().map(|&pos| Point::new(pos, WHITE)).collect()
+    }
+
+    #[test]
+    fn c1_mirrors_the_following_handle() {
+        let mut control = points(&[vec2(0., 0.), vec2(1., 1.), vec2(2., 1.), vec2(3., 0.), vec2(5., 5.), vec2(6., 1.), vec2(7., 0.)]);
+        enforce(&mut control, 3, Continuity::C1, true);
+        assert_eq!(control[4].pos, vec2(4., -1.));
+        assert_eq!(measure(&control, 3), Continuity::C1);
+    }
+
+    #[test]
+    fn locked_handles_are_left_alone() {
+        let mut control = points(&[vec2(0., 0.), vec2(1., 1.), vec2(2., 1.), vec2(3., 0.), vec2(5., 5.), vec2(6., 1.), vec2(7., 0.)]);
+        control[4].locked = true;
+        for continuity in [Continuity::G1, Continuity::C1, Continuity::C2] {
+            enforce(&mut control, 3, continuity, true);
+            assert_eq!(control[4].pos, vec2(5., 5.));
+        }
+        // The free handle past it still follows for C2
+        assert_ne!(control[5].pos, vec2(6., 1.));
+    }
+}
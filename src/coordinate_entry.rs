@@ -0,0 +1,110 @@
+use macroquad::prelude::*;
+
+use crate::coords::CoordinateSystem;
+use crate::curve::Curve;
+use crate::hud::draw_text_box;
+
+/// Text field for typing exact coordinates of a control point, as "x, y" or "x y", or the
+/// length and angle of a handle from its anchor the same way
+#[derive(Default)]
+pub struct CoordinateEntry {
+    target: Option<usize>,
+    polar: bool,
+    text: String,
+    error: Option<String>
+}
+
+/// Two numbers separated by a comma, semicolon or whitespace
+pub fn parse_coordinates(text: &str) -> Option<Vec2> {
+    let numbers: Vec<f32> = text.split(|c: char| c == ',' || c == ';' || c.is_whitespace())
+        .filter(|part| !part.is_empty())
+        .map(|part| part.parse::<f32>().ok())
+        .collect::<Option<_>>()?;
+
+    match numbers[..] {
+        [x, y] if x.is_finite() && y.is_finite() => Some(vec2(x, y)),
+        _ => None
+    }
+}
+
+impl CoordinateEntry {
+    /// Start editing point `id` at `pos` in the user's coordinates, the field starts with them
+    pub fn open(&mut self, id: usize, pos: Vec2) {
+        self.target = Some(id);
+        self.polar = false;
+        self.text = format!("{:.2}, {:.2}", pos.x, pos.y);
+        self.error = None;
+        // Drop whatever was typed before the field opened
+        while get_char_pressed().is_some() {}
+    }
+
+    /// Start editing handle `id` by its length in units and angle in degrees from its anchor
+    pub fn open_polar(&mut self, id: usize, length: f32, angle: f32) {
+        self.open(id, vec2(length, angle));
+        self.polar = true;
+    }
+
+    pub fn close(&mut self) {
+        self.target = None;
+    }
+
+    /// While open the keyboard belongs to the field, shortcuts shouldn't fire
+    pub fn is_open(&self) -> bool {
+        self.target.is_some()
+    }
+
+    /// Read typed characters, enter moves the point to the typed `coords` and escape cancels
+    pub fn update(&mut self, curve: &mut Curve, coords: &CoordinateSystem) {
+        let Some(id) = self.target else { return };
+        if id >= curve.control.len() {
+            self.close();
+            return;
+        }
+
+        // macroquad hands out the queued characters newest first
+        let mut typed: Vec<char> = std::iter::from_fn(get_char_pressed).collect();
+        typed.reverse();
+        self.text.extend(typed.into_iter().filter(|&c| c.is_ascii_digit() || "+-.,; e".contains(c)));
+        if is_key_pressed(KeyCode::Backspace) {
+            self.text.pop();
+        }
+
+        if is_key_pressed(KeyCode::Escape) {
+            self.close();
+        } else if is_key_pressed(KeyCode::Enter) || is_key_pressed(KeyCode::KpEnter) {
+            let anchor = curve.handle_anchor(id).map(|anchor| coords.to_math(curve.control[anchor].pos));
+            let typed = parse_coordinates(&self.text);
+            let pos = match (self.polar, anchor) {
+                (true, Some(anchor)) => typed.map(|polar| anchor + Vec2::from_angle(polar.y.to_radians()) * polar.x),
+                (true, None) => {
+                    self.close();
+                    return;
+                },
+                (false, _) => typed
+            };
+            match pos {
+                Some(pos) => {
+                    curve.move_point(id, coords.from_math(pos), false);
+                    self.close();
+                },
+                None => self.error = Some("expected two numbers, like 120, -45.5".to_string())
+            }
+        }
+    }
+
+    pub fn draw(&self) {
+        let Some(id) = self.target else { return };
+        let field = match self.polar {
+            true => format!("handle #{} length, angle: {}_", id, self.text),
+            false => format!("point #{} x, y: {}_", id, self.text)
+        };
+        let mut lines = vec![
+            field,
+            "enter to apply, esc to cancel".to_string()
+        ];
+        if let Some(error) = &self.error {
+            lines.push(error.clone());
+        }
+        draw_text_box(&lines, vec2(10., screen_height() - 200.));
+    }
+}
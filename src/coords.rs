@@ -0,0 +1,99 @@
+use macroquad::prelude::*;
+
+use crate::analysis::ShapeProperties;
+use crate::curve::CurveMetrics;
+
+pub const DEFAULT_PIXELS_PER_UNIT: f32 = 50.0;
+pub const MIN_PIXELS_PER_UNIT: f32 = 0.01;
+pub const MAX_PIXELS_PER_UNIT: f32 = 10000.0;
+
+/// Smallest 1, 2 or 5 times a power of ten that is at least `min_step`, for ticks and grid
+/// lines that land on round numbers
+pub fn nice_step(min_step: f32) -> f32 {
+    let magnitude = 10f32.powf(min_step.max(f32::MIN_POSITIVE).log10().floor());
+    [1., 2., 5., 10.].into_iter()
+        .map(|m| m * magnitude)
+        .find(|&step| step >= min_step)
+        .unwrap_or(10. * magnitude)
+}
+
+/// `value` with as many decimals as `step` needs, so neighbouring labels differ
+pub fn tick_label(value: f32, step: f32) -> String {
+    let decimals = (0..6)
+        .find(|&d| {
+            let scaled = step * 10f32.powi(d);
+            (scaled - scaled.round()).abs() < 1e-3 * scaled.max(1.)
+        })
+        .unwrap_or(6) as usize;
+    format!("{:.*}", decimals, value)
+}
+
+/// Mathematical coordinates shown to the user and exported, as opposed to the world
+/// positions curves are stored in (canvas pixels with y down). `origin` is the world
+/// position of (0, 0) and `pixels_per_unit` how many world pixels one unit spans
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CoordinateSystem {
+    pub origin: Vec2,
+    pub pixels_per_unit: f32,
+    pub y_up: bool
+}
+
+impl Default for CoordinateSystem {
+    fn default() -> Self {
+        Self {
+            origin: vec2(screen_width() / 2., screen_height() / 2.),
+            pixels_per_unit: DEFAULT_PIXELS_PER_UNIT,
+            y_up: true
+        }
+    }
+}
+
+impl CoordinateSystem {
+    fn flip(&self) -> Vec2 {
+        match self.y_up {
+            true => vec2(1., -1.),
+            false => vec2(1., 1.)
+        }
+    }
+
+    /// World position to coordinates
+    pub fn to_math(&self, world: Vec2) -> Vec2 {
+        (world - self.origin) * self.flip() / self.pixels_per_unit
+    }
+
+    /// Coordinates to world position
+    pub fn from_math(&self, math: Vec2) -> Vec2 {
+        self.origin + math * self.flip() * self.pixels_per_unit
+    }
+
+    /// World distance in units, also for sizes and displacements' lengths
+    pub fn length(&self, world: f32) -> f32 {
+        world / self.pixels_per_unit
+    }
+
+    /// Multiply the units scale by `factor`, kept within the allowed range
+    pub fn scale_units(&mut self, factor: f32) {
+        self.pixels_per_unit = (self.pixels_per_unit * factor).clamp(MIN_PIXELS_PER_UNIT, MAX_PIXELS_PER_UNIT);
+    }
+
+    /// The same metrics measured in units. Flipping y reverses the orientation, so the
+    /// signed area and the product of inertia change sign
+    pub fn metrics(&self, metrics: &CurveMetrics) -> CurveMetrics {
+        let sign = self.flip().y;
+        let (l, l2, l4) = (self.pixels_per_unit, self.pixels_per_unit.powi(2), self.pixels_per_unit.powi(4));
+
+        CurveMetrics {
+            length: metrics.length / l,
+            segment_lengths: metrics.segment_lengths.iter().map(|length| length / l).collect(),
+            max_curvature: metrics.max_curvature * l,
+            bounds_size: metrics.bounds_size.map(|size| size / l),
+            shape: metrics.shape.map(|shape| ShapeProperties {
+                signed_area: shape.signed_area * sign / l2,
+                centroid: self.to_math(shape.centroid),
+                ixx: shape.ixx / l4,
+                iyy: shape.iyy / l4,
+                ixy: shape.ixy * sign / l4
+            })
+        }
+    }
+}
@@ -0,0 +1,901 @@
+use std::borrow::Cow;
+use std::ops::Range;
+
+use macroquad::prelude::*;
+
+use crate::analysis::{shape_properties, winding_number, ShapeProperties};
+use crate::bspline::BSpline;
+use crate::continuity::{self, Continuity};
+use crate::coords::CoordinateSystem;
+use crate::elevation::Elevation;
+use crate::error::SplineError;
+use crate::interval::{conservative_box, INTERVAL_BOX_PIECES};
+use crate::math::*;
+use crate::point::{Point, CONTROLPOINT_RADIUS};
+use crate::profiler::count_draw_calls;
+use crate::segment::{CubicSegment, SegmentCache};
+use crate::settings::{Evaluator, RenderSettings};
+use crate::stroke::{draw_polyline, draw_smooth_polyline, LineJoin};
+
+pub struct BoundingBox {
+    pub point_min: Vec2,
+    pub point_max: Vec2,
+    pub point_color: Color,
+    pub outline_color: Color
+}
+
+impl BoundingBox {
+    pub fn draw(&self) {
+        let (pmin, pmax) = (self.point_min, self.point_max);
+
+        draw_circle(pmin.x, pmin.y, 5.0, self.point_color);
+        draw_circle(pmax.x, pmax.y, 5.0, self.point_color);
+
+
+        draw_line(pmin.x, pmin.y, pmin.x, pmax.y, 1., self.outline_color);
+        draw_line(pmax.x, pmin.y, pmax.x, pmax.y, 1., self.outline_color);
+
+        draw_line(pmin.x, pmin.y, pmax.x, pmin.y, 1., self.outline_color);
+        draw_line(pmin.x, pmax.y, pmax.x, pmax.y, 1., self.outline_color);
+    }
+}
+
+/// Numbers describing a curve, shown by the HUD
+pub struct CurveMetrics {
+    pub length: f32,
+    pub segment_lengths: Vec<f32>,
+    pub max_curvature: f32,
+    pub bounds_size: Option<Vec2>,
+    pub shape: Option<ShapeProperties>
+}
+
+#[derive(Default)]
+pub struct Curve {
+    pub control: Vec<Point>,
+    pub rendered: Vec<Point>,
+    /// Pieces of `rendered` drawn as one line each, split where off screen segments
+    /// weren't sampled
+    pub runs: Vec<Range<usize>>,
+    pub boxes: Vec<BoundingBox>,
+    pub hulls: Vec<Vec<Vec2>>,
+    pub oriented_box: Option<[Vec2; 4]>,
+    pub closed: bool,
+    /// When set the control points are a cubic B-spline's control polygon instead of
+    /// bezier segments, with these knots. Reset to uniform when the point count changes
+    pub knots: Option<Vec<f32>>,
+    /// Enforced continuity of each join, indexed by anchor (control index / 3). Missing
+    /// entries are C0, which leaves the handles free
+    pub continuity: Vec<Continuity>,
+    /// Index into the document's layers
+    pub layer: usize,
+    /// Curves with the same group id are moved, scaled and duplicated together
+    pub group: Option<usize>,
+    /// Drawn along the curve when not empty
+    pub text: String,
+    /// Pixels between the curve and the text's baseline, positive to the left of the curve
+    pub text_offset: f32,
+    /// Height over arc length, for roads over terrain and level profiles
+    pub elevation: Option<Elevation>,
+    /// World rectangle segments were culled against when sampling, None if none were
+    pub sampled_view: Option<[Vec2; 2]>,
+    /// Coefficients and arc length tables of every segment as of the last render, stale
+    /// while `modified` is set
+    pub cache: Vec<SegmentCache>,
+    pub modified: bool
+}
+
+// Ranges of the polyline whose lines touch `visible`, each keeping the points just outside
+// it so the lines still run off the edge
+fn visible_pieces(points: &[Point], visible: [Vec2; 2]) -> Vec<Range<usize>> {
+    let mut pieces: Vec<Range<usize>> = Vec::new();
+    for (i, pair) in points.windows(2).enumerate() {
+        let (a, b) = (pair[0].pos, pair[1].pos);
+        if !boxes_overlap([a.min(b), a.max(b)], visible) { continue };
+        match pieces.last_mut() {
+            Some(piece) if piece.end == i + 1 => piece.end = i + 2,
+            _ => pieces.push(i..i + 2)
+        }
+    }
+
+    pieces
+}
+
+impl Curve {
+    pub fn bounding_box(points: &[Point]) -> [Vec2; 2] {
+        let (mut min_x, mut min_y) = (f32::MAX, f32::MAX);
+        let (mut max_x, mut max_y) = (f32::MIN, f32::MIN);
+
+        for p in points {
+            min_x = min_x.min(p.pos.x);
+            max_x = max_x.max(p.pos.x);
+
+            min_y = min_y.min(p.pos.y);
+            max_y = max_y.max(p.pos.y);
+        }
+
+        [vec2(min_x, min_y), vec2(max_x, max_y)]
+    }
+
+    /// Points where B'(t) is zero on either axis, labeled by axis and root, used as tight box candidates
+    pub fn extrema(points: &[Point]) -> Vec<(&'static str, Vec2)> {
+        let mut candidates = Vec::new();
+
+        let xs = [points[0].pos.x, points[1].pos.x, points[2].pos.x, points[3].pos.x];
+        if let Some((tx0, tx1)) = solve_quadratic(xs) {
+            if (0.0..1.0).contains(&tx0) { candidates.push(("x0", cubic_bezier(tx0, points))) };
+            if (0.0..1.0).contains(&tx1) { candidates.push(("x1", cubic_bezier(tx1, points))) };
+        }
+
+        let ys = [points[0].pos.y, points[1].pos.y, points[2].pos.y, points[3].pos.y];
+        if let Some((ty0, ty1)) = solve_quadratic(ys) {
+            if (0.0..1.0).contains(&ty0) { candidates.push(("y0", cubic_bezier(ty0, points))) };
+            if (0.0..1.0).contains(&ty1) { candidates.push(("y1", cubic_bezier(ty1, points))) };
+        }
+
+        candidates
+    }
+
+    /// Take the derivative on each eaxis then build by comparing with start_anchor and end_anchor points
+    pub fn tight_bounding_box(points: &[Point]) -> [Vec2; 2] {
+        let [mut pmin, mut pmax] = Curve::bounding_box(&[points[0], points[3]]);
+
+        for (label, candidate) in Curve::extrema(points) {
+            if label.starts_with('x') {
+                pmin.x = pmin.x.min(candidate.x);
+                pmax.x = pmax.x.max(candidate.x);
+            } else {
+                pmin.y = pmin.y.min(candidate.y);
+                pmax.y = pmax.y.max(candidate.y);
+            }
+        }
+
+        [pmin, pmax]
+    }
+
+    /// Minimum-area box around the curve flattened within `tolerance`, not aligned to the axes
+    pub fn oriented_bounding_box(&self, tolerance: f32) -> Option<[Vec2; 4]> {
+        let positions: Vec<Vec2> = self.flatten(tolerance).iter().map(|p| p.pos).collect();
+        min_area_rect(&positions)
+    }
+
+    /// Control points of every complete segment, B-splines are split into their bezier
+    /// pieces. Closed curves get an extra straight segment from the last anchor back to
+    /// the first one, like SVG's `Z`
+    pub fn segments(&self) -> Vec<[Point; 4]> {
+        let mut segments: Vec<[Point; 4]> = match self.bspline() {
+            Some(spline) => spline.to_bezier(),
+            None => self.control.windows(4).step_by(3).map(|w| [w[0], w[1], w[2], w[3]]).collect()
+        };
+
+        if self.closed && !segments.is_empty() {
+            let (start, end) = (self.control[0], segments[segments.len() - 1][3]);
+            if start.pos != end.pos {
+                segments.push([end, end.lerp(&start, 1. / 3.), end.lerp(&start, 2. / 3.), start]);
+            }
+        }
+
+        segments
+    }
+
+    /// The cached segments, or freshly computed ones while the cache is stale
+    pub fn segment_caches(&self) -> Cow<'_, [SegmentCache]> {
+        match self.modified || self.cache.is_empty() {
+            true => Cow::Owned(self.segments().into_iter().map(SegmentCache::new).collect()),
+            false => Cow::Borrowed(&self.cache)
+        }
+    }
+
+    // Redo the cache of the segments whose control points changed, keeping the rest
+    fn refresh_cache(&mut self) {
+        let mut old: Vec<Option<SegmentCache>> = std::mem::take(&mut self.cache).into_iter().map(Some).collect();
+        self.cache = self.segments().into_iter().enumerate()
+            .map(|(i, segment)| {
+                match old.get_mut(i).and_then(Option::take).filter(|cache| cache.matches(&segment)) {
+                    Some(cache) => SegmentCache { points: segment, ..cache },
+                    None => SegmentCache::new(segment)
+                }
+            })
+            .collect();
+    }
+
+    pub fn metrics(&self) -> CurveMetrics {
+        let segments = self.segments();
+        let segment_lengths: Vec<f32> = self.segment_caches().iter().map(SegmentCache::length).collect();
+
+        // Sampled, since the true maximum needs the roots of a high degree polynomial
+        let max_curvature = segments.iter()
+            .flat_map(|&s| (0..=200).map(move |t| curvature(s, t as f32 / 200.).abs()))
+            .fold(0., f32::max);
+
+        CurveMetrics {
+            length: segment_lengths.iter().sum(),
+            segment_lengths,
+            max_curvature,
+            bounds_size: self.bounds().map(|[pmin, pmax]| pmax - pmin),
+            shape: self.shape()
+        }
+    }
+
+    /// Area and centroid, only defined for closed curves
+    pub fn shape(&self) -> Option<ShapeProperties> {
+        if !self.closed { return None };
+        shape_properties(&self.segments())
+    }
+
+    /// Winding number of `p` with respect to this curve, always zero for open curves
+    pub fn winding_number(&self, p: Vec2) -> i32 {
+        if !self.closed { return 0 };
+        winding_number(&self.segments(), p)
+    }
+
+    /// The cubic B-spline described by the control points, when the curve is in B-spline mode
+    pub fn bspline(&self) -> Option<BSpline> {
+        let knots = self.knots.as_ref()?;
+        BSpline::new(3, self.control.clone(), knots.clone())
+            .or_else(|| BSpline::clamped_uniform(3, self.control.clone()))
+    }
+
+    /// Switch between bezier segments and a cubic B-spline keeping the shape, knots are
+    /// merged wherever the joins are smooth enough. Curves with a trailing incomplete
+    /// segment can't be converted exactly, so their points are reused as a uniform B-spline
+    pub fn toggle_bspline(&mut self) {
+        match self.bspline() {
+            Some(spline) => {
+                let segments = spline.to_bezier();
+                if let Some(first) = segments.first() {
+                    self.control = first.to_vec();
+                    for segment in &segments[1..] {
+                        self.control.extend_from_slice(&segment[1..]);
+                    }
+                }
+                self.knots = None;
+            },
+            None => {
+                let windows: Vec<[Point; 4]> = self.control.windows(4).step_by(3)
+                    .map(|w| [w[0], w[1], w[2], w[3]])
+                    .collect();
+
+                let exact = (self.control.len() % 3 == 1)
+                    .then(|| BSpline::from_bezier(&windows, 0.01))
+                    .flatten();
+
+                match exact {
+                    Some(spline) => {
+                        self.control = spline.control;
+                        self.knots = Some(spline.knots);
+                    },
+                    // Empty knots fall back to uniform ones in `bspline`
+                    None => self.knots = Some(Vec::new())
+                }
+            }
+        }
+        // Merged knots and split segments leave the joins in different places
+        self.continuity.clear();
+        self.modified = true;
+    }
+
+    /// Swap a B-spline's knots between clamped and unclamped uniform ones
+    pub fn toggle_clamped(&mut self) {
+        let Some(spline) = self.bspline() else { return };
+        let toggled = match spline.is_clamped() {
+            true => BSpline::unclamped_uniform(3, spline.control),
+            false => BSpline::clamped_uniform(3, spline.control)
+        };
+
+        self.knots = toggled.map(|s| s.knots);
+        self.modified = true;
+    }
+
+    /// Insert a knot at the curve point closest to `p` if it's within `radius`, refining
+    /// the B-spline without changing its shape. Returns whether a knot was inserted
+    pub fn insert_knot_near(&mut self, p: Vec2, radius: f32) -> bool {
+        let Some(mut spline) = self.bspline() else { return false };
+        let (u, distance) = spline.closest_param(p);
+        if distance > radius || !spline.insert_knot(u) { return false };
+
+        self.control = spline.control;
+        self.knots = Some(spline.knots);
+        self.modified = true;
+
+        true
+    }
+
+    /// Treat every control point as an anchor and generate the handles of the natural C2
+    /// spline through them, so a curve can be drawn by clicking only the points it passes by
+    pub fn auto_smooth(&mut self) {
+        let anchors: Vec<Vec2> = self.control.iter().map(|p| p.pos).collect();
+        let handles = smooth_handles(&anchors);
+        if handles.is_empty() { return };
+
+        let mut control = Vec::with_capacity(handles.len() * 3 + 1);
+        for (pair, (first, second)) in self.control.windows(2).zip(handles) {
+            let (a, b) = (pair[0], pair[1]);
+            control.push(a);
+            control.push(Point::new(first, a.lerp(&b, 1. / 3.).color));
+            control.push(Point::new(second, a.lerp(&b, 2. / 3.).color));
+        }
+        control.push(self.control[self.control.len() - 1]);
+
+        self.control = control;
+        self.knots = None;
+        // Every point is an anchor now, so the join that was at point 3a becomes join 3a
+        self.continuity = self.continuity.iter().flat_map(|&c| [c, Continuity::C0, Continuity::C0]).collect();
+        self.modified = true;
+    }
+
+    /// Segment closest to `p` within `radius`, with the parameter of the closest point on it
+    pub fn pick_segment(&self, p: Vec2, radius: f32) -> Option<(usize, f32)> {
+        self.segment_caches().iter().enumerate()
+            .map(|(i, segment)| {
+                let (t, pos) = segment.project(p);
+                (i, t, pos.distance(p))
+            })
+            .filter(|&(_, _, distance)| distance <= radius)
+            .min_by(|a, b| a.2.total_cmp(&b.2))
+            .map(|(i, t, _)| (i, t))
+    }
+
+    /// Drag control point `id` to `pos`. With `carry_handles` an anchor takes its handles
+    /// along so the tangents at it keep their shape
+    pub fn move_point(&mut self, id: usize, pos: Vec2, carry_handles: bool) {
+        if self.control[id].locked { return };
+        let delta = pos - self.control[id].pos;
+        self.control[id].pos = pos;
+        if carry_handles && self.knots.is_none() && id.is_multiple_of(3) {
+            let handles = [id.checked_sub(1), Some(id + 1).filter(|&h| h < self.control.len())];
+            for handle in handles.into_iter().flatten() {
+                let point = &mut self.control[handle];
+                if !point.locked { point.pos += delta };
+            }
+        }
+        self.enforce_continuity(id);
+        self.modified = true;
+    }
+
+    /// Tooltip lines for control point `id`: its index, role, position in `coords` and the
+    /// segments it shapes. A B-spline point shapes the parameter range its basis function covers
+    pub fn describe_point(&self, id: usize, coords: &CoordinateSystem) -> Vec<String> {
+        let point = &self.control[id];
+        let pos = coords.to_math(point.pos);
+        let mut lines = vec![format!("point #{} ({:.2}, {:.2})", id, pos.x, pos.y)];
+
+        if let Some(spline) = self.bspline() {
+            let (start, end) = (spline.knots[id], spline.knots[id + spline.degree + 1]);
+            lines.push("B-spline control point".to_string());
+            lines.push(format!("shapes u in [{:.2}, {:.2}]", start, end));
+        } else {
+            let complete = self.control.len().saturating_sub(1) / 3;
+            let mut segments: Vec<usize> = match id.is_multiple_of(3) {
+                true => [id.checked_sub(3), Some(id)].into_iter().flatten().map(|start| start / 3).collect(),
+                false => vec![id / 3]
+            };
+            segments.retain(|&segment| segment < complete);
+            // The closing segment runs from the last anchor back to the first
+            if self.closed && complete > 0 && (id == 0 || id == complete * 3) {
+                segments.push(complete);
+            }
+
+            let role = match id % 3 {
+                0 => "anchor",
+                1 => "outgoing handle",
+                _ => "incoming handle"
+            };
+            lines.push(role.to_string());
+            if let Some((length, angle)) = self.handle_polar(id, coords) {
+                lines.push(format!("length {:.2}, angle {:.1}° from its anchor", length, angle));
+            }
+            let names: Vec<String> = segments.iter().map(|s| format!("#{}", s)).collect();
+            lines.push(match names.len() {
+                0 => "not in a complete segment yet".to_string(),
+                1 => format!("segment {}", names[0]),
+                _ => format!("segments {}", names.join(", "))
+            });
+        }
+        if point.locked {
+            lines.push("locked".to_string());
+        }
+
+        lines
+    }
+
+    /// Anchor the bezier handle `id` hangs from, None for anchors, B-spline control points
+    /// and an incoming handle whose anchor isn't placed yet
+    pub fn handle_anchor(&self, id: usize) -> Option<usize> {
+        if self.knots.is_some() || id.is_multiple_of(3) { return None };
+        let anchor = (id + 1) / 3 * 3;
+        (anchor < self.control.len()).then_some(anchor)
+    }
+
+    /// Length in units of handle `id` and its angle from its anchor in degrees, counterclockwise
+    /// from the x axis of the coordinate system
+    pub fn handle_polar(&self, id: usize, coords: &CoordinateSystem) -> Option<(f32, f32)> {
+        let anchor = self.handle_anchor(id)?;
+        let offset = coords.to_math(self.control[id].pos) - coords.to_math(self.control[anchor].pos);
+        Some((offset.length(), offset.y.atan2(offset.x).to_degrees()))
+    }
+
+    /// Remove control point `id` unless it's locked, returns whether it was removed
+    pub fn remove_point(&mut self, id: usize) -> bool {
+        if self.control[id].locked { return false };
+        self.control.remove(id);
+        // Every point after it moves down one place, so the joins there no longer line up
+        self.continuity.truncate(id / 3);
+        self.modified = true;
+        true
+    }
+
+    /// Move every control point by `delta`, except the locked ones
+    pub fn translate(&mut self, delta: Vec2) {
+        for point in self.control.iter_mut().filter(|point| !point.locked) {
+            point.pos += delta;
+        }
+        self.modified = true;
+    }
+
+    /// Scale every control point by `factor` around `center`, except the locked ones
+    pub fn scale_about(&mut self, center: Vec2, factor: f32) {
+        for point in self.control.iter_mut().filter(|point| !point.locked) {
+            point.pos = center + (point.pos - center) * factor;
+        }
+        self.modified = true;
+    }
+
+    /// Apply the affine `matrix` (translation, rotation, scale and shear) to every control
+    /// point, except the locked ones
+    pub fn transform(&mut self, matrix: Mat3) {
+        for point in self.control.iter_mut().filter(|point| !point.locked) {
+            point.pos = matrix.transform_point2(point.pos);
+        }
+        self.modified = true;
+    }
+
+    /// Copy of the curve's shape moved by `offset`, on the same layer and in the same group
+    pub fn duplicate(&self, offset: Vec2) -> Curve {
+        let control = self.control.iter().map(|p| Point { pos: p.pos + offset, ..*p }).collect();
+        Curve {
+            control,
+            closed: self.closed,
+            knots: self.knots.clone(),
+            continuity: self.continuity.clone(),
+            layer: self.layer,
+            group: self.group,
+            elevation: self.elevation.clone(),
+            modified: true,
+            ..Default::default()
+        }
+    }
+
+    /// Move a bezier segment's four control points by `delta`. The closing segment of a
+    /// closed curve has no handles of its own, so its two anchors move. Locked points stay
+    pub fn translate_segment(&mut self, segment: usize, delta: Vec2) {
+        if self.knots.is_some() { return };
+        let start = segment * 3;
+        let indices: Vec<usize> = match start + 3 < self.control.len() {
+            true => (start..=start + 3).collect(),
+            false if self.closed && start < self.control.len() => vec![start, 0],
+            false => return
+        };
+
+        for i in indices {
+            let point = &mut self.control[i];
+            if !point.locked { point.pos += delta };
+        }
+        self.modified = true;
+    }
+
+    /// Replace bezier segment `segment` with `pieces` sub-segments of the same shape, uniform
+    /// either in t or in arc length. B-splines and the closing segment can't be split
+    pub fn subdivide_segment(&mut self, segment: usize, pieces: usize, by_length: bool) {
+        let start = segment * 3;
+        if self.knots.is_some() || pieces < 2 || start + 3 >= self.control.len() { return };
+
+        let points = [self.control[start], self.control[start + 1], self.control[start + 2], self.control[start + 3]];
+        let split = match by_length {
+            true => subdivide_by_length(points, pieces),
+            false => subdivide(points, pieces)
+        };
+
+        let replacement: Vec<Point> = split.iter().flat_map(|s| s[1..].iter().copied()).collect();
+        self.control.splice(start + 1..=start + 3, replacement);
+
+        // The new joins are C0, later joins keep their settings but move further along
+        if self.continuity.len() > segment + 1 {
+            let new_joints = std::iter::repeat_n(Continuity::C0, pieces - 1);
+            self.continuity.splice(segment + 1..segment + 1, new_joints);
+        }
+        self.modified = true;
+    }
+
+    /// Whether `anchor` (index into `control`) joins two bezier segments
+    pub fn is_joint(&self, anchor: usize) -> bool {
+        self.knots.is_none() && anchor.is_multiple_of(3) && anchor >= 3 && anchor + 3 < self.control.len()
+    }
+
+    pub fn joint_continuity(&self, anchor: usize) -> Continuity {
+        self.continuity.get(anchor / 3).copied().unwrap_or_default()
+    }
+
+    /// Step the enforced continuity of the join at `anchor` to the next class and apply it
+    pub fn cycle_continuity(&mut self, anchor: usize) {
+        if !self.is_joint(anchor) { return };
+
+        let id = anchor / 3;
+        if self.continuity.len() <= id {
+            self.continuity.resize(id + 1, Continuity::C0);
+        }
+        self.continuity[id] = self.continuity[id].next();
+        self.enforce_continuity(anchor);
+    }
+
+    /// Clean up the kink at the joint of anchor `id`, or of the anchor handle `id` hangs from:
+    /// give both handles their average length, or with `collinear` turn both halfway to a
+    /// straight line through the anchor keeping their lengths. A locked handle stays and the
+    /// other one matches it. Returns whether there was a joint to fix
+    pub fn even_handles(&mut self, id: usize, collinear: bool) -> bool {
+        let anchor = self.handle_anchor(id).unwrap_or(id);
+        if !self.is_joint(anchor) { return false };
+
+        let a = self.control[anchor].pos;
+        let (before, after) = (self.control[anchor - 1], self.control[anchor + 1]);
+        let (incoming, outgoing) = (before.pos - a, after.pos - a);
+        let (incoming, outgoing) = match (collinear, before.locked, after.locked) {
+            (false, false, false) => {
+                let length = (incoming.length() + outgoing.length()) / 2.;
+                (incoming.normalize_or_zero() * length, outgoing.normalize_or_zero() * length)
+            },
+            (false, true, _) => (incoming, outgoing.normalize_or_zero() * incoming.length()),
+            (false, false, true) => (incoming.normalize_or_zero() * outgoing.length(), outgoing),
+            (true, false, false) => {
+                // Handles folded onto each other have no halfway direction, the outgoing one is kept
+                let direction = (outgoing.normalize_or_zero() - incoming.normalize_or_zero()).try_normalize()
+                    .unwrap_or(outgoing.normalize_or_zero());
+                (-direction * incoming.length(), direction * outgoing.length())
+            },
+            (true, true, _) => (incoming, -incoming.normalize_or_zero() * outgoing.length()),
+            (true, false, true) => (-outgoing.normalize_or_zero() * incoming.length(), outgoing)
+        };
+
+        self.control[anchor - 1].pos = a + incoming;
+        self.control[anchor + 1].pos = a + outgoing;
+        self.modified = true;
+        true
+    }
+
+    /// Re-apply the enforced continuity of every join next to the point that just moved,
+    /// the side that wasn't touched is the one that follows
+    pub fn enforce_continuity(&mut self, moved: usize) {
+        let anchors = [moved.saturating_sub(2), moved.saturating_sub(1), moved, moved + 1, moved + 2];
+        for anchor in anchors {
+            let continuity = self.joint_continuity(anchor);
+            if !self.is_joint(anchor) || continuity == Continuity::C0 { continue };
+
+            let fix_right = moved <= anchor;
+            continuity::enforce(&mut self.control, anchor, continuity, fix_right);
+            self.modified = true;
+        }
+    }
+
+    /// Polyline within `tolerance` of the whole curve
+    pub fn flatten(&self, tolerance: f32) -> Vec<Point> {
+        let mut points: Vec<Point> = Vec::new();
+        for segment in self.segments() {
+            let steps = flattening_steps(&segment, tolerance);
+            // Consecutive segments share their anchor
+            let skip = if points.is_empty() { 0 } else { 1 };
+            points.extend((skip..=steps).map(|i| decasteljau(&segment, i as f32 / steps as f32)));
+        }
+
+        points
+    }
+
+    /// Position and unit tangent `length` world units along the curve from its start, None
+    /// outside of it or without a complete segment
+    pub fn point_at_length(&self, length: f32) -> Option<(Vec2, Vec2)> {
+        if length < 0. { return None };
+        let mut travelled = 0.;
+        for segment in self.segment_caches().iter() {
+            let segment_length = segment.length();
+            if length <= travelled + segment_length {
+                let t = segment.param_at_length(length - travelled);
+                let tangent = segment.velocity(t).try_normalize().unwrap_or(Vec2::X);
+                return Some((segment.eval(t), tangent));
+            }
+            travelled += segment_length;
+        }
+
+        None
+    }
+
+    /// Union of every segment's tight bounding box, None if there's no complete segment
+    pub fn bounds(&self) -> Option<[Vec2; 2]> {
+        self.segments().iter()
+            .map(|s| Curve::tight_bounding_box(s))
+            .reduce(|[amin, amax], [bmin, bmax]| [amin.min(bmin), amax.max(bmax)])
+    }
+
+    pub fn render(&mut self, settings: &RenderSettings) {
+        info!("Rendering new curve!");
+        self.refresh_cache();
+        // A screen's worth of margin on every side, so panning a little doesn't resample
+        let view = settings.visible.map(|[min, max]| [min - (max - min), max + (max - min)]);
+        let mut culled = false;
+        let mut run_start = 0;
+        for (i, control_window) in self.segments().iter().enumerate() {
+            let [tight_min, tight_max] = match settings.interval_boxes {
+                true => conservative_box(*control_window, 0., 1., INTERVAL_BOX_PIECES),
+                false => Curve::tight_bounding_box(control_window)
+            };
+            let off_screen = view.is_some_and(|view| !boxes_overlap([tight_min, tight_max], view));
+            if off_screen {
+                if self.rendered.len() > run_start { self.runs.push(run_start..self.rendered.len()) };
+                run_start = self.rendered.len();
+                culled = true;
+            }
+
+            let steps = match settings.world_tolerance() {
+                Some(tolerance) => flattening_steps(control_window, tolerance),
+                None => {
+                    let polygon_length: f32 = control_window.windows(2).map(|w| w[0].pos.distance(w[1].pos)).sum();
+                    settings.samples_for(polygon_length)
+                }
+            };
+            let samples = match off_screen {
+                true => 0,
+                false => steps + 1
+            };
+            let coefficients = self.cache[i].coefficients;
+            let (start, end) = (control_window[0].color.to_vec(), control_window[3].color.to_vec());
+            let streamed = CubicSegment::new(*control_window).iter_samples(samples);
+            for (t, streamed) in (0..samples).map(|t| t as f32 / steps as f32).zip(streamed) {
+                let color = Color::from_vec(start.lerp(end, t));
+                let new_point = match settings.evaluator {
+                    Evaluator::Horner => Point::new(cubic_bezier(t, control_window), color),
+                    Evaluator::Bernstein => Point::new(cubic_bezier_powers(t, control_window), color),
+                    Evaluator::Casteljau => decasteljau(control_window, t),
+                    Evaluator::Matrix => Point::new(eval_power_basis(&coefficients, t), color),
+                    Evaluator::ForwardDifferences => Point::new(streamed, color)
+                };
+                self.rendered.push(new_point);
+            }
+
+            let [point_min, point_max] = Curve::bounding_box(control_window);
+            self.boxes.push(BoundingBox {
+                point_min,
+                point_max,
+                point_color: settings.theme.bounding_box,
+                outline_color: settings.theme.bounding_box
+            });
+
+            for (label, candidate) in Curve::extrema(control_window) {
+                draw_circle(candidate.x, candidate.y, 5.0, RED);
+                draw_text(label, candidate.x, candidate.y, 20.0, YELLOW);
+            }
+
+            self.boxes.push(BoundingBox {
+                point_min: tight_min,
+                point_max: tight_max,
+                point_color: RED,
+                outline_color: settings.theme.tight_box
+            });
+
+            let positions: Vec<Vec2> = control_window.iter().map(|p| p.pos).collect();
+            self.hulls.push(convex_hull(&positions));
+        }
+
+        if self.rendered.len() > run_start { self.runs.push(run_start..self.rendered.len()) };
+        self.sampled_view = view.filter(|_| culled);
+
+        // From the whole curve, the samples can miss the culled parts
+        self.oriented_box = self.oriented_bounding_box(settings.fill_tolerance());
+        self.modified = false;
+    }
+
+    /// Fails while the curve doesn't have a single complete segment to draw
+    pub fn validate(&self) -> Result<(), SplineError> {
+        let first = &self.control[..self.control.len().min(CubicSegment::POINTS)];
+        CubicSegment::try_new(first).map(|_| ())
+    }
+
+    /// Points still missing to complete the last segment, 0 when every segment is complete.
+    /// A B-spline only needs enough points for its first span
+    pub fn points_needed(&self) -> usize {
+        let len = self.control.len();
+        match (len < CubicSegment::POINTS, self.knots.is_some()) {
+            (true, _) => CubicSegment::POINTS - len,
+            (false, true) => 0,
+            (false, false) => (3 - (len - 1) % 3) % 3
+        }
+    }
+
+    /// Resample the curve if it was modified, or the view left the area sampled around
+    /// culled segments. Returns whether it was resampled
+    pub fn update(&mut self, settings: &RenderSettings) -> bool {
+        let view_left = match (self.sampled_view, settings.visible) {
+            (Some(sampled), Some(visible)) => !box_contains(sampled, visible),
+            (Some(_), None) => true,
+            (None, _) => false
+        };
+        if self.validate().is_err() || !(self.modified || view_left) { return false };
+        self.rendered.clear();
+        self.runs.clear();
+        self.boxes.clear();
+        self.hulls.clear();
+        self.render(settings);
+        true
+    }
+
+    pub fn draw(&mut self, settings: &RenderSettings) {
+        if self.validate().is_err() { return };
+        self.update(settings);
+
+        // Only the parts of each run on screen, padded so wide lines don't end at the edge
+        let pad = Vec2::splat(settings.world_line_width() + 2. / settings.pixels_per_unit);
+        let visible = settings.visible.map(|[min, max]| [min - pad, max + pad]);
+        let pieces: Vec<&[Point]> = self.runs.iter()
+            .map(|run| &self.rendered[run.clone()])
+            .flat_map(|run| match visible {
+                Some(visible) => visible_pieces(run, visible).into_iter().map(|piece| &run[piece]).collect(),
+                None => vec![run]
+            })
+            .collect();
+
+        match (settings.antialias, settings.flatten_tolerance) {
+            (true, _) => {
+                let pixel = 1. / settings.pixels_per_unit;
+                for piece in pieces {
+                    draw_smooth_polyline(piece, settings.world_line_width(), pixel);
+                }
+            },
+            (false, Some(_)) => for piece in pieces {
+                draw_polyline(piece, settings.world_line_width(), LineJoin::Round);
+            },
+            // One pixel dots whatever the zoom
+            (false, None) => {
+                let size = 1. / settings.pixels_per_unit;
+                let points: Vec<&Point> = pieces.iter().flat_map(|piece| piece.iter()).collect();
+                for point in &points {
+                    draw_rectangle(point.pos.x, point.pos.y, size, size, point.color);
+                }
+                count_draw_calls(points.len());
+            }
+        }
+
+        if settings.draw_bounding {
+            for bbox in &self.boxes {
+                bbox.draw();
+            }
+
+            if let Some(corners) = self.oriented_box {
+                for (i, a) in corners.iter().enumerate() {
+                    let b = corners[(i + 1) % 4];
+                    draw_line(a.x, a.y, b.x, b.y, 1., settings.theme.oriented_box);
+                }
+            }
+        }
+
+        if settings.draw_hull {
+            for hull in &self.hulls {
+                for (i, a) in hull.iter().enumerate() {
+                    let b = hull[(i + 1) % hull.len()];
+                    draw_line(a.x, a.y, b.x, b.y, 1., settings.theme.convex_hull);
+                }
+            }
+        }
+    }
+
+    pub fn draw_controls(&mut self, settings: &RenderSettings) {
+        for control in &self.control {
+            control.draw_control();
+        }
+
+        // A new curve has nothing to connect yet
+        if self.control.is_empty() { return };
+        let width = settings.world_line_width();
+        if self.knots.is_some() {
+            draw_polyline(&self.control, width, LineJoin::Miter);
+            return;
+        }
+
+        // Each anchor's handles are one polyline so the corner at the anchor is filled
+        let last = self.control.len() - 1;
+        for anchor in (0..=last).step_by(3) {
+            let handles = &self.control[anchor.saturating_sub(1)..=(anchor + 1).min(last)];
+            draw_polyline(handles, width, LineJoin::Miter);
+        }
+
+        // Badges with the measured class of each join, gold when a class is enforced
+        for anchor in (3..self.control.len()).step_by(3).filter(|&a| self.is_joint(a)) {
+            let measured = continuity::measure(&self.control, anchor);
+            let color = match self.joint_continuity(anchor) {
+                Continuity::C0 => GRAY,
+                _ => GOLD
+            };
+            let pos = self.control[anchor].pos;
+            draw_text(measured.label(), pos.x + CONTROLPOINT_RADIUS, pos.y - CONTROLPOINT_RADIUS, 18., color);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn curve(positions: &[Vec2]) -> Curve {
+        Curve { control: positions.iter().map(|&pos| Point::new(pos, WHITE)).collect(), ..Curve::default() }
+    }
+
+    #[test]
+    fn oriented_box_without_rendering() {
+        // A diamond, its tightest box is turned 45° from the axes
+        let corners = [vec2(0., -10.), vec2(10., 0.), vec2(0., 10.), vec2(-10., 0.), vec2(0., -10.)];
+        let mut positions = vec![corners[0]];
+        for pair in corners.windows(2) {
+            positions.extend([pair[0].lerp(pair[1], 1. / 3.), pair[0].lerp(pair[1], 2. / 3.), pair[1]]);
+        }
+        let diamond = Curve { closed: true, ..curve(&positions) };
+
+        let [a, b, c, _] = diamond.oriented_bounding_box(0.1).unwrap();
+        assert!((a.distance(b) * b.distance(c) - 200.).abs() < 1e-2);
+        assert!(diamond.rendered.is_empty());
+    }
+
+    // Four straight segments along the x axis
+    fn chain() -> Curve {
+        curve(&(0..13).map(|i| vec2(i as f32 * 10., 0.)).collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn joins_follow_subdivision() {
+        let mut curve = chain();
+        curve.continuity = vec![Continuity::C0, Continuity::C0, Continuity::C1, Continuity::C2];
+        curve.subdivide_segment(0, 3, false);
+        assert_eq!(curve.joint_continuity(9), Continuity::C0);
+        assert_eq!(curve.joint_continuity(12), Continuity::C1);
+        assert_eq!(curve.joint_continuity(15), Continuity::C2);
+    }
+
+    #[test]
+    fn removing_a_point_drops_the_joins_after_it() {
+        let mut curve = chain();
+        curve.continuity = vec![Continuity::C0, Continuity::C1, Continuity::C2, Continuity::G1];
+        assert!(curve.remove_point(7));
+        assert_eq!(curve.continuity, [Continuity::C0, Continuity::C1]);
+    }
+
+    #[test]
+    fn joins_follow_auto_smooth() {
+        let mut curve = chain();
+        curve.continuity = vec![Continuity::C0, Continuity::G1];
+        curve.auto_smooth();
+        assert_eq!(curve.joint_continuity(9), Continuity::G1);
+        assert_eq!(curve.joint_continuity(3), Continuity::C0);
+        assert_eq!(curve.joint_continuity(12), Continuity::C0);
+    }
+
+    #[test]
+    fn bspline_round_trip_forgets_the_joins() {
+        let mut curve = chain();
+        curve.continuity = vec![Continuity::C0, Continuity::C2];
+        curve.toggle_bspline();
+        curve.toggle_bspline();
+        assert!(curve.continuity.is_empty());
+    }
+
+    #[test]
+    fn transforms_leave_locked_points() {
+        let mut curve = chain();
+        curve.control[3].locked = true;
+        curve.transform(Mat3::from_angle(std::f32::consts::FRAC_PI_2));
+        assert_eq!(curve.control[3].pos, vec2(30., 0.));
+        assert!(curve.control[6].pos.distance(vec2(0., 60.)) < 1e-4);
+    }
+
+    #[test]
+    fn scaling_leaves_locked_points() {
+        let mut curve = chain();
+        curve.control[0].locked = true;
+        curve.scale_about(vec2(60., 0.), 2.);
+        assert_eq!(curve.control[0].pos, vec2(0., 0.));
+        assert_eq!(curve.control[12].pos, vec2(180., 0.));
+    }
+}
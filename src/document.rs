@@ -0,0 +1,409 @@
+use macroquad::prelude::*;
+
+use crate::boolean::{boolean, BooleanOp};
+use crate::bvh::{Bvh, RayHit};
+use crate::curve::Curve;
+use crate::point::Point;
+
+/// Named group of curves that can be hidden or locked together
+#[derive(Clone, Debug, PartialEq)]
+pub struct Layer {
+    pub name: String,
+    pub visible: bool,
+    /// Curves on locked layers are drawn but can't become the active curve
+    pub locked: bool
+}
+
+impl Layer {
+    pub fn new(name: &str) -> Self {
+        Self { name: name.to_string(), visible: true, locked: false }
+    }
+}
+
+/// How the curves linked by a symmetry are made from each other
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SymmetryKind {
+    /// Reflection across the vertical line at world x `axis`
+    Mirror { axis: f32 },
+    /// `folds` copies evenly rotated around `center`
+    Radial { center: Vec2, folds: usize }
+}
+
+impl SymmetryKind {
+    /// Copies the symmetry keeps, the edited curve included
+    pub fn copies(self) -> usize {
+        match self {
+            SymmetryKind::Mirror { .. } => 2,
+            SymmetryKind::Radial { folds, .. } => folds.max(1)
+        }
+    }
+
+    /// The point moved `steps` copies along, reflected for odd steps or rotated by that many folds
+    pub fn apply(self, p: Vec2, steps: usize) -> Vec2 {
+        match self {
+            SymmetryKind::Mirror { axis } if steps % 2 == 1 => vec2(2. * axis - p.x, p.y),
+            SymmetryKind::Mirror { .. } => p,
+            SymmetryKind::Radial { center, folds } => {
+                let angle = std::f32::consts::TAU * steps as f32 / folds.max(1) as f32;
+                center + Vec2::from_angle(angle).rotate(p - center)
+            }
+        }
+    }
+}
+
+/// Curves kept as copies of each other under a mirror or rotation, editing any of them
+/// updates the rest
+#[derive(Clone, Debug, PartialEq)]
+pub struct Symmetry {
+    pub kind: SymmetryKind,
+    /// Linked curves, the k-th is the first one moved k steps
+    pub curves: Vec<usize>
+}
+
+/// Every curve being edited, only the active one receives input
+pub struct Document {
+    pub curves: Vec<Curve>,
+    pub active: usize,
+    /// Drawn in order, the first one at the bottom. Never empty
+    pub layers: Vec<Layer>,
+    /// Layer new curves go on
+    pub active_layer: usize,
+    /// Mirror editing, off while None. Not saved with the project
+    pub symmetry: Option<Symmetry>
+}
+
+impl Default for Document {
+    fn default() -> Self {
+        Self {
+            curves: vec![Curve { modified: true, ..Default::default() }],
+            active: 0,
+            layers: vec![Layer::new("Layer 1")],
+            active_layer: 0,
+            symmetry: None
+        }
+    }
+}
+
+impl Document {
+    pub fn active(&self) -> &Curve {
+        &self.curves[self.active]
+    }
+
+    pub fn active_mut(&mut self) -> &mut Curve {
+        &mut self.curves[self.active]
+    }
+
+    /// Force every curve to be sampled again, for when the render settings change
+    pub fn mark_modified(&mut self) {
+        for curve in &mut self.curves {
+            curve.modified = true;
+        }
+    }
+
+    /// Start a new empty curve on the active layer and make it the active one, unless the
+    /// layer is locked or hidden. Returns whether the curve was added
+    pub fn new_curve(&mut self) -> bool {
+        let layer = &self.layers[self.active_layer];
+        if !layer.visible || layer.locked { return false };
+
+        self.curves.push(Curve { layer: self.active_layer, modified: true, ..Default::default() });
+        self.active = self.curves.len() - 1;
+        true
+    }
+
+    /// Add `curves` to the active layer and make the last one active, replacing the active
+    /// curve if it's empty
+    pub fn append(&mut self, curves: Vec<Curve>) {
+        if curves.is_empty() { return };
+        if self.active().control.is_empty() {
+            self.curves.remove(self.active);
+            // The pair can't be told apart from the shifted curves anymore
+            self.symmetry = None;
+        }
+        let layer = self.active_layer;
+        self.curves.extend(curves.into_iter().map(|curve| Curve { layer, ..curve }));
+        self.active = self.curves.len() - 1;
+    }
+
+    /// Make the next curve that can be edited active, staying put if there's none
+    pub fn cycle_active(&mut self) {
+        let count = self.curves.len();
+        if let Some(next) = (1..=count).map(|i| (self.active + i) % count).find(|&i| self.is_editable(i)) {
+            self.active = next;
+        }
+    }
+
+    /// Layer of the curve at `index`, the top one for curves whose layer is gone
+    pub fn layer_of(&self, index: usize) -> &Layer {
+        let layer = self.curves[index].layer.min(self.layers.len() - 1);
+        &self.layers[layer]
+    }
+
+    /// Whether the curve at `index` is on a visible, unlocked layer
+    pub fn is_editable(&self, index: usize) -> bool {
+        let layer = self.layer_of(index);
+        layer.visible && !layer.locked
+    }
+
+    /// Indices of the curves on visible layers, bottom layer first and in document order
+    /// within each layer, which is the order curves are drawn in
+    pub fn draw_order(&self) -> Vec<usize> {
+        let mut order: Vec<usize> = (0..self.curves.len()).filter(|&i| self.layer_of(i).visible).collect();
+        order.sort_by_key(|&i| self.curves[i].layer.min(self.layers.len() - 1));
+        order
+    }
+
+    /// Add an empty layer on top and make it the active one
+    pub fn add_layer(&mut self) {
+        self.layers.push(Layer::new(&format!("Layer {}", self.layers.len() + 1)));
+        self.active_layer = self.layers.len() - 1;
+    }
+
+    /// Make `layer` the one new curves go on. When it can be edited the active curve moves
+    /// to its newest curve, which is a new empty one if it has none
+    pub fn select_layer(&mut self, layer: usize) {
+        if layer >= self.layers.len() { return };
+        self.active_layer = layer;
+        if self.layers[layer].locked || !self.layers[layer].visible || self.curves[self.active].layer == layer { return };
+
+        match self.curves.iter().rposition(|curve| curve.layer == layer) {
+            Some(newest) => self.active = newest,
+            None if self.active().control.is_empty() => self.move_active_to(layer),
+            None => { self.new_curve(); }
+        }
+    }
+
+    /// Indices of the curves grouped with the one at `index`, itself included
+    pub fn group_members(&self, index: usize) -> Vec<usize> {
+        match self.curves[index].group {
+            Some(group) => (0..self.curves.len()).filter(|&i| self.curves[i].group == Some(group)).collect(),
+            None => vec![index]
+        }
+    }
+
+    /// Group id no curve uses yet
+    pub fn next_group_id(&self) -> usize {
+        self.curves.iter().filter_map(|curve| curve.group).max().map_or(0, |id| id + 1)
+    }
+
+    /// Put the curves at `a` and `b` in one group, along with everything already grouped
+    /// with either of them
+    pub fn group(&mut self, a: usize, b: usize) {
+        let id = self.curves[a].group.or(self.curves[b].group).unwrap_or(self.next_group_id());
+        for i in self.group_members(a).into_iter().chain(self.group_members(b)) {
+            self.curves[i].group = Some(id);
+        }
+    }
+
+    /// Take the curve at `index` out of its group, dissolving groups left with one curve
+    pub fn ungroup(&mut self, index: usize) {
+        let members = self.group_members(index);
+        self.curves[index].group = None;
+        if let [a, b] = members[..] {
+            self.curves[a].group = None;
+            self.curves[b].group = None;
+        }
+    }
+
+    // Members that can be edited, curves on hidden or locked layers stay put
+    fn editable_members(&self, index: usize) -> Vec<usize> {
+        self.group_members(index).into_iter().filter(|&i| self.is_editable(i)).collect()
+    }
+
+    /// Union of the bounds of the curve's group
+    pub fn group_bounds(&self, index: usize) -> Option<[Vec2; 2]> {
+        self.group_members(index).into_iter()
+            .filter_map(|i| self.curves[i].bounds())
+            .reduce(|[amin, amax], [bmin, bmax]| [amin.min(bmin), amax.max(bmax)])
+    }
+
+    /// Move the curve at `index` and the rest of its group by `delta`
+    pub fn translate_group(&mut self, index: usize, delta: Vec2) {
+        for i in self.editable_members(index) {
+            self.curves[i].translate(delta);
+        }
+    }
+
+    /// Scale the curve at `index` and the rest of its group by `factor` around the center
+    /// of their bounds
+    pub fn scale_group(&mut self, index: usize, factor: f32) {
+        let Some([min, max]) = self.group_bounds(index) else { return };
+        for i in self.editable_members(index) {
+            self.curves[i].scale_about((min + max) / 2., factor);
+        }
+    }
+
+    /// Apply `matrix` to the curve at `index` and the rest of its group, with the origin
+    /// moved to the center of their bounds so they flip and turn in place
+    pub fn transform_group(&mut self, index: usize, matrix: Mat3) {
+        let Some([min, max]) = self.group_bounds(index) else { return };
+        self.transform_group_about(index, (min + max) / 2., matrix);
+    }
+
+    /// Apply `matrix` to the curve at `index` and the rest of its group, with the origin
+    /// moved to `center`
+    pub fn transform_group_about(&mut self, index: usize, center: Vec2, matrix: Mat3) {
+        let matrix = Mat3::from_translation(center) * matrix * Mat3::from_translation(-center);
+        for i in self.editable_members(index) {
+            self.curves[i].transform(matrix);
+        }
+    }
+
+    /// Copy the curve at `index` and the rest of its group `offset` away, the copies get a
+    /// group of their own. The copy of the curve at `index` becomes the active one
+    pub fn duplicate_group(&mut self, index: usize, offset: Vec2) {
+        let members = self.group_members(index);
+        let id = self.curves[index].group.map(|_| self.next_group_id());
+
+        for &i in &members {
+            let copy = Curve { group: id, ..self.curves[i].duplicate(offset) };
+            self.curves.push(copy);
+            if i == index { self.active = self.curves.len() - 1 };
+        }
+    }
+
+    /// Draw the active curve above every other curve of its layer
+    pub fn bring_to_front(&mut self) {
+        let last = self.curves.len() - 1;
+        self.move_curve(self.active, last);
+    }
+
+    /// Draw the active curve below every other curve of its layer
+    pub fn send_to_back(&mut self) {
+        self.move_curve(self.active, 0);
+    }
+
+    // Move the curve at `from` to `to` and make it active, the mirrored pair keeps
+    // pointing at the same curves
+    fn move_curve(&mut self, from: usize, to: usize) {
+        let curve = self.curves.remove(from);
+        self.curves.insert(to, curve);
+        self.active = to;
+
+        let shift = |i: usize| match i {
+            i if i == from => to,
+            i if from < i && i <= to => i - 1,
+            i if to <= i && i < from => i + 1,
+            i => i
+        };
+        if let Some(symmetry) = &mut self.symmetry {
+            for curve in &mut symmetry.curves {
+                *curve = shift(*curve);
+            }
+        }
+    }
+
+    /// Link the active curve to new copies of it made by `kind`, which follow its edits
+    /// until `stop_symmetry`
+    pub fn start_symmetry(&mut self, kind: SymmetryKind) {
+        let mut curves = vec![self.active];
+        for _ in 1..kind.copies() {
+            curves.push(self.curves.len());
+            self.curves.push(Curve { group: None, ..self.curves[self.active].duplicate(Vec2::ZERO) });
+        }
+        self.symmetry = Some(Symmetry { kind, curves });
+        self.curves[self.active].modified = true;
+        self.sync_symmetry();
+    }
+
+    /// Unlink the copies, every curve is kept
+    pub fn stop_symmetry(&mut self) {
+        self.symmetry = None;
+    }
+
+    /// Copy the edits of the active linked curve, or the first one, to the others. Call
+    /// after editing and before sampling, which clears the modified flags
+    pub fn sync_symmetry(&mut self) {
+        let Some(Symmetry { kind, curves }) = self.symmetry.clone() else { return };
+        if curves.iter().any(|&i| i >= self.curves.len()) {
+            self.symmetry = None;
+            return;
+        }
+
+        let lead = curves.iter().position(|&i| i == self.active).unwrap_or(0);
+        let leader = &self.curves[curves[lead]];
+        if !leader.modified { return };
+        let (control, closed, knots, continuity) = (leader.control.clone(), leader.closed, leader.knots.clone(), leader.continuity.clone());
+
+        let n = curves.len();
+        for (k, &i) in curves.iter().enumerate().filter(|&(k, _)| k != lead) {
+            let steps = (k + n - lead) % n;
+            let follower = &mut self.curves[i];
+            follower.control = control.iter().map(|p| Point { pos: kind.apply(p.pos, steps), ..*p }).collect();
+            (follower.closed, follower.knots, follower.continuity) = (closed, knots.clone(), continuity.clone());
+            follower.modified = true;
+        }
+    }
+
+    /// Put the active curve on `layer`
+    pub fn move_active_to(&mut self, layer: usize) {
+        if layer >= self.layers.len() { return };
+        let active = self.active;
+        self.curves[active].layer = layer;
+        self.curves[active].modified = true;
+    }
+
+    /// Union of the bounds of every curve, None if no curve has a complete segment
+    pub fn bounds(&self) -> Option<[Vec2; 2]> {
+        self.curves.iter()
+            .filter_map(Curve::bounds)
+            .reduce(|[amin, amax], [bmin, bmax]| [amin.min(bmin), amax.max(bmax)])
+    }
+
+    /// Apply a boolean operation between two closed curves, appending the results as new
+    /// curves. The last result becomes the active curve. Returns how many curves were added
+    pub fn boolean(&mut self, a: usize, b: usize, op: BooleanOp) -> usize {
+        let results = boolean(&self.curves[a], &self.curves[b], op);
+        let count = results.len();
+
+        let layer = self.active_layer;
+        self.curves.extend(results.into_iter().map(|curve| Curve { layer, ..curve }));
+        if count > 0 { self.active = self.curves.len() - 1 };
+
+        count
+    }
+
+    /// Sum of the winding numbers of every closed curve around `p`
+    pub fn winding_number(&self, p: Vec2) -> i32 {
+        self.curves.iter().map(|c| c.winding_number(p)).sum()
+    }
+
+    /// Crossings of the ray from `origin` along `dir` with every curve, nearest first. Builds
+    /// the hierarchy each call, keep a `bvh` around to cast many rays at unchanged curves
+    pub fn raycast(&self, origin: Vec2, dir: Vec2) -> Vec<RayHit> {
+        self.bvh().raycast(origin, dir)
+    }
+
+    /// Hierarchy over every segment of every curve for closest-point, picking and intersection queries
+    pub fn bvh(&self) -> Bvh {
+        Bvh::new(&self.curves)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn no_new_curves_on_a_locked_layer() {
+        let mut doc = Document::default();
+        doc.layers[0].locked = true;
+        assert!(!doc.new_curve());
+        assert_eq!(doc.curves.len(), 1);
+
+        doc.layers[0].locked = false;
+        assert!(doc.new_curve());
+        assert_eq!(doc.active, 1);
+    }
+
+    #[test]
+    fn raycast_crosses_every_curve() {
+        let mut doc = Document::default();
+        for x in [20., 10.] {
+            let control = [vec2(x, -5.), vec2(x, -2.), vec2(x, 2.), vec2(x, 5.)].map(|pos| Point::new(pos, WHITE)).to_vec();
+            doc.curves.push(Curve { control, ..Curve::default() });
+        }
+        let hits = doc.raycast(vec2(0., 0.), Vec2::X);
+        assert_eq!(hits.iter().map(|hit| hit.segment.curve).collect::<Vec<_>>(), [2, 1]);
+    }
+}
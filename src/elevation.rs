@@ -0,0 +1,143 @@
+use macroquad::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::curve::Curve;
+
+// Terrain colors from the lowest height to the highest
+const RAMP: [(f32, Color); 4] = [
+    (0., Color::new(0.15, 0.35, 0.85, 1.)),
+    (0.35, Color::new(0.2, 0.7, 0.3, 1.)),
+    (0.7, Color::new(0.55, 0.4, 0.2, 1.)),
+    (1., Color::new(0.95, 0.95, 0.95, 1.))
+];
+
+/// Height profile attached to a curve, a 1D spline of height in units over the fraction of
+/// the curve's arc length, for roads over terrain and side-scroller levels. The keys are
+/// (fraction, height) pairs sorted by fraction, the first at 0 and the last at 1
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Elevation {
+    pub keys: Vec<[f32; 2]>
+}
+
+impl Default for Elevation {
+    fn default() -> Self {
+        Self { keys: vec![[0., 0.], [1., 0.]] }
+    }
+}
+
+impl Elevation {
+    /// Whether the keys are sorted and span the whole curve, profiles read from files may not be
+    pub fn is_valid(&self) -> bool {
+        self.keys.len() >= 2
+            && self.keys[0][0] == 0.
+            && self.keys[self.keys.len() - 1][0] == 1.
+            && self.keys.windows(2).all(|pair| pair[0][0] < pair[1][0])
+    }
+
+    // Slope at every key for a monotone cubic (Fritsch and Carlson), so the profile never
+    // overshoots its keys: flat at peaks and valleys, limited next to steep stretches
+    fn slopes(&self) -> Vec<f32> {
+        let secants: Vec<f32> = self.keys.windows(2).map(|pair| (pair[1][1] - pair[0][1]) / (pair[1][0] - pair[0][0])).collect();
+        let mut slopes: Vec<f32> = (0..self.keys.len())
+            .map(|i| match (i.checked_sub(1).map(|j| secants[j]), secants.get(i).copied()) {
+                (Some(before), Some(after)) if before * after > 0. => (before + after) / 2.,
+                (Some(_), Some(_)) => 0.,
+                (Some(before), None) => before,
+                (None, Some(after)) => after,
+                (None, None) => 0.
+            })
+            .collect();
+        for (i, &secant) in secants.iter().enumerate() {
+            if secant == 0. {
+                slopes[i] = 0.;
+                slopes[i + 1] = 0.;
+                continue;
+            }
+            let (a, b) = (slopes[i] / secant, slopes[i + 1] / secant);
+            let magnitude = (a * a + b * b).sqrt();
+            if magnitude > 3. {
+                slopes[i] = 3. / magnitude * a * secant;
+                slopes[i + 1] = 3. / magnitude * b * secant;
+            }
+        }
+
+        slopes
+    }
+
+    /// Height at `fraction` of the arc length, clamped to the ends
+    pub fn height_at(&self, fraction: f32) -> f32 {
+        let (Some(first), Some(last)) = (self.keys.first(), self.keys.last()) else { return 0. };
+        if fraction <= first[0] { return first[1] };
+        if fraction >= last[0] { return last[1] };
+
+        let i = self.keys.partition_point(|key| key[0] <= fraction) - 1;
+        let slopes = self.slopes();
+        let ([x0, h0], [x1, h1]) = (self.keys[i], self.keys[i + 1]);
+        let width = x1 - x0;
+        let t = (fraction - x0) / width;
+        let (t2, t3) = (t * t, t * t * t);
+        h0 * (2. * t3 - 3. * t2 + 1.) + slopes[i] * width * (t3 - 2. * t2 + t)
+            + h1 * (-2. * t3 + 3. * t2) + slopes[i + 1] * width * (t3 - t2)
+    }
+
+    /// Lowest and highest key, the profile stays between them
+    pub fn range(&self) -> (f32, f32) {
+        self.keys.iter().fold((f32::MAX, f32::MIN), |(min, max), key| (min.min(key[1]), max.max(key[1])))
+    }
+
+    /// Add a key at `fraction` with `height`, returns its index. None on the ends or an existing key
+    pub fn insert(&mut self, fraction: f32, height: f32) -> Option<usize> {
+        if fraction <= 0. || fraction >= 1. { return None };
+        let i = self.keys.partition_point(|key| key[0] < fraction);
+        if self.keys[i][0] == fraction { return None };
+        self.keys.insert(i, [fraction, height]);
+        Some(i)
+    }
+
+    /// Move key `i` to `fraction` and `height`. The ends keep their fraction and the others
+    /// stay between their neighbours
+    pub fn move_key(&mut self, i: usize, fraction: f32, height: f32) {
+        let last = self.keys.len() - 1;
+        let fraction = match i {
+            0 => 0.,
+            _ if i == last => 1.,
+            _ => fraction.clamp(self.keys[i - 1][0] + 1e-3, self.keys[i + 1][0] - 1e-3)
+        };
+        self.keys[i] = [fraction, height];
+    }
+
+    /// Remove key `i`, returns false for the ends which can't be
+    pub fn remove(&mut self, i: usize) -> bool {
+        if i == 0 || i + 1 >= self.keys.len() { return false };
+        self.keys.remove(i);
+        true
+    }
+}
+
+/// Color of `height` on a terrain ramp, deep blue at `min` through green and brown to snow at `max`
+pub fn height_color(height: f32, (min, max): (f32, f32)) -> Color {
+    let t = match max - min > 0. {
+        true => ((height - min) / (max - min)).clamp(0., 1.),
+        false => 0.5
+    };
+    let i = RAMP.iter().rposition(|&(stop, _)| stop <= t).unwrap_or(0).min(RAMP.len() - 2);
+    let ((a, from), (b, to)) = (RAMP[i], RAMP[i + 1]);
+    Color::from_vec(from.to_vec().lerp(to.to_vec(), (t - a) / (b - a)))
+}
+
+/// The curve's lines colored by the height of its profile there, with the world camera set
+pub fn draw_elevation(curve: &Curve, tolerance: f32, line_width: f32) {
+    let Some(elevation) = &curve.elevation else { return };
+    let samples: Vec<Vec2> = curve.flatten(tolerance).iter().map(|p| p.pos).collect();
+    let total: f32 = samples.windows(2).map(|pair| pair[0].distance(pair[1])).sum();
+    if total <= 0. { return };
+
+    let range = elevation.range();
+    let mut travelled = 0.;
+    for pair in samples.windows(2) {
+        let length = pair[0].distance(pair[1]);
+        let color = height_color(elevation.height_at((travelled + length / 2.) / total), range);
+        draw_line(pair[0].x, pair[0].y, pair[1].x, pair[1].y, line_width, color);
+        travelled += length;
+    }
+}
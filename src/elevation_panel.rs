@@ -0,0 +1,146 @@
+use macroquad::prelude::*;
+
+use crate::coords::CoordinateSystem;
+use crate::curve::Curve;
+use crate::elevation::height_color;
+use crate::math::arc_length;
+
+// Below the history timeline at its tallest and right of the profile view
+const PANEL_TOP: f32 = 170.0;
+const PANEL_LEFT: f32 = 340.0;
+const PANEL_RIGHT_MARGIN: f32 = 10.0;
+const PANEL_HEIGHT: f32 = 130.0;
+const HEADER_HEIGHT: f32 = 20.0;
+const GRAPH_PADDING: f32 = 10.0;
+const KEY_RADIUS: f32 = 6.0;
+const SAMPLES: usize = 200;
+
+/// Side view of the active curve's elevation profile, height over arc length. Clicking adds
+/// a key, dragging moves one and right clicking removes it
+#[derive(Default)]
+pub struct ElevationPanel {
+    // Key being dragged and the height range when the drag started, so the graph doesn't
+    // rescale under the mouse
+    dragging: Option<(usize, (f32, f32))>
+}
+
+fn panel_rect() -> Rect {
+    Rect::new(PANEL_LEFT, PANEL_TOP, screen_width() - PANEL_LEFT - PANEL_RIGHT_MARGIN, PANEL_HEIGHT)
+}
+
+fn graph_rect() -> Rect {
+    let panel = panel_rect();
+    Rect::new(
+        panel.x + GRAPH_PADDING,
+        panel.y + HEADER_HEIGHT + GRAPH_PADDING,
+        panel.w - 2. * GRAPH_PADDING,
+        panel.h - HEADER_HEIGHT - 2. * GRAPH_PADDING
+    )
+}
+
+// The keys' range padded so the extremes aren't on the edges
+fn padded((min, max): (f32, f32)) -> (f32, f32) {
+    let pad = match max - min > 1e-3 {
+        true => (max - min) * 0.15,
+        false => 1.
+    };
+    (min - pad, max + pad)
+}
+
+fn to_screen([fraction, height]: [f32; 2], (min, max): (f32, f32)) -> Vec2 {
+    let rect = graph_rect();
+    vec2(rect.x + fraction * rect.w, rect.y + rect.h - (height - min) / (max - min) * rect.h)
+}
+
+fn from_screen(p: Vec2, (min, max): (f32, f32)) -> [f32; 2] {
+    let rect = graph_rect();
+    [((p.x - rect.x) / rect.w).clamp(0., 1.), min + (rect.y + rect.h - p.y) / rect.h * (max - min)]
+}
+
+impl ElevationPanel {
+    /// Whether the mouse is over the panel of a curve with a profile, clicks there shouldn't
+    /// reach the canvas
+    pub fn hovered(&self, curve: &Curve) -> bool {
+        curve.elevation.is_some() && panel_rect().contains(mouse_position().into())
+    }
+
+    /// Handle adding, dragging and removing keys, returns true while the panel is using the mouse
+    pub fn update(&mut self, curve: &mut Curve) -> bool {
+        let hovered = self.hovered(curve);
+        let Some(elevation) = curve.elevation.as_mut() else {
+            self.dragging = None;
+            return false;
+        };
+        if !is_mouse_button_down(MouseButton::Left) {
+            self.dragging = None;
+        }
+
+        let mouse: Vec2 = mouse_position().into();
+        let range = padded(elevation.range());
+        let under_mouse = elevation.keys.iter().position(|&key| to_screen(key, range).distance(mouse) <= KEY_RADIUS);
+        if hovered && is_mouse_button_pressed(MouseButton::Right) {
+            if let Some(i) = under_mouse { elevation.remove(i); }
+        }
+        if hovered && is_mouse_button_pressed(MouseButton::Left) {
+            let [fraction, height] = from_screen(mouse, range);
+            self.dragging = under_mouse.or_else(|| elevation.insert(fraction, height)).map(|i| (i, range));
+        }
+
+        if let Some((i, range)) = self.dragging {
+            match i < elevation.keys.len() {
+                true => {
+                    let [fraction, height] = from_screen(mouse, range);
+                    elevation.move_key(i, fraction, height);
+                },
+                false => self.dragging = None
+            }
+        }
+
+        self.dragging.is_some() || hovered
+    }
+
+    pub fn draw(&self, curve: &Curve, coords: &CoordinateSystem) {
+        let Some(elevation) = &curve.elevation else { return };
+        let panel = panel_rect();
+        draw_rectangle(panel.x, panel.y, panel.w, panel.h, Color::new(0., 0., 0., 0.8));
+
+        let key_range = elevation.range();
+        let range = self.dragging.map_or_else(|| padded(key_range), |(_, range)| range);
+        let length: f32 = curve.segments().into_iter().map(arc_length).sum();
+        let label = format!("elevation: {:.2} to {:.2} over {:.2} units of arc length - click adds a key, drag moves, right click removes",
+            key_range.0, key_range.1, coords.length(length));
+        draw_text(&label, panel.x + GRAPH_PADDING, panel.y + 14., 16., LIGHTGRAY);
+
+        let rect = graph_rect();
+        if range.0 < 0. && range.1 > 0. {
+            let zero = to_screen([0., 0.], range);
+            draw_line(rect.x, zero.y, rect.x + rect.w, zero.y, 1., DARKGRAY);
+        }
+
+        // Filled down to the bottom like terrain, in the same colors as the curve on the canvas
+        let points: Vec<Vec2> = (0..=SAMPLES)
+            .map(|i| {
+                let fraction = i as f32 / SAMPLES as f32;
+                to_screen([fraction, elevation.height_at(fraction)], range)
+            })
+            .collect();
+        let bottom = rect.y + rect.h;
+        for (i, pair) in points.windows(2).enumerate() {
+            let fraction = (i as f32 + 0.5) / SAMPLES as f32;
+            let color = height_color(elevation.height_at(fraction), key_range);
+            let (top_left, top_right) = (pair[0].min(vec2(f32::MAX, bottom)), pair[1].min(vec2(f32::MAX, bottom)));
+            draw_triangle(top_left, top_right, vec2(top_right.x, bottom), Color { a: 0.35, ..color });
+            draw_triangle(top_left, vec2(top_right.x, bottom), vec2(top_left.x, bottom), Color { a: 0.35, ..color });
+            draw_line(pair[0].x, pair[0].y, pair[1].x, pair[1].y, 2., color);
+        }
+
+        for (i, &key) in elevation.keys.iter().enumerate() {
+            let pos = to_screen(key, range);
+            let color = match self.dragging.is_some_and(|(dragged, _)| dragged == i) {
+                true => YELLOW,
+                false => WHITE
+            };
+            draw_circle(pos.x, pos.y, KEY_RADIUS, color);
+        }
+    }
+}
@@ -0,0 +1,22 @@
+use std::fmt;
+
+/// Why a spline or segment couldn't be built from the given points
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SplineError {
+    /// `needed` more points are required on top of the `found` ones
+    NotEnoughPoints { found: usize, needed: usize },
+    /// A segment takes exactly `expected` points
+    TooManyPoints { found: usize, expected: usize }
+}
+
+impl fmt::Display for SplineError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SplineError::NotEnoughPoints { needed: 1, .. } => write!(f, "need 1 more point"),
+            SplineError::NotEnoughPoints { needed, .. } => write!(f, "need {} more points", needed),
+            SplineError::TooManyPoints { found, expected } => write!(f, "expected {} points, got {}", expected, found)
+        }
+    }
+}
+
+impl std::error::Error for SplineError {}
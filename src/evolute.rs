@@ -0,0 +1,53 @@
+use macroquad::prelude::*;
+
+use crate::curve::Curve;
+use crate::math::{cubic_bezier, curvature, velocity};
+use crate::point::Point;
+
+// The evolute is far more sensitive than the curve, its cusps need dense samples
+const SAMPLES_PER_SEGMENT: usize = 128;
+// Radii of curvature past this many times the curve's size are treated as straight
+const MAX_RADIUS_FACTOR: f32 = 20.;
+
+/// Center of curvature p + n / κ at `t`, None where the curve is straight or stops
+pub fn center_of_curvature(segment: [Point; 4], t: f32, max_radius: f32) -> Option<Vec2> {
+    let k = curvature(segment, t);
+    if k.abs() * max_radius < 1. { return None };
+    let normal = velocity(segment, t).try_normalize()?.perp();
+    Some(cubic_bezier(t, &segment) + normal / k)
+}
+
+/// Evolute of `curve`, the locus of its centers of curvature, as polylines. It runs off to
+/// infinity at inflections and straight stretches, so it's broken there instead, and at
+/// the joints, where the curvature may jump
+pub fn evolute(curve: &Curve) -> Vec<Vec<Vec2>> {
+    let Some([min, max]) = curve.bounds() else { return Vec::new() };
+    let max_radius = (max - min).length().max(1.) * MAX_RADIUS_FACTOR;
+
+    let mut pieces: Vec<Vec<Vec2>> = Vec::new();
+    for segment in curve.segments() {
+        let mut open = false;
+        for i in 0..=SAMPLES_PER_SEGMENT {
+            let t = i as f32 / SAMPLES_PER_SEGMENT as f32;
+            let center = center_of_curvature(segment, t, max_radius);
+            match (center, pieces.last_mut().filter(|_| open)) {
+                (Some(center), Some(piece)) => piece.push(center),
+                (Some(center), None) => pieces.push(vec![center]),
+                (None, _) => ()
+            }
+            open = center.is_some();
+        }
+    }
+    pieces.retain(|piece| piece.len() > 1);
+
+    pieces
+}
+
+/// Every piece of the evolute as a line, with the world camera set
+pub fn draw_evolute(pieces: &[Vec<Vec2>], line_width: f32, color: Color) {
+    for piece in pieces {
+        for pair in piece.windows(2) {
+            draw_line(pair[0].x, pair[0].y, pair[1].x, pair[1].y, line_width, color);
+        }
+    }
+}
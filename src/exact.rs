@@ -0,0 +1,239 @@
+use macroquad::prelude::*;
+use num_bigint::BigInt;
+use num_rational::BigRational;
+use num_traits::{Signed, ToPrimitive, Zero};
+
+use crate::curve::Curve;
+use crate::math::{cubic_bezier, cubic_roots, intersections, power_basis};
+use crate::point::Point;
+
+/// Size the crossings are narrowed down to by `verify_curve`
+pub const CROSSING_TOLERANCE: f32 = 0.01;
+/// Furthest a float root may be from a sign change of the exact polynomial, in ulps,
+/// before it's reported
+pub const MAX_ROOT_ULPS: u32 = 64;
+
+// Polynomial with exact coefficients, lowest degree first
+type Polynomial = Vec<BigRational>;
+
+/// Every finite float is a binary fraction, so this loses nothing
+pub fn rational(x: f32) -> BigRational {
+    BigRational::from_float(x).unwrap_or_else(BigRational::zero)
+}
+
+fn small(n: i64) -> BigRational {
+    BigRational::from_integer(BigInt::from(n))
+}
+
+fn eval(polynomial: &[BigRational], t: &BigRational) -> BigRational {
+    polynomial.iter().rev().fold(BigRational::zero(), |value, c| value * t + c)
+}
+
+// Drop the vanishing leading coefficients, the zero polynomial ends up empty
+fn trim(mut polynomial: Polynomial) -> Polynomial {
+    while polynomial.last().is_some_and(|c| c.is_zero()) {
+        polynomial.pop();
+    }
+    polynomial
+}
+
+fn derivative(polynomial: &[BigRational]) -> Polynomial {
+    trim(polynomial.iter().enumerate().skip(1).map(|(i, c)| c * small(i as i64)).collect())
+}
+
+// Remainder of the long division of `a` by the non-zero `b`
+fn remainder(a: &[BigRational], b: &[BigRational]) -> Polynomial {
+    let mut rest = trim(a.to_vec());
+    let lead = &b[b.len() - 1];
+    while rest.len() >= b.len() {
+        let shift = rest.len() - b.len();
+        let factor = &rest[rest.len() - 1] / lead;
+        for (i, c) in b.iter().enumerate() {
+            rest[shift + i] -= &factor * c;
+        }
+        rest.pop();
+        rest = trim(rest);
+    }
+    rest
+}
+
+/// Distinct real roots of the polynomial by Sturm's theorem, exactly. The zero polynomial
+/// has none, as far as `cubic_roots` is concerned
+pub fn count_real_roots(polynomial: &[BigRational]) -> usize {
+    let first = trim(polynomial.to_vec());
+    if first.len() < 2 { return 0 };
+
+    let mut chain = vec![first.clone(), derivative(&first)];
+    loop {
+        let next: Polynomial = remainder(&chain[chain.len() - 2], &chain[chain.len() - 1]).into_iter().map(|c| -c).collect();
+        if next.is_empty() { break };
+        chain.push(next);
+    }
+
+    // Signs at -inf and +inf follow the leading coefficients and the degrees
+    let changes = |at_infinity: bool| {
+        let signs: Vec<bool> = chain.iter()
+            .map(|p| p[p.len() - 1].is_positive() == (at_infinity || p.len() % 2 == 1))
+            .collect();
+        signs.windows(2).filter(|pair| pair[0] != pair[1]).count()
+    };
+    changes(false) - changes(true)
+}
+
+/// One root from floats checked against the exact polynomial
+#[derive(Clone, Debug)]
+pub struct RootCheck {
+    pub root: f32,
+    /// Exact value of the polynomial at the root, rounded
+    pub residual: f64,
+    /// Ulps to either side of the root within which the exact polynomial changes sign or
+    /// vanishes, None past `MAX_ROOT_ULPS`
+    pub ulps: Option<u32>
+}
+
+/// The roots `cubic_roots` found, checked one by one, and how many distinct real roots the
+/// polynomial has exactly
+#[derive(Clone, Debug)]
+pub struct RootReport {
+    pub checks: Vec<RootCheck>,
+    pub exact_count: usize,
+    /// Distinct roots among the checked ones, those that bracket the same sign change once
+    pub found: usize
+}
+
+impl RootReport {
+    /// Roots further than `MAX_ROOT_ULPS` from any true root, and true roots that were missed
+    pub fn discrepancies(&self) -> usize {
+        self.checks.iter().filter(|check| check.ulps.is_none()).count() + self.exact_count.saturating_sub(self.found)
+    }
+}
+
+// Position of `x` among all floats in order, so the difference of two is the ulps between them
+fn ulp_index(x: f32) -> i64 {
+    let bits = x.to_bits() as i32;
+    match bits < 0 {
+        true => i32::MIN as i64 - bits as i64,
+        false => bits as i64
+    }
+}
+
+/// Run `cubic_roots` on c0 + c1 t + c2 t^2 + c3 t^3 and check what it returns exactly
+pub fn verify_roots(coefficients: [f32; 4]) -> RootReport {
+    let polynomial: Polynomial = coefficients.iter().map(|&c| rational(c)).collect();
+    let sign = |t: f32| eval(&polynomial, &rational(t)).signum();
+
+    let mut roots = cubic_roots(coefficients);
+    roots.sort_by(f32::total_cmp);
+    let checks: Vec<RootCheck> = roots.iter()
+        .map(|&root| {
+            let residual = eval(&polynomial, &rational(root)).to_f64().unwrap_or(f64::NAN);
+            let (mut low, mut high) = (root, root);
+            let ulps = (0..=MAX_ROOT_ULPS).find(|&k| {
+                if k > 0 { (low, high) = (low.next_down(), high.next_up()) };
+                (sign(low) * sign(high)) <= BigRational::zero()
+            });
+            RootCheck { root, residual, ulps }
+        })
+        .collect();
+
+    // Neighbours whose brackets overlap are the same root found twice
+    let found = checks.windows(2)
+        .filter(|pair| {
+            let reach: i64 = pair.iter().map(|check| check.ulps.unwrap_or(0) as i64).sum();
+            ulp_index(pair[1].root) - ulp_index(pair[0].root) > reach
+        })
+        .count() + usize::from(!checks.is_empty());
+
+    RootReport { checks, exact_count: count_real_roots(&polynomial), found }
+}
+
+/// Where the curve is at `t` with no rounding at all
+pub fn exact_point(points: [Point; 4], t: f32) -> [BigRational; 2] {
+    // Bernstein's form straight from the control points, the power basis would already be rounded
+    let t = rational(t);
+    let s = small(1) - &t;
+    let weights = [&s * &s * &s, small(3) * &s * &s * &t, small(3) * &s * &t * &t, &t * &t * &t];
+    let axis = |c: fn(Vec2) -> f32| {
+        points.iter().zip(&weights).fold(BigRational::zero(), |sum, (p, w)| sum + rational(c(p.pos)) * w)
+    };
+    [axis(|p| p.x), axis(|p| p.y)]
+}
+
+/// One crossing from `intersections` checked against exact arithmetic
+#[derive(Clone, Debug)]
+pub struct IntersectionCheck {
+    pub ta: f32,
+    pub tb: f32,
+    /// Distance between the two curves at the crossing's parameters, in floats
+    pub float_gap: f32,
+    /// The same distance computed exactly, then rounded
+    pub exact_gap: f64
+}
+
+impl IntersectionCheck {
+    /// Overlapping boxes smaller than `tolerance` hold points at most 2√2 `tolerance` apart,
+    /// the segments really being further apart than that means the crossing isn't one
+    pub fn is_discrepancy(&self, tolerance: f32) -> bool {
+        self.exact_gap > 2. * std::f64::consts::SQRT_2 * tolerance as f64
+    }
+}
+
+/// Run `intersections` on the two segments and measure exactly how far apart they are at
+/// every crossing it found
+pub fn verify_intersections(a: [Point; 4], b: [Point; 4], tolerance: f32) -> Vec<IntersectionCheck> {
+    intersections(&a, &b, tolerance).into_iter()
+        .map(|(ta, tb)| {
+            let ([ax, ay], [bx, by]) = (exact_point(a, ta), exact_point(b, tb));
+            let (dx, dy) = (ax - bx, ay - by);
+            let squared = &dx * &dx + &dy * &dy;
+            IntersectionCheck {
+                ta,
+                tb,
+                float_gap: cubic_bezier(ta, &a).distance(cubic_bezier(tb, &b)),
+                exact_gap: squared.to_f64().unwrap_or(f64::INFINITY).sqrt()
+            }
+        })
+        .collect()
+}
+
+/// Totals of `verify_curve`
+#[derive(Clone, Debug, Default)]
+pub struct CurveReport {
+    pub roots: usize,
+    pub bad_roots: usize,
+    pub crossings: usize,
+    pub bad_crossings: usize,
+    /// Widest gap at a crossing as the floats and the exact arithmetic saw it
+    pub widest_gap: (f32, f64)
+}
+
+/// Check the roots behind the curve's tight boxes, where each axis' derivative vanishes,
+/// and its crossings with every segment of `others`
+pub fn verify_curve(curve: &Curve, others: &[&Curve]) -> CurveReport {
+    let segments = curve.segments();
+    let mut report = CurveReport::default();
+
+    for &segment in &segments {
+        let [_, c1, c2, c3] = power_basis(segment);
+        for axis in [|v: Vec2| v.x, |v: Vec2| v.y] {
+            let roots = verify_roots([axis(c1), 2. * axis(c2), 3. * axis(c3), 0.]);
+            report.roots += roots.checks.len();
+            report.bad_roots += roots.discrepancies();
+        }
+    }
+
+    let other_segments: Vec<[Point; 4]> = others.iter().flat_map(|other| other.segments()).collect();
+    for &a in &segments {
+        for &b in &other_segments {
+            for check in verify_intersections(a, b, CROSSING_TOLERANCE) {
+                report.crossings += 1;
+                report.bad_crossings += usize::from(check.is_discrepancy(CROSSING_TOLERANCE));
+                if check.exact_gap > report.widest_gap.1 {
+                    report.widest_gap = (check.float_gap, check.exact_gap);
+                }
+            }
+        }
+    }
+
+    report
+}
@@ -0,0 +1,161 @@
+use macroquad::prelude::*;
+use serde::Serialize;
+
+use crate::coords::CoordinateSystem;
+use crate::curve::Curve;
+
+/// Suggested name for exported colliders
+pub const COLLIDER_FILE: &str = "colliders.json";
+
+/// Language of an exported code snippet
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CodeSyntax {
+    #[default]
+    Rust,
+    C,
+    Glsl
+}
+
+impl CodeSyntax {
+    pub fn next(self) -> Self {
+        match self {
+            CodeSyntax::Rust => CodeSyntax::C,
+            CodeSyntax::C => CodeSyntax::Glsl,
+            CodeSyntax::Glsl => CodeSyntax::Rust
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            CodeSyntax::Rust => "Rust",
+            CodeSyntax::C => "C",
+            CodeSyntax::Glsl => "GLSL"
+        }
+    }
+}
+
+// Up to four decimals but always a float literal, "2.0" rather than "2"
+fn float(value: f32) -> String {
+    let text = format!("{:.4}", value);
+    let text = text.trim_end_matches('0');
+    match text.strip_suffix('.') {
+        Some("-0") => "0.0".to_string(),
+        Some(whole) => format!("{}.0", whole),
+        None => text.to_string()
+    }
+}
+
+/// The control points as an array constant to paste into code, in the user's `coords`
+pub fn control_points_code(curve: &Curve, coords: &CoordinateSystem, syntax: CodeSyntax) -> String {
+    let points: Vec<Vec2> = curve.control.iter().map(|p| coords.to_math(p.pos)).collect();
+    let kind = match curve.knots.is_some() {
+        true => "cubic B-spline control polygon",
+        false => "cubic bezier segments, anchors every 3 points"
+    };
+    let closed = match curve.closed {
+        true => ", closed",
+        false => ""
+    };
+    let header = format!("// {} control points, {}{}", points.len(), kind, closed);
+
+    let (open, close, item, last_comma) = match syntax {
+        CodeSyntax::Rust => ("const CONTROL_POINTS: &[Vec2] = &[".to_string(), "];", "Vec2::new", true),
+        CodeSyntax::C => ("static const float CONTROL_POINTS[][2] = {".to_string(), "};", "", true),
+        CodeSyntax::Glsl => (format!("const vec2 CONTROL_POINTS[{0}] = vec2[{0}](", points.len()), ");", "vec2", false)
+    };
+    let lines: Vec<String> = points.iter().enumerate().map(|(i, p)| {
+        let comma = match i + 1 < points.len() || last_comma {
+            true => ",",
+            false => ""
+        };
+        match syntax {
+            CodeSyntax::C => format!("    {{{}f, {}f}}{}", float(p.x), float(p.y), comma),
+            _ => format!("    {}({}, {}){}", item, float(p.x), float(p.y), comma)
+        }
+    }).collect();
+
+    format!("{}\n{}\n{}\n{}", header, open, lines.join("\n"), close)
+}
+
+/// Direction closed collider chains go round in the user's coordinates. Engines differ in
+/// which side of a chain collides, open chains always keep the direction they were drawn in
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ChainWinding {
+    #[default]
+    CounterClockwise,
+    Clockwise,
+    AsDrawn
+}
+
+impl ChainWinding {
+    /// "ccw", "cw" or "as_drawn"
+    pub fn named(name: &str) -> Option<Self> {
+        match name {
+            "ccw" => Some(ChainWinding::CounterClockwise),
+            "cw" => Some(ChainWinding::Clockwise),
+            "as_drawn" => Some(ChainWinding::AsDrawn),
+            _ => None
+        }
+    }
+}
+
+/// How curves are flattened into collider chains
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ColliderOptions {
+    /// Farthest the chain strays from the curve, in units
+    pub tolerance: f32,
+    pub winding: ChainWinding
+}
+
+impl Default for ColliderOptions {
+    fn default() -> Self {
+        Self { tolerance: 0.02, winding: ChainWinding::default() }
+    }
+}
+
+#[derive(Serialize)]
+struct ColliderFile {
+    tolerance: f32,
+    chains: Vec<ChainFile>
+}
+
+#[derive(Serialize)]
+struct ChainFile {
+    closed: bool,
+    points: Vec<[f32; 2]>
+}
+
+// Shoelace formula, positive when counter-clockwise with y up
+fn signed_area(points: &[Vec2]) -> f32 {
+    let n = points.len();
+    (0..n).map(|i| points[i].perp_dot(points[(i + 1) % n])).sum::<f32>() / 2.
+}
+
+/// The curves flattened into chains of points in the user's `coords`, as JSON for the chain
+/// and polygon colliders of engines like Box2D, Godot or Bevy's physics crates. Closed
+/// chains don't repeat their first point at the end
+pub fn collider_json<'a>(curves: impl IntoIterator<Item = &'a Curve>, coords: &CoordinateSystem, options: ColliderOptions) -> String {
+    let tolerance = options.tolerance * coords.pixels_per_unit;
+    let chains = curves.into_iter()
+        .filter_map(|curve| {
+            let mut points: Vec<Vec2> = curve.flatten(tolerance).iter().map(|p| coords.to_math(p.pos)).collect();
+            if points.len() < 2 { return None };
+            if curve.closed && points.len() > 2 && points[0].distance(points[points.len() - 1]) < 1e-6 {
+                points.pop();
+            }
+
+            let reverse = match options.winding {
+                ChainWinding::CounterClockwise => signed_area(&points) < 0.,
+                ChainWinding::Clockwise => signed_area(&points) > 0.,
+                ChainWinding::AsDrawn => false
+            };
+            if curve.closed && reverse {
+                points.reverse();
+            }
+            Some(ChainFile { closed: curve.closed, points: points.iter().map(|p| [p.x, p.y]).collect() })
+        })
+        .collect();
+
+    let file = ColliderFile { tolerance: options.tolerance, chains };
+    serde_json::to_string_pretty(&file).expect("colliders should serialize")
+}
@@ -0,0 +1,84 @@
+use macroquad::prelude::*;
+
+use crate::curve::Curve;
+use crate::math::flatten;
+
+/// Which points count as inside a self-intersecting or overlapping path
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FillRule {
+    /// Inside where the path winds around a nonzero number of times
+    #[default]
+    NonZero,
+    /// Inside where a ray crosses the path an odd number of times
+    EvenOdd
+}
+
+impl FillRule {
+    pub fn label(self) -> &'static str {
+        match self {
+            FillRule::NonZero => "nonzero",
+            FillRule::EvenOdd => "even-odd"
+        }
+    }
+
+    fn inside(self, winding: i32) -> bool {
+        match self {
+            FillRule::NonZero => winding != 0,
+            FillRule::EvenOdd => winding % 2 != 0
+        }
+    }
+}
+
+/// Edges of the closed curve flattened within `tolerance`, empty for open curves
+pub fn fill_edges(curve: &Curve, tolerance: f32) -> Vec<(Vec2, Vec2)> {
+    if !curve.closed { return Vec::new() };
+
+    let mut points: Vec<Vec2> = curve.segments().iter()
+        .flat_map(|segment| flatten(segment, tolerance).into_iter().map(|p| p.pos))
+        .collect();
+    points.dedup();
+    if points.len() < 3 { return Vec::new() };
+
+    let last = points[points.len() - 1];
+    points.windows(2).map(|pair| (pair[0], pair[1])).chain([(last, points[0])]).collect()
+}
+
+/// Spans (x0, x1) of the row at height `y` that are inside the edges under `rule`. Each
+/// crossing adds the direction the edge crosses the row in to the winding, left to right
+pub fn scanline_spans(edges: &[(Vec2, Vec2)], y: f32, rule: FillRule) -> Vec<(f32, f32)> {
+    let mut crossings: Vec<(f32, i32)> = edges.iter()
+        .filter(|(a, b)| (a.y <= y) != (b.y <= y))
+        .map(|&(a, b)| {
+            let x = a.x + (y - a.y) / (b.y - a.y) * (b.x - a.x);
+            (x, if b.y > a.y { 1 } else { -1 })
+        })
+        .collect();
+    crossings.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    let mut spans = Vec::new();
+    let mut winding = 0;
+    for pair in crossings.windows(2) {
+        winding += pair[0].1;
+        if rule.inside(winding) && pair[1].0 > pair[0].0 {
+            spans.push((pair[0].0, pair[1].0));
+        }
+    }
+
+    spans
+}
+
+/// Fill the closed curve row by row, `row_height` world units apart, over the rows within
+/// `visible` (min and max world corners). Drawn in world space
+pub fn draw_fill(curve: &Curve, rule: FillRule, tolerance: f32, row_height: f32, visible: [Vec2; 2], color: Color) {
+    let edges = fill_edges(curve, tolerance);
+    let Some([min, max]) = curve.bounds().filter(|_| !edges.is_empty()) else { return };
+
+    let (top, bottom) = (min.y.max(visible[0].y), max.y.min(visible[1].y));
+    let rows = ((bottom - top) / row_height).ceil().max(0.) as usize;
+    for row in 0..rows {
+        let y = top + row as f32 * row_height;
+        for (x0, x1) in scanline_spans(&edges, y + row_height / 2., rule) {
+            draw_rectangle(x0, y, x1 - x0, row_height, color);
+        }
+    }
+}
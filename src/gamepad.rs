@@ -0,0 +1,112 @@
+use gilrs::{Axis, Button, EventType, Gilrs};
+use macroquad::prelude::*;
+
+use crate::pointer::Pointer;
+
+// Screen pixels per second the cursor moves at full tilt
+const CURSOR_SPEED: f32 = 700.0;
+const PAN_SPEED: f32 = 900.0;
+// Zoom factor per second with a trigger fully pressed
+const ZOOM_SPEED: f32 = 3.0;
+const DEADZONE: f32 = 0.15;
+
+/// Buttons that stand in for keyboard shortcuts, by keymap action
+const BUTTON_ACTIONS: &[(Button, &str)] = &[
+    (Button::North, "toggle_bspline"),
+    (Button::West, "close_curve"),
+    (Button::Start, "new_curve"),
+    (Button::Select, "next_curve"),
+    (Button::DPadUp, "auto_smooth"),
+    (Button::DPadDown, "split_segment"),
+    (Button::DPadLeft, "cycle_continuity"),
+    (Button::DPadRight, "zoom_to_fit"),
+    (Button::LeftThumb, "grid"),
+    (Button::RightThumb, "info")
+];
+
+fn deadzone(value: f32) -> f32 {
+    match value.abs() < DEADZONE {
+        true => 0.,
+        false => value
+    }
+}
+
+/// Drives the editor from a controller: the left stick moves a cursor, the right one pans,
+/// the triggers zoom, south adds and drags, east deletes and the rest trigger shortcuts.
+/// Moving the mouse hands control back to it
+pub struct Gamepad {
+    gilrs: Option<Gilrs>,
+    cursor: Vec2,
+    active: bool,
+    last_mouse: Vec2
+}
+
+impl Default for Gamepad {
+    fn default() -> Self {
+        let gilrs = Gilrs::new()
+            .map_err(|error| warn!("gamepads unavailable: {}", error))
+            .ok();
+        let mouse: Vec2 = mouse_position().into();
+        Self { gilrs, cursor: mouse, active: false, last_mouse: mouse }
+    }
+}
+
+impl Gamepad {
+    /// The controller's input this frame, None while the mouse is in charge
+    pub fn pointer(&mut self) -> Option<Pointer> {
+        let gilrs = self.gilrs.as_mut()?;
+        let mouse: Vec2 = mouse_position().into();
+        if mouse != self.last_mouse {
+            self.active = false;
+            self.cursor = mouse;
+        }
+        self.last_mouse = mouse;
+
+        let mut pressed = Vec::new();
+        while let Some(event) = gilrs.next_event() {
+            match event.event {
+                EventType::ButtonPressed(button, _) => {
+                    pressed.push(button);
+                    self.active = true;
+                },
+                EventType::AxisChanged(_, value, _) if value.abs() >= DEADZONE => self.active = true,
+                _ => ()
+            }
+        }
+        if !self.active { return None };
+
+        // Any connected pad can drive, the first one with its sticks moved wins
+        let dt = get_frame_time();
+        let (mut stick, mut pan, mut zoom, mut primary_down) = (Vec2::ZERO, Vec2::ZERO, 0., false);
+        for (_, pad) in gilrs.gamepads() {
+            let left = vec2(deadzone(pad.value(Axis::LeftStickX)), -deadzone(pad.value(Axis::LeftStickY)));
+            let right = vec2(deadzone(pad.value(Axis::RightStickX)), -deadzone(pad.value(Axis::RightStickY)));
+            if stick == Vec2::ZERO { stick = left };
+            if pan == Vec2::ZERO { pan = right };
+            let trigger = |button| pad.button_data(button).map_or(0., |data| data.value());
+            zoom += trigger(Button::RightTrigger2) - trigger(Button::LeftTrigger2);
+            primary_down |= pad.is_pressed(Button::South);
+        }
+
+        let size = vec2(screen_width(), screen_height());
+        self.cursor = (self.cursor + stick * CURSOR_SPEED * dt).clamp(Vec2::ZERO, size);
+
+        Some(Pointer {
+            position: self.cursor,
+            primary_pressed: pressed.contains(&Button::South),
+            primary_down,
+            secondary_pressed: pressed.contains(&Button::East),
+            pan: -pan * PAN_SPEED * dt,
+            zoom: ZOOM_SPEED.powf(zoom * dt),
+            actions: BUTTON_ACTIONS.iter()
+                .filter(|(button, _)| pressed.contains(button))
+                .map(|&(_, action)| action)
+                .collect()
+        })
+    }
+
+    /// Where to draw the controller's cursor, when it's the one in use
+    pub fn cursor(&self) -> Option<Vec2> {
+        self.active.then_some(self.cursor)
+    }
+}
@@ -0,0 +1,93 @@
+use macroquad::prelude::*;
+
+use crate::coords::{nice_step, tick_label, CoordinateSystem};
+use crate::theme::Theme;
+use crate::view::View;
+
+// Automatic spacing keeps major lines at least this many pixels apart
+const MIN_MAJOR_SPACING: f32 = 80.0;
+// Lines closer than this on screen are skipped instead of filling the window
+const MIN_LINE_SPACING: f32 = 4.0;
+const LABEL_SIZE: f32 = 14.0;
+
+/// Background grid in units of the coordinate system. Major lines are `spacing` apart, or
+/// a round number picked from the zoom when it's `None`, with `subdivisions` minor steps
+pub struct Grid {
+    pub spacing: Option<f32>,
+    pub subdivisions: usize
+}
+
+impl Default for Grid {
+    fn default() -> Self {
+        Self { spacing: None, subdivisions: 5 }
+    }
+}
+
+/// Line positions between `from` and `to` with whether each one is major. Minor lines are
+/// dropped when too dense on screen, and then the major ones too
+fn grid_values(from: f32, to: f32, minor: f32, major: f32, pixels_per_unit: f32) -> Vec<(f32, bool)> {
+    let step = match (minor * pixels_per_unit >= MIN_LINE_SPACING, major * pixels_per_unit >= MIN_LINE_SPACING) {
+        (true, _) => minor,
+        (false, true) => major,
+        (false, false) => return Vec::new()
+    };
+
+    let (first, last) = ((from / step).floor() as i64, (to / step).ceil() as i64);
+    (first..=last).map(|i| {
+        let value = i as f32 * step;
+        (value, ((value / major).round() * major - value).abs() < step / 2.)
+    }).collect()
+}
+
+impl Grid {
+    /// Major line spacing in units at the view's zoom
+    pub fn major_step(&self, view: &View, coords: &CoordinateSystem) -> f32 {
+        self.spacing.unwrap_or_else(|| nice_step(MIN_MAJOR_SPACING / (coords.pixels_per_unit * view.scale)))
+    }
+
+    /// Halve or double the spacing, starting from the automatic one
+    pub fn scale_spacing(&mut self, factor: f32, view: &View, coords: &CoordinateSystem) {
+        self.spacing = Some((self.major_step(view, coords) * factor).clamp(1e-4, 1e6));
+    }
+
+    pub fn draw(&self, view: &View, coords: &CoordinateSystem, theme: &Theme) {
+        let line_color = |is_major: bool| match is_major {
+            true => theme.grid_major,
+            false => theme.grid_minor
+        };
+        let major = self.major_step(view, coords);
+        let minor = major / self.subdivisions.max(1) as f32;
+        let pixels_per_unit = coords.pixels_per_unit * view.scale;
+        let to_screen = |math: Vec2| view.world_to_screen(coords.from_math(math));
+
+        let corner_a = coords.to_math(view.screen_to_world(Vec2::ZERO));
+        let corner_b = coords.to_math(view.screen_to_world(vec2(screen_width(), screen_height())));
+        let (low, high) = (corner_a.min(corner_b), corner_a.max(corner_b));
+        let origin = to_screen(Vec2::ZERO);
+
+        for (x, is_major) in grid_values(low.x, high.x, minor, major, pixels_per_unit) {
+            let sx = to_screen(vec2(x, 0.)).x;
+            draw_line(sx, 0., sx, screen_height(), 1., line_color(is_major));
+            if is_major && x.abs() > major / 2. {
+                let y = origin.y.clamp(LABEL_SIZE, screen_height() - 4.);
+                draw_text(&tick_label(x, major), sx + 3., y - 3., LABEL_SIZE, GRAY);
+            }
+        }
+
+        for (y, is_major) in grid_values(low.y, high.y, minor, major, pixels_per_unit) {
+            let sy = to_screen(vec2(0., y)).y;
+            draw_line(0., sy, screen_width(), sy, 1., line_color(is_major));
+            if is_major && y.abs() > major / 2. {
+                let text = tick_label(y, major);
+                let width = measure_text(&text, None, LABEL_SIZE as u16, 1.).width;
+                let x = origin.x.clamp(2., screen_width() - width - 6.);
+                draw_text(&text, x + 3., sy - 3., LABEL_SIZE, GRAY);
+            }
+        }
+
+        draw_line(0., origin.y, screen_width(), origin.y, 1., theme.axis);
+        draw_line(origin.x, 0., origin.x, screen_height(), 1., theme.axis);
+        draw_circle(origin.x, origin.y, 5., theme.axis);
+        draw_text("0", origin.x + 3., origin.y - 3., LABEL_SIZE, GRAY);
+    }
+}
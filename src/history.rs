@@ -0,0 +1,173 @@
+use crate::document::Document;
+use crate::project;
+
+/// A recorded document state and the state it was edited from
+pub struct HistoryEntry {
+    pub parent: Option<usize>,
+    /// The document as project JSON, which is already how it's saved and autosaved
+    pub state: String,
+    /// Row of the timeline the entry is drawn on, each branch gets its own
+    pub row: usize
+}
+
+/// Every state the document has been in as a tree. Going back and editing starts a new
+/// branch from there instead of dropping the states after it
+pub struct History {
+    entries: Vec<HistoryEntry>,
+    current: usize,
+    // Newest entry of the branch being looked at, going back keeps it to come forward again
+    tip: usize,
+    rows: usize
+}
+
+impl History {
+    pub fn new(doc: &Document) -> Self {
+        let root = HistoryEntry { parent: None, state: project::to_json(doc), row: 0 };
+        Self { entries: vec![root], current: 0, tip: 0, rows: 1 }
+    }
+
+    pub fn entries(&self) -> &[HistoryEntry] {
+        &self.entries
+    }
+
+    pub fn current(&self) -> usize {
+        self.current
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// Add the document as a child of the current state when it differs from it, returns
+    /// whether it did
+    pub fn record(&mut self, doc: &Document) -> bool {
+        let state = project::to_json(doc);
+        if state == self.entries[self.current].state { return false };
+
+        // The first child continues its parent's row, later ones branch off below
+        let has_children = self.entries.iter().any(|entry| entry.parent == Some(self.current));
+        let row = match has_children {
+            true => {
+                self.rows += 1;
+                self.rows - 1
+            },
+            false => self.entries[self.current].row
+        };
+        self.entries.push(HistoryEntry { parent: Some(self.current), state, row });
+        self.current = self.entries.len() - 1;
+        self.tip = self.current;
+        true
+    }
+
+    /// Entries from the first state to the tip of the branch being looked at, in order
+    pub fn branch(&self) -> Vec<usize> {
+        let mut branch: Vec<usize> = std::iter::successors(Some(self.tip), |&i| self.entries[i].parent).collect();
+        branch.reverse();
+        branch
+    }
+
+    /// Go to entry `index`, returning its document. Entries off the current branch switch
+    /// to the newest state after them
+    pub fn jump(&mut self, index: usize) -> Option<Document> {
+        if index >= self.entries.len() { return None };
+        if !self.branch().contains(&index) {
+            self.tip = index;
+            while let Some(child) = self.entries.iter().rposition(|entry| entry.parent == Some(self.tip)) {
+                self.tip = child;
+            }
+        }
+        self.current = index;
+        // The states are written by `to_json`, they always read back
+        project::from_json(&self.entries[index].state).ok()
+    }
+
+    /// The state before the current one
+    pub fn back(&mut self) -> Option<Document> {
+        let parent = self.entries[self.current].parent?;
+        self.jump(parent)
+    }
+
+    /// The state after the current one on the branch being looked at
+    pub fn forward(&mut self) -> Option<Document> {
+        let branch = self.branch();
+        let position = branch.iter().position(|&i| i == self.current)?;
+        let next = *branch.get(position + 1)?;
+        self.jump(next)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use macroquad::prelude::*;
+
+    use super::*;
+    use crate::point::Point;
+
+    // A document told apart by how many points its curve has
+    fn doc(points: usize) -> Document {
+        let mut doc = Document::default();
+        doc.curves[0].control = (0..points).map(|i| Point::new(vec2(i as f32, 0.), WHITE)).collect();
+        doc
+    }
+
+    fn points(doc: Option<Document>) -> usize {
+        doc.unwrap().curves[0].control.len()
+    }
+
+    // 0 - 1 - 2
+    //      \
+    //       3 - 4
+    fn two_branches() -> History {
+        let mut history = History::new(&doc(0));
+        assert!(history.record(&doc(1)));
+        assert!(history.record(&doc(2)));
+        assert_eq!(points(history.back()), 1);
+        assert!(history.record(&doc(3)));
+        assert!(history.record(&doc(4)));
+        history
+    }
+
+    #[test]
+    fn recording_branches_onto_new_rows() {
+        let history = two_branches();
+        let rows: Vec<usize> = history.entries().iter().map(|entry| entry.row).collect();
+        assert_eq!(rows, [0, 0, 0, 1, 1]);
+        assert_eq!(history.rows(), 2);
+        assert_eq!(history.entries()[3].parent, Some(1));
+        assert_eq!(history.branch(), [0, 1, 3, 4]);
+        assert_eq!(history.current(), 4);
+    }
+
+    #[test]
+    fn unchanged_documents_arent_recorded() {
+        let mut history = two_branches();
+        assert!(!history.record(&doc(4)));
+        assert_eq!(history.entries().len(), 5);
+    }
+
+    #[test]
+    fn jumping_off_the_branch_follows_it_to_its_newest_state() {
+        let mut history = two_branches();
+        assert_eq!(points(history.jump(2)), 2);
+        assert_eq!(history.branch(), [0, 1, 2]);
+
+        assert_eq!(points(history.jump(3)), 3);
+        assert_eq!(history.branch(), [0, 1, 3, 4]);
+        assert_eq!(history.current(), 3);
+        assert!(history.jump(5).is_none());
+    }
+
+    #[test]
+    fn back_and_forward_stay_on_the_branch() {
+        let mut history = two_branches();
+        assert_eq!(points(history.back()), 3);
+        assert_eq!(points(history.back()), 1);
+        assert_eq!(points(history.back()), 0);
+        assert!(history.back().is_none());
+
+        assert_eq!(points(history.forward()), 1);
+        assert_eq!(points(history.forward()), 3);
+        assert_eq!(points(history.forward()), 4);
+        assert!(history.forward().is_none());
+    }
+}
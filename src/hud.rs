@@ -0,0 +1,50 @@
+use macroquad::prelude::*;
+
+use crate::curve::CurveMetrics;
+
+const HUD_FONT_SIZE: f32 = 20.0;
+const HUD_MAX_SEGMENT_LINES: usize = 8;
+
+/// Info box on the top left corner describing the active curve, drawn in screen space
+pub fn draw_hud(metrics: &CurveMetrics) {
+    let mut lines = vec![
+        format!("length: {:.2}", metrics.length),
+        format!("segments: {}", metrics.segment_lengths.len()),
+    ];
+
+    for (i, length) in metrics.segment_lengths.iter().enumerate().take(HUD_MAX_SEGMENT_LINES) {
+        lines.push(format!("  #{}: {:.2}", i, length));
+    }
+    if metrics.segment_lengths.len() > HUD_MAX_SEGMENT_LINES {
+        lines.push(format!("  ... {} more", metrics.segment_lengths.len() - HUD_MAX_SEGMENT_LINES));
+    }
+
+    lines.push(format!("max curvature: {:.4}", metrics.max_curvature));
+    match metrics.bounds_size {
+        Some(size) => lines.push(format!("bounds: {:.1} x {:.1}", size.x, size.y)),
+        None => lines.push("bounds: -".to_string()),
+    }
+
+    if let Some(shape) = metrics.shape {
+        lines.push(format!("signed area: {:.2}", shape.signed_area));
+        lines.push(format!("centroid: ({:.1}, {:.1})", shape.centroid.x, shape.centroid.y));
+        lines.push(format!("Ixx: {:.4e}  Iyy: {:.4e}", shape.ixx, shape.iyy));
+        lines.push(format!("Ixy: {:.4e}  J: {:.4e}", shape.ixy, shape.polar_moment()));
+    }
+
+    draw_text_box(&lines, vec2(10., 10.));
+}
+
+/// Lines of text over a translucent background, `origin` is the top left corner
+pub fn draw_text_box(lines: &[String], origin: Vec2) {
+    let width = lines.iter()
+        .map(|l| measure_text(l, None, HUD_FONT_SIZE as u16, 1.0).width)
+        .fold(0., f32::max);
+    let height = lines.len() as f32 * HUD_FONT_SIZE;
+
+    draw_rectangle(origin.x, origin.y, width + 20., height + 10., Color::new(0., 0., 0., 0.7));
+    for (i, line) in lines.iter().enumerate() {
+        let y = origin.y + HUD_FONT_SIZE * (i as f32 + 1.);
+        draw_text(line, origin.x + 10., y, HUD_FONT_SIZE, WHITE);
+    }
+}
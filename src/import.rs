@@ -0,0 +1,71 @@
+use std::fmt;
+
+use macroquad::prelude::*;
+
+use crate::coordinate_entry::parse_coordinates;
+use crate::coords::CoordinateSystem;
+use crate::curve::Curve;
+use crate::document::Document;
+use crate::point::Point;
+use crate::project::{self, ProjectError};
+use crate::svg::{parse_svg, SvgError};
+
+/// What opening a file gives, a whole document or curves to add to the current one
+pub enum Opened {
+    Project(Document),
+    Curves(Vec<Curve>)
+}
+
+#[derive(Debug)]
+pub enum ImportError {
+    UnknownFormat(String),
+    Project(ProjectError),
+    Svg(SvgError),
+    /// 1-based line of a CSV file that isn't a pair of numbers
+    BadLine(usize),
+    NoPoints
+}
+
+impl fmt::Display for ImportError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ImportError::UnknownFormat(name) => write!(f, "don't know how to open \"{}\", expected .json, .svg or .csv", name),
+            ImportError::Project(error) => write!(f, "{}", error),
+            ImportError::Svg(error) => write!(f, "{}", error),
+            ImportError::BadLine(line) => write!(f, "line {} should be two numbers, like 1.5, -2", line),
+            ImportError::NoPoints => write!(f, "no points in the file")
+        }
+    }
+}
+
+impl std::error::Error for ImportError {}
+
+/// One curve through the "x, y" pairs of each line, in the user's `coords`. A first line
+/// that isn't numbers is taken as a header
+pub fn parse_csv(text: &str, coords: &CoordinateSystem, colors: &mut impl Iterator<Item = Color>) -> Result<Curve, ImportError> {
+    let mut control = Vec::new();
+    for (i, line) in text.lines().enumerate() {
+        if line.trim().is_empty() { continue };
+        match parse_coordinates(line) {
+            Some(pos) => control.push(Point::new(coords.from_math(pos), colors.next().unwrap_or(WHITE))),
+            None if i == 0 => (),
+            None => return Err(ImportError::BadLine(i + 1))
+        }
+    }
+
+    match control.is_empty() {
+        true => Err(ImportError::NoPoints),
+        false => Ok(Curve { control, modified: true, ..Default::default() })
+    }
+}
+
+/// Read the file called `name` by its extension, a project, an SVG or a CSV of points
+pub fn open_file(name: &str, text: &str, coords: &CoordinateSystem, colors: &mut impl Iterator<Item = Color>) -> Result<Opened, ImportError> {
+    let extension = name.rsplit_once('.').map(|(_, extension)| extension.to_lowercase());
+    match extension.as_deref() {
+        Some("json") => project::from_json(text).map(Opened::Project).map_err(ImportError::Project),
+        Some("svg") => parse_svg(text, colors).map(Opened::Curves).map_err(ImportError::Svg),
+        Some("csv" | "txt") => parse_csv(text, coords, colors).map(|curve| Opened::Curves(vec![curve])),
+        _ => Err(ImportError::UnknownFormat(name.to_string()))
+    }
+}
@@ -0,0 +1,193 @@
+use std::ops::{Add, Mul, Sub};
+
+use macroquad::prelude::*;
+
+use crate::point::Point;
+
+/// Pieces the parameter range is split into for `conservative_box`. Each piece's box
+/// overshoots by about the square of its width, more pieces hug the curve closer
+pub const INTERVAL_BOX_PIECES: usize = 16;
+
+/// Closed range of reals [lo, hi]. Operations round outwards by an ulp, so the exact result
+/// for any numbers inside the operands is always inside the result
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Interval {
+    pub lo: f32,
+    pub hi: f32
+}
+
+impl Interval {
+    pub fn new(lo: f32, hi: f32) -> Self {
+        Self { lo: lo.min(hi), hi: lo.max(hi) }
+    }
+
+    pub fn point(x: f32) -> Self {
+        Self { lo: x, hi: x }
+    }
+
+    // Widen by an ulp on each side, enough to cover one correctly rounded operation
+    fn outward(lo: f32, hi: f32) -> Self {
+        Self { lo: lo.next_down(), hi: hi.next_up() }
+    }
+
+    pub fn contains(&self, x: f32) -> bool {
+        self.lo <= x && x <= self.hi
+    }
+
+    pub fn width(&self) -> f32 {
+        self.hi - self.lo
+    }
+
+    /// Smallest interval holding both
+    pub fn hull(self, other: Self) -> Self {
+        Self { lo: self.lo.min(other.lo), hi: self.hi.max(other.hi) }
+    }
+}
+
+impl Add for Interval {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self::outward(self.lo + other.lo, self.hi + other.hi)
+    }
+}
+
+impl Sub for Interval {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self::outward(self.lo - other.hi, self.hi - other.lo)
+    }
+}
+
+impl Mul for Interval {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        let products = [self.lo * other.lo, self.lo * other.hi, self.hi * other.lo, self.hi * other.hi];
+        Self::outward(products.into_iter().fold(f32::MAX, f32::min), products.into_iter().fold(f32::MIN, f32::max))
+    }
+}
+
+impl Mul<f32> for Interval {
+    type Output = Self;
+
+    fn mul(self, k: f32) -> Self {
+        self * Interval::point(k)
+    }
+}
+
+// B(t) on an interval in Bernstein's form nested Horner style, for one axis. The
+// dependency between t and 1 - t makes it as loose as the control points are far from zero
+fn bernstein_range(p: [Interval; 4], t: Interval) -> Interval {
+    let s = Interval::point(1.) - t;
+    let t2 = t * t;
+    ((p[0] * s + p[1] * t * 3.) * s + p[2] * t2 * 3.) * s + p[3] * t2 * t
+}
+
+// B'(t) on an interval for one axis, as `bernstein_range` on the derivative's points
+fn velocity_range(p: [Interval; 4], t: Interval) -> Interval {
+    let [d0, d1, d2] = [(p[1] - p[0]) * 3., (p[2] - p[1]) * 3., (p[3] - p[2]) * 3.];
+    let s = Interval::point(1.) - t;
+    (d0 * s + d1 * t * 2.) * s + d2 * t * t
+}
+
+/// Ranges of x and y that B(t) takes for every t in `t`. The mean value form, B(c) plus
+/// the range of B' times t - c around the middle c, loses much less to the dependency than
+/// evaluating B directly does, and more so the narrower `t` is
+pub fn eval_range(points: [Point; 4], t: Interval) -> [Interval; 2] {
+    let middle = Interval::point((t.lo + t.hi) / 2.);
+    let axis = |c: fn(Vec2) -> f32| {
+        let p = points.map(|p| Interval::point(c(p.pos)));
+        bernstein_range(p, middle) + velocity_range(p, t) * (t - middle)
+    };
+
+    [axis(|p| p.x), axis(|p| p.y)]
+}
+
+/// Min and max corners of a box guaranteed to hold the segment between `t0` and `t1`, the
+/// union of `eval_range` over `pieces` equal parts. Unlike the tight box it finds no roots,
+/// so coincident or collinear control points can't throw it off
+pub fn conservative_box(points: [Point; 4], t0: f32, t1: f32, pieces: usize) -> [Vec2; 2] {
+    let pieces = pieces.max(1);
+    let h = (t1 - t0) / pieces as f32;
+    let [x, y] = (0..pieces)
+        .map(|i| {
+            // The last piece ends right on t1, so rounding never leaves a sliver out
+            let end = match i + 1 == pieces {
+                true => t1,
+                false => t0 + h * (i + 1) as f32
+            };
+            eval_range(points, Interval::new(t0 + h * i as f32, end))
+        })
+        .reduce(|[ax, ay], [bx, by]| [ax.hull(bx), ay.hull(by)])
+        .unwrap_or([Interval::point(0.); 2]);
+
+    [vec2(x.lo, y.lo), vec2(x.hi, y.hi)]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::curve::Curve;
+
+    fn segment(positions: [Vec2; 4]) -> [Point; 4] {
+        positions.map(|pos| Point::new(pos, WHITE))
+    }
+
+    // B(t) in f64, rounding far below the box's own
+    fn exact(points: [Point; 4], t: f64) -> [f64; 2] {
+        let s = 1. - t;
+        let weights = [s * s * s, 3. * s * s * t, 3. * s * t * t, t * t * t];
+        let axis = |c: fn(Vec2) -> f32| points.iter().zip(weights).map(|(p, w)| c(p.pos) as f64 * w).sum();
+        [axis(|p| p.x), axis(|p| p.y)]
+    }
+
+    fn assert_holds(points: [Point; 4], t0: f32, t1: f32, pieces: usize) {
+        let [min, max] = conservative_box(points, t0, t1, pieces);
+        for i in 0..=2000 {
+            let t = t0 as f64 + (t1 - t0) as f64 * i as f64 / 2000.;
+            let [x, y] = exact(points, t);
+            assert!(min.x as f64 <= x && x <= max.x as f64, "x {} outside at {}", x, t);
+            assert!(min.y as f64 <= y && y <= max.y as f64, "y {} outside at {}", y, t);
+        }
+    }
+
+    fn wavy() -> [Point; 4] {
+        segment([vec2(100., 500.), vec2(250., 80.), vec2(520., 620.), vec2(700., 150.)])
+    }
+
+    #[test]
+    fn box_holds_the_segment() {
+        for pieces in [1, 3, INTERVAL_BOX_PIECES] {
+            assert_holds(wavy(), 0., 1., pieces);
+            assert_holds(wavy(), 0.2, 0.7, pieces);
+        }
+    }
+
+    #[test]
+    fn box_holds_degenerate_segments() {
+        let degenerate = [
+            segment([vec2(3.3, 7.1); 4]),
+            segment([vec2(0., 0.), vec2(0., 0.), vec2(40.7, 13.3), vec2(40.7, 13.3)]),
+            segment([vec2(0., 0.), vec2(30., 10.), vec2(-15., -5.), vec2(60., 20.)]),
+            segment([vec2(0.1, 0.), vec2(0.1, 1e-3), vec2(0.1, -1e-3), vec2(0.1, 0.)])
+        ];
+        for points in degenerate {
+            for pieces in [1, INTERVAL_BOX_PIECES] {
+                assert_holds(points, 0., 1., pieces);
+            }
+        }
+    }
+
+    #[test]
+    fn more_pieces_hug_closer() {
+        let area = |[min, max]: [Vec2; 2]| (max - min).x * (max - min).y;
+        let areas: Vec<f32> = [1, 4, 16, 64].iter().map(|&pieces| area(conservative_box(wavy(), 0., 1., pieces))).collect();
+        assert!(areas.windows(2).all(|pair| pair[1] < pair[0]));
+
+        let [tight_min, tight_max] = Curve::tight_bounding_box(&wavy());
+        let [min, max] = conservative_box(wavy(), 0., 1., 64);
+        assert!(tight_min.distance(min) < 5. && tight_max.distance(max) < 5.);
+    }
+}
@@ -0,0 +1,46 @@
+use macroquad::prelude::*;
+
+use crate::curve::Curve;
+use crate::math::{arc_length, arc_length_between};
+use crate::point::Point;
+
+// Points the generated curve passes through per segment of the original
+const POINTS_PER_SEGMENT: usize = 12;
+
+/// Arc length from the start of `curve` to parameter `t` of segment `segment`
+pub fn length_to(curve: &Curve, segment: usize, t: f32) -> f32 {
+    let segments = curve.segments();
+    let before: f32 = segments.iter().take(segment).map(|&s| arc_length(s)).sum();
+    before + segments.get(segment).map_or(0., |&s| arc_length_between(s, 0., t))
+}
+
+/// The end of a taut string wound on `curve` and unwound from `start` world units along it
+/// to the end: I(s) = B(s) - T(s) (s - start), at `count` + 1 evenly spaced arc lengths
+pub fn involute_points(curve: &Curve, start: f32, count: usize) -> Vec<Vec2> {
+    let total: f32 = curve.segments().into_iter().map(arc_length).sum();
+    if start >= total || count == 0 { return Vec::new() };
+    (0..=count)
+        .filter_map(|i| {
+            let s = start + (total - start) * i as f32 / count as f32;
+            let (pos, tangent) = curve.point_at_length(s)?;
+            Some(pos - tangent * (s - start))
+        })
+        .collect()
+}
+
+/// The involute from `start` world units along `curve` as a new curve of C2 bezier segments
+/// through its points, in the curve's color. None when less than two points are left
+pub fn involute_curve(curve: &Curve, start: f32) -> Option<Curve> {
+    let count = curve.segments().len() * POINTS_PER_SEGMENT;
+    let points = involute_points(curve, start, count);
+    if points.len() < 2 { return None };
+
+    let color = curve.control.first().map_or(WHITE, |p| p.color);
+    let mut involute = Curve {
+        control: points.into_iter().map(|p| Point::new(p, color)).collect(),
+        modified: true,
+        ..Default::default()
+    };
+    involute.auto_smooth();
+    Some(involute)
+}
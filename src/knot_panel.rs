@@ -0,0 +1,123 @@
+use macroquad::prelude::*;
+
+use crate::curve::Curve;
+
+const PANEL_MARGIN: f32 = 40.0;
+const PANEL_HEIGHT: f32 = 70.0;
+const TICK_RADIUS: f32 = 8.0;
+
+/// Strip at the bottom of the window showing the active B-spline's knot vector as ticks
+/// on a line. Dragging a tick moves every copy of that knot value
+#[derive(Default)]
+pub struct KnotPanel {
+    // Index of the first knot with the dragged value
+    dragging: Option<usize>
+}
+
+fn line_y() -> f32 {
+    screen_height() - PANEL_HEIGHT / 2.
+}
+
+fn to_screen(u: f32, (min, max): (f32, f32)) -> f32 {
+    let width = screen_width() - 2. * PANEL_MARGIN;
+    PANEL_MARGIN + if max > min { (u - min) / (max - min) * width } else { 0. }
+}
+
+fn from_screen(x: f32, (min, max): (f32, f32)) -> f32 {
+    let width = screen_width() - 2. * PANEL_MARGIN;
+    min + (x - PANEL_MARGIN) / width * (max - min)
+}
+
+/// Distinct knot values as (index of first copy, value, multiplicity)
+fn distinct(knots: &[f32]) -> Vec<(usize, f32, usize)> {
+    let mut out: Vec<(usize, f32, usize)> = Vec::new();
+    for (i, &k) in knots.iter().enumerate() {
+        match out.last_mut() {
+            Some((_, value, multiplicity)) if *value == k => *multiplicity += 1,
+            _ => out.push((i, k, 1))
+        }
+    }
+
+    out
+}
+
+impl KnotPanel {
+    /// Whether the mouse is over the panel, clicks there shouldn't reach the canvas
+    pub fn hovered(&self) -> bool {
+        mouse_position().1 >= screen_height() - PANEL_HEIGHT
+    }
+
+    /// Handle dragging ticks, returns true while the panel is using the mouse
+    pub fn update(&mut self, curve: &mut Curve) -> bool {
+        let Some(spline) = curve.bspline() else {
+            self.dragging = None;
+            return false;
+        };
+
+        let mut knots = spline.knots;
+        let range = (knots[0], knots[knots.len() - 1]);
+        let (mx, my) = mouse_position();
+
+        if !is_mouse_button_down(MouseButton::Left) {
+            self.dragging = None;
+        }
+
+        if is_mouse_button_pressed(MouseButton::Left) && (my - line_y()).abs() <= TICK_RADIUS {
+            // The outer knots set the panel's range, so they stay put
+            let count = knots.len();
+            self.dragging = distinct(&knots).into_iter()
+                .filter(|&(first, _, multiplicity)| first != 0 && first + multiplicity != count)
+                .find(|&(_, value, _)| (to_screen(value, range) - mx).abs() <= TICK_RADIUS)
+                .map(|(first, _, _)| first);
+        }
+
+        if let Some(first) = self.dragging {
+            let groups = distinct(&knots);
+            let Some(group) = groups.iter().position(|g| g.0 == first) else {
+                self.dragging = None;
+                return self.hovered();
+            };
+
+            // The knots must stay sorted, so a tick can't move past its neighbours
+            let (_, _, multiplicity) = groups[group];
+            let low = if group > 0 { groups[group - 1].1 } else { f32::MIN };
+            let high = groups.get(group + 1).map_or(f32::MAX, |g| g.1);
+            let value = from_screen(mx, range).clamp(low + 1e-4, high - 1e-4);
+
+            for knot in &mut knots[first..first + multiplicity] {
+                *knot = value;
+            }
+
+            curve.knots = Some(knots);
+            curve.modified = true;
+        }
+
+        self.dragging.is_some() || self.hovered()
+    }
+
+    /// `toggle_key` names the key that swaps between clamped and unclamped knots
+    pub fn draw(&self, curve: &Curve, toggle_key: &str) {
+        let Some(spline) = curve.bspline() else { return };
+        let knots = &spline.knots;
+        let range = (knots[0], knots[knots.len() - 1]);
+        let domain = spline.domain();
+        let y = line_y();
+
+        draw_rectangle(0., screen_height() - PANEL_HEIGHT, screen_width(), PANEL_HEIGHT, Color::new(0., 0., 0., 0.8));
+        draw_line(PANEL_MARGIN, y, screen_width() - PANEL_MARGIN, y, 1., GRAY);
+        draw_line(to_screen(domain.0, range), y, to_screen(domain.1, range), y, 3., WHITE);
+
+        for (first, value, multiplicity) in distinct(knots) {
+            let x = to_screen(value, range);
+            let color = if self.dragging == Some(first) { YELLOW } else { ORANGE };
+            draw_line(x, y - TICK_RADIUS, x, y + TICK_RADIUS, 2., color);
+            draw_text(&format!("{:.2}", value), x - 12., y + 24., 16., LIGHTGRAY);
+            if multiplicity > 1 {
+                draw_text(&format!("x{}", multiplicity), x - 6., y - 12., 16., color);
+            }
+        }
+
+        let label = if spline.is_clamped() { "clamped" } else { "unclamped" };
+        draw_text(&format!("knots ({}), {} toggles", label, toggle_key), PANEL_MARGIN, screen_height() - PANEL_HEIGHT + 14., 16., LIGHTGRAY);
+    }
+}
@@ -0,0 +1,91 @@
+use kurbo::{BezPath, CubicBez, Line, ParamCurve, PathEl, QuadBez};
+use macroquad::prelude::*;
+
+use crate::curve::Curve;
+use crate::point::Point;
+use crate::segment::CubicSegment;
+
+/// kurbo works in f64
+pub fn to_kurbo(v: Vec2) -> kurbo::Point {
+    kurbo::Point::new(v.x as f64, v.y as f64)
+}
+
+pub fn from_kurbo(p: kurbo::Point) -> Vec2 {
+    vec2(p.x as f32, p.y as f32)
+}
+
+impl From<CubicSegment> for CubicBez {
+    fn from(segment: CubicSegment) -> Self {
+        let [p0, p1, p2, p3] = segment.points.map(|p| to_kurbo(p.pos));
+        CubicBez::new(p0, p1, p2, p3)
+    }
+}
+
+/// kurbo curves have no colors, the points come out white
+impl From<CubicBez> for CubicSegment {
+    fn from(bez: CubicBez) -> Self {
+        let points = [bez.p0, bez.p1, bez.p2, bez.p3].map(|p| Point::new(from_kurbo(p), WHITE));
+        CubicSegment::new(points)
+    }
+}
+
+/// One subpath with a `curve_to` per segment, closed like the curve. B-splines are
+/// converted to their bezier segments
+impl From<&Curve> for BezPath {
+    fn from(curve: &Curve) -> Self {
+        let mut path = BezPath::new();
+        let segments = curve.segments();
+        let Some(first) = segments.first() else { return path };
+
+        path.move_to(to_kurbo(first[0].pos));
+        for [_, c1, c2, end] in &segments {
+            path.curve_to(to_kurbo(c1.pos), to_kurbo(c2.pos), to_kurbo(end.pos));
+        }
+        if curve.closed { path.close_path() };
+
+        path
+    }
+}
+
+/// A curve per subpath of `path` with points of `color`, lines and quadratics are raised
+/// to cubics. Subpaths without a segment are skipped
+pub fn curves_from_bez_path(path: &BezPath, color: Color) -> Vec<Curve> {
+    let mut curves = Vec::new();
+    let mut points: Vec<Vec2> = Vec::new();
+    let (mut start, mut pos) = (kurbo::Point::ZERO, kurbo::Point::ZERO);
+
+    let mut finish = |points: &mut Vec<Vec2>, closed: bool| {
+        let control: Vec<Point> = points.drain(..).map(|p| Point::new(p, color)).collect();
+        if control.len() >= CubicSegment::POINTS {
+            curves.push(Curve { control, closed, modified: true, ..Default::default() });
+        }
+    };
+
+    for element in path.iter() {
+        let cubic = match element {
+            PathEl::MoveTo(p) => {
+                finish(&mut points, false);
+                (start, pos) = (p, p);
+                continue;
+            },
+            PathEl::ClosePath => {
+                finish(&mut points, true);
+                pos = start;
+                continue;
+            },
+            PathEl::LineTo(p) => {
+                let line = Line::new(pos, p);
+                CubicBez::new(pos, line.eval(1. / 3.), line.eval(2. / 3.), p)
+            },
+            PathEl::QuadTo(p1, p2) => QuadBez::new(pos, p1, p2).raise(),
+            PathEl::CurveTo(p1, p2, p3) => CubicBez::new(pos, p1, p2, p3)
+        };
+
+        if points.is_empty() { points.push(from_kurbo(cubic.p0)) };
+        points.extend([cubic.p1, cubic.p2, cubic.p3].map(from_kurbo));
+        pos = cubic.p3;
+    }
+    finish(&mut points, false);
+
+    curves
+}
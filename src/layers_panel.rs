@@ -0,0 +1,115 @@
+use macroquad::prelude::*;
+
+use crate::document::Document;
+
+const PANEL_WIDTH: f32 = 200.0;
+const PANEL_MARGIN: f32 = 10.0;
+// Above the knot panel, which takes the bottom of the window
+const PANEL_BOTTOM_GAP: f32 = 80.0;
+const ROW_HEIGHT: f32 = 22.0;
+const TOGGLE_SIZE: f32 = 14.0;
+
+/// List of the document's layers on the right of the window, top layer first. Each row
+/// has a visibility and a lock toggle, clicking the name makes it the active layer and
+/// shift + click moves the active curve there
+#[derive(Default)]
+pub struct LayersPanel {
+    open: bool
+}
+
+// One row per layer plus the header and the add button
+fn panel_rect(doc: &Document) -> Rect {
+    let height = ROW_HEIGHT * (doc.layers.len() + 2) as f32;
+    let x = screen_width() - PANEL_WIDTH - PANEL_MARGIN;
+    Rect::new(x, screen_height() - PANEL_BOTTOM_GAP - height, PANEL_WIDTH, height)
+}
+
+// Top of the row showing `layer`, layers are listed from the top one down
+fn row_y(doc: &Document, layer: usize) -> f32 {
+    panel_rect(doc).y + ROW_HEIGHT * (doc.layers.len() - layer) as f32
+}
+
+fn toggle_rect(x: f32, y: f32) -> Rect {
+    Rect::new(x, y + (ROW_HEIGHT - TOGGLE_SIZE) / 2., TOGGLE_SIZE, TOGGLE_SIZE)
+}
+
+impl LayersPanel {
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    pub fn toggle(&mut self) {
+        self.open = !self.open;
+    }
+
+    /// Whether the mouse is over the open panel, clicks there shouldn't reach the canvas
+    pub fn hovered(&self, doc: &Document) -> bool {
+        self.open && panel_rect(doc).contains(mouse_position().into())
+    }
+
+    /// Handle clicks on the toggles and rows, returns true while the panel has the mouse
+    pub fn update(&mut self, doc: &mut Document, shift_down: bool) -> bool {
+        if !self.hovered(doc) { return false };
+        if !is_mouse_button_pressed(MouseButton::Left) { return true };
+
+        let mouse: Vec2 = mouse_position().into();
+        let rect = panel_rect(doc);
+        let add_y = rect.y + rect.h - ROW_HEIGHT;
+        if mouse.y >= add_y {
+            doc.add_layer();
+            doc.select_layer(doc.active_layer);
+            return true;
+        }
+
+        let Some(layer) = (0..doc.layers.len()).find(|&l| (row_y(doc, l)..row_y(doc, l) + ROW_HEIGHT).contains(&mouse.y)) else {
+            return true;
+        };
+        let y = row_y(doc, layer);
+        match (toggle_rect(rect.x + 8., y).contains(mouse), toggle_rect(rect.x + 28., y).contains(mouse)) {
+            (true, _) => doc.layers[layer].visible = !doc.layers[layer].visible,
+            (false, true) => doc.layers[layer].locked = !doc.layers[layer].locked,
+            (false, false) if shift_down => doc.move_active_to(layer),
+            (false, false) => doc.select_layer(layer)
+        }
+
+        // The active curve may have just been hidden or locked
+        if !doc.is_editable(doc.active) {
+            doc.cycle_active();
+        }
+
+        true
+    }
+
+    pub fn draw(&self, doc: &Document) {
+        if !self.open { return };
+        let rect = panel_rect(doc);
+        let active_curve_layer = doc.curves[doc.active].layer;
+
+        draw_rectangle(rect.x, rect.y, rect.w, rect.h, Color::new(0., 0., 0., 0.8));
+        draw_text("layers (shift + click moves the curve)", rect.x + 8., rect.y + 15., 14., LIGHTGRAY);
+
+        for (i, layer) in doc.layers.iter().enumerate() {
+            let y = row_y(doc, i);
+            if i == doc.active_layer {
+                draw_rectangle(rect.x, y, rect.w, ROW_HEIGHT, Color::new(1., 1., 1., 0.12));
+            }
+
+            for (x, on, label) in [(rect.x + 8., layer.visible, "v"), (rect.x + 28., layer.locked, "l")] {
+                let toggle = toggle_rect(x, y);
+                match on {
+                    true => draw_rectangle(toggle.x, toggle.y, toggle.w, toggle.h, GRAY),
+                    false => draw_rectangle_lines(toggle.x, toggle.y, toggle.w, toggle.h, 1., GRAY)
+                }
+                draw_text(label, toggle.x + 4., toggle.y + 11., 14., WHITE);
+            }
+
+            let color = if layer.visible { WHITE } else { GRAY };
+            let curves = doc.curves.iter().filter(|curve| curve.layer == i).count();
+            let marker = if i == active_curve_layer { "*" } else { "" };
+            let text = format!("{}{} ({})", layer.name, marker, curves);
+            draw_text(&text, rect.x + 50., y + 15., 16., color);
+        }
+
+        draw_text("+ new layer", rect.x + 8., rect.y + rect.h - 6., 16., LIGHTGRAY);
+    }
+}
@@ -0,0 +1,71 @@
+pub mod analysis;
+pub mod boolean;
+pub mod bones;
+pub mod bspline;
+pub mod bvh;
+pub mod camera_path;
+pub mod coaster;
+pub mod color_picker;
+pub mod config;
+pub mod continuity;
+pub mod coordinate_entry;
+pub mod coords;
+pub mod curve;
+pub mod document;
+pub mod elevation;
+pub mod elevation_panel;
+pub mod evolute;
+pub mod error;
+#[cfg(feature = "exact")]
+pub mod exact;
+pub mod export;
+pub mod fill;
+#[cfg(feature = "gamepad")]
+pub mod gamepad;
+pub mod grid;
+pub mod history;
+pub mod hud;
+pub mod import;
+pub mod interval;
+pub mod involute;
+pub mod knot_panel;
+#[cfg(feature = "kurbo")]
+pub mod kurbo_interop;
+pub mod layers_panel;
+#[cfg(feature = "lyon")]
+pub mod lyon_render;
+pub mod math;
+pub mod measure;
+pub mod medial_axis;
+pub mod obstacles;
+pub mod offsets;
+pub mod osculating;
+pub mod overlay;
+pub mod pattern;
+pub mod physics;
+pub mod plot_panel;
+pub mod point;
+pub mod pointer;
+pub mod preview;
+pub mod profile_panel;
+pub mod profiler;
+pub mod project;
+pub mod raster;
+pub mod reference;
+pub mod rope;
+pub mod rulers;
+pub mod sdf;
+pub mod segment;
+pub mod selection;
+pub mod settings;
+pub mod snap;
+pub mod storage;
+pub mod stroke;
+pub mod svg;
+pub mod text_path;
+pub mod theme;
+pub mod timeline;
+pub mod trace;
+pub mod transform_entry;
+pub mod vehicle;
+pub mod view;
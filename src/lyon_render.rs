@@ -0,0 +1,81 @@
+use lyon::math::point;
+use lyon::path::Path;
+use lyon::tessellation::{
+    BuffersBuilder, FillOptions, FillTessellator, FillVertex, LineJoin, StrokeOptions, StrokeTessellator,
+    StrokeVertex, VertexBuffers
+};
+use macroquad::prelude::*;
+
+use crate::curve::Curve;
+use crate::stroke::{vertex, Batch};
+
+/// Triangles lyon made out of a path, in world coordinates
+#[derive(Default)]
+pub struct Tessellation {
+    pub vertices: Vec<Vec2>,
+    pub indices: Vec<u16>
+}
+
+impl Tessellation {
+    pub fn triangles(&self) -> usize {
+        self.indices.len() / 3
+    }
+
+    pub fn draw(&self, color: Color) {
+        let mut batch = Batch::default();
+        for triangle in self.indices.chunks_exact(3) {
+            let corners = [0, 1, 2].map(|i| vertex(self.vertices[triangle[i] as usize], color));
+            batch.push(&corners, &[0, 1, 2]);
+        }
+        batch.flush();
+    }
+}
+
+/// The curve's bezier segments as a lyon path, None without a complete segment
+pub fn lyon_path(curve: &Curve) -> Option<Path> {
+    let segments = curve.segments();
+    let first = segments.first()?;
+    let to_lyon = |v: Vec2| point(v.x, v.y);
+
+    let mut builder = Path::builder();
+    builder.begin(to_lyon(first[0].pos));
+    for [_, c1, c2, end] in &segments {
+        builder.cubic_bezier_to(to_lyon(c1.pos), to_lyon(c2.pos), to_lyon(end.pos));
+    }
+    builder.end(curve.closed);
+
+    Some(builder.build())
+}
+
+/// Inside of a closed curve with the non-zero rule, flattened within `tolerance` world units
+pub fn fill(curve: &Curve, tolerance: f32) -> Option<Tessellation> {
+    let path = lyon_path(curve).filter(|_| curve.closed)?;
+    let mut buffers: VertexBuffers<Vec2, u16> = VertexBuffers::new();
+    let options = FillOptions::non_zero().with_tolerance(tolerance);
+
+    FillTessellator::new().tessellate_path(
+        &path,
+        &options,
+        &mut BuffersBuilder::new(&mut buffers, |v: FillVertex| vec2(v.position().x, v.position().y))
+    ).ok()?;
+
+    Some(Tessellation { vertices: buffers.vertices, indices: buffers.indices })
+}
+
+/// Line `width` world units wide along the curve with round joins
+pub fn stroke(curve: &Curve, width: f32, tolerance: f32) -> Option<Tessellation> {
+    let path = lyon_path(curve)?;
+    let mut buffers: VertexBuffers<Vec2, u16> = VertexBuffers::new();
+    let options = StrokeOptions::default()
+        .with_line_width(width)
+        .with_line_join(LineJoin::Round)
+        .with_tolerance(tolerance);
+
+    StrokeTessellator::new().tessellate_path(
+        &path,
+        &options,
+        &mut BuffersBuilder::new(&mut buffers, |v: StrokeVertex| vec2(v.position().x, v.position().y))
+    ).ok()?;
+
+    Some(Tessellation { vertices: buffers.vertices, indices: buffers.indices })
+}
@@ -1,424 +1,1409 @@
 use macroquad::prelude::*;
 
-const CONTROLPOINT_RADIUS: f32 = 10.0;
-#[derive(Clone, Copy)]
-struct Point {
-    pub pos: Vec2,
-    pub color: Color
+use numerical_curves::boolean::BooleanOp;
+use numerical_curves::bones::{draw_chain, pose_chain, BONE_COUNTS};
+use numerical_curves::camera_path::{draw_viewport, CameraTrack, CAMERA_PATH_FILE};
+use numerical_curves::color_picker::{ColorPicker, PickerTarget};
+use numerical_curves::config::{Config, CONFIG_PATH};
+use numerical_curves::coordinate_entry::CoordinateEntry;
+use numerical_curves::coords::CoordinateSystem;
+use numerical_curves::document::{Document, SymmetryKind};
+use numerical_curves::elevation::{draw_elevation, Elevation};
+use numerical_curves::elevation_panel::ElevationPanel;
+use numerical_curves::evolute::{draw_evolute, evolute};
+#[cfg(feature = "exact")]
+use numerical_curves::exact::verify_curve;
+use numerical_curves::export::{collider_json, control_points_code, CodeSyntax, COLLIDER_FILE};
+use numerical_curves::fill::{draw_fill, FillRule};
+#[cfg(feature = "gamepad")]
+use numerical_curves::gamepad::Gamepad;
+use numerical_curves::grid::Grid;
+use numerical_curves::history::History;
+use numerical_curves::hud::{draw_hud, draw_text_box};
+use numerical_curves::import::{open_file, Opened};
+use numerical_curves::involute::{involute_curve, length_to};
+use numerical_curves::knot_panel::KnotPanel;
+use numerical_curves::layers_panel::LayersPanel;
+#[cfg(feature = "lyon")]
+use numerical_curves::lyon_render;
+use numerical_curves::measure::Measure;
+use numerical_curves::medial_axis::{draw_medial_axis, MedialAxisCache};
+use numerical_curves::obstacles::{collisions, draw_obstacles, obstacle_at, Obstacle};
+use numerical_curves::offsets::{draw_offsets, offset_family, OFFSETS_PER_SIDE, OFFSET_SPACINGS};
+use numerical_curves::osculating::{draw_osculating, max_radius, OsculatingTour};
+use numerical_curves::overlay::{draw_distance_field, draw_winding};
+use numerical_curves::pattern::{default_spacing, stamp_along};
+use numerical_curves::physics::SoftBody;
+use numerical_curves::plot_panel::PlotPanel;
+use numerical_curves::point::{Point, CONTROLPOINT_RADIUS};
+use numerical_curves::pointer::Pointer;
+use numerical_curves::preview::{draw_next_segment, draw_partial_segment, next_segment};
+use numerical_curves::profile_panel::ProfilePanel;
+use numerical_curves::profiler::{Phase, Profiler};
+use numerical_curves::project::{self, PROJECT_FILE};
+use numerical_curves::reference::ReferenceImage;
+use numerical_curves::rope::{Rope, ROPE_PARTICLES};
+use numerical_curves::rulers::draw_rulers;
+use numerical_curves::sdf::{signed_distance_field, SDF_RESOLUTIONS};
+use numerical_curves::selection::Selection;
+use numerical_curves::settings::{Evaluator, RenderSettings, DEFAULT_FLATTEN_TOLERANCE};
+use numerical_curves::snap::{align, constrain_to_axes, draw_guide, draw_snap, nearest_target, snap_targets, SnapTarget};
+use numerical_curves::storage;
+use numerical_curves::svg::{clipped_path_data, path_data};
+use numerical_curves::text_path::{draw_text_on_path, PathTextEntry};
+use numerical_curves::timeline::Timeline;
+use numerical_curves::trace::{trace, TRACE_THRESHOLDS};
+use numerical_curves::transform_entry::TransformEntry;
+use numerical_curves::vehicle::{draw_vehicle, tight_stretches, Vehicle};
+use numerical_curves::view::View;
+
+// Fold counts shift + the radial symmetry key cycles through
+const RADIAL_FOLDS: [usize; 6] = [3, 4, 5, 6, 8, 12];
+// Formats opened as a reference image instead of curves
+const IMAGE_EXTENSIONS: [&str; 3] = ["png", "jpg", "jpeg"];
+// Units per second the osculating circle travels
+const OSCULATING_SPEED: f32 = 1.5;
+// Seconds between autosaves of a changed document
+const AUTOSAVE_INTERVAL: f64 = 2.0;
+
+fn window_conf() -> Conf {
+    Conf {
+        window_title: "Trabalho Numéricos".to_owned(),
+        window_resizable: true,
+        // macroquad then measures everything in logical pixels, so radii, line widths,
+        // text and hit tests keep their size on high density displays
+        high_dpi: true,
+        ..Default::default()
+    }
 }
 
-impl Point {
-    pub fn new(pos: Vec2, color: Color) -> Self { Self { pos, color } }
+/// Main Function - here we treat the inputs, the curve
+///  creation and call the drawing methods each frame
+#[macroquad::main(window_conf)]
+async fn main() {
+    let config = Config::load(CONFIG_PATH).unwrap_or_else(|error| {
+        warn!("{}, using the defaults", error);
+        Config::default()
+    });
+    let mut color_it = config.point_colors.clone().into_iter().cycle();
+    let mut doc = Document::default();
+    // The last session's autosave, offered until it's restored with enter or discarded with escape
+    let mut recovered = storage::autosaved()
+        .and_then(|text| project::from_json(&text).ok())
+        .filter(|doc| doc.curves.iter().any(|curve| !curve.control.is_empty()));
+    let mut view = View::default();
+    let mut coords = CoordinateSystem::default();
+    let mut knot_panel = KnotPanel::default();
+    let mut profile_panel = ProfilePanel::default();
+    let mut elevation_panel = ElevationPanel::default();
+    let mut plot_panel = PlotPanel::default();
+    let mut color_picker = ColorPicker::default();
+    let mut coordinate_entry = CoordinateEntry::default();
+    let mut path_text_entry = PathTextEntry::default();
+    let mut transform_entry = TransformEntry::default();
+    let mut profiler = Profiler::default();
+    let mut timeline = Timeline::default();
+    let mut layers_panel = LayersPanel::default();
+    #[cfg(feature = "gamepad")]
+    let mut gamepad = Gamepad::default();
 
-    pub fn draw_control(&self) {
-        draw_circle(self.pos.x, self.pos.y, CONTROLPOINT_RADIUS, self.color);
+    let mut selected: Option<usize> = None;
+    // Where the dragged point was when the drag started, shift keeps it on lines through there
+    let mut drag_origin = Vec2::ZERO;
+    // What the dragged point can snap to, found once per drag since none of it moves along
+    let mut drag_targets: Option<Vec<SnapTarget>> = None;
+    let toggles = config.toggles;
+    let mut settings = RenderSettings {
+        theme: config.theme,
+        draw_bounding: toggles.bounding_boxes,
+        draw_hull: toggles.convex_hull,
+        antialias: toggles.antialias,
+        level_of_detail: toggles.level_of_detail,
+        evaluator: match toggles.casteljau {
+            true => Evaluator::Casteljau,
+            false => Evaluator::Horner
+        },
+        flatten_tolerance: toggles.flatten.then_some(DEFAULT_FLATTEN_TOLERANCE),
+        ..RenderSettings::default()
+    };
+    let mut draw_grid = toggles.grid;
+    let mut grid = Grid::default();
+    let mut draw_rulers_mode = toggles.rulers;
+    let mut draw_info = toggles.info;
+    let mut draw_winding_mode = toggles.winding;
+    let mut draw_distance_mode = false;
+    // Scanline fill of closed curves, off while None
+    let mut fill_rule: Option<FillRule> = None;
+    let mut boolean_operand: Option<usize> = None;
+    let mut split_pieces: usize = 2;
+    // Pen mode adds a whole segment with generated handles per click
+    let mut pen_mode = false;
+    let mut carry_handles = false;
+    let mut smart_guides = toggles.smart_guides;
+    let mut code_syntax = CodeSyntax::default();
+    let mut sdf_resolution = 2;
+    let mut radial_folds = 6;
+    // Image to trace over, while adjusting it the mouse moves and scales it instead of editing
+    let mut reference: Option<ReferenceImage> = None;
+    let mut adjusting_reference = false;
+    let mut trace_threshold = 0.5;
+    // Springs on a curve's control points, nothing is recorded while they move
+    let mut soft_body: Option<SoftBody> = None;
+    // Rope sliding along a curve, it doesn't change the document
+    let mut rope: Option<Rope> = None;
+    // Circles the curves are checked against, placed while `placing_obstacles`
+    let mut obstacles: Vec<Obstacle> = Vec::new();
+    let mut placing_obstacles = false;
+    let mut dragged_obstacle: Option<usize> = None;
+    // Distance and angle measurement, clicks place its ends while it's on
+    let mut measure: Option<Measure> = None;
+    // Points of the active curve picked with a box, scaled and rotated together while it's on
+    let mut selection: Option<Selection> = None;
+    // Drives along a curve to check it can be followed with the configured turning limit
+    let mut vehicle: Option<Vehicle> = None;
+    // Preview of a curve used as a cutscene camera's track
+    let mut camera_track: Option<CameraTrack> = None;
+    // Bone chain posed along the active curve, as an index into BONE_COUNTS
+    let mut bones: Option<usize> = None;
+    let mut show_evolute = false;
+    let mut osculating: Option<OsculatingTour> = None;
+    let mut offset_spacing: Option<usize> = None;
+    let mut medial: Option<MedialAxisCache> = None;
+    // Draw the curves with lyon's tessellation instead of the samples
+    #[cfg(feature = "lyon")]
+    let mut lyon_mode = false;
+    // Zoom the curves were last sampled at, to resample when it changes enough
+    let mut sampled_scale = view.scale;
+    let mut last_mouse: Vec2 = mouse_position().into();
+    // Alt + drag on the curve body moves all of it, or only the grabbed segment with shift
+    let mut body_drag: Option<Option<usize>> = None;
+    let mut last_world = view.screen_to_world(last_mouse);
+    let mut last_screen = vec2(screen_width(), screen_height());
+    let mut last_autosave = (get_time(), project::to_json(&doc));
+    let mut history = History::new(&doc);
+    // Set by anything that could be an edit, the state is recorded once the mouse is up
+    let mut history_pending = false;
+    // There's no drag and drop outside the browser, files can be opened from the command line
+    if let Some(path) = std::env::args().nth(1) {
+        if let Err(error) = storage::request_open(&path) {
+            warn!("{}", error);
+        }
     }
+    loop {
+        clear_background(settings.theme.background);
 
-    pub fn draw(&self) {
-        draw_rectangle(self.pos.x, self.pos.y, 1.0, 1.0, self.color);
-    }
+        // Everything else reads the window size each frame, the view only has to keep its corner
+        let screen = vec2(screen_width(), screen_height());
+        if screen != last_screen {
+            view.resize(last_screen, screen);
+            last_screen = screen;
+        }
+        profiler.begin(Phase::HitTesting);
+
+        // A controller stands in for the mouse while it's being used
+        #[cfg(feature = "gamepad")]
+        let pointer = gamepad.pointer().unwrap_or_else(|| Pointer::from_mouse(last_mouse));
+        #[cfg(not(feature = "gamepad"))]
+        let pointer = Pointer::from_mouse(last_mouse);
+
+        let Vec2 { x: mx, y: my } = view.screen_to_world(pointer.position);
+
+        let prompting = recovered.is_some();
+        if let Some(previous) = recovered.take() {
+            match (is_key_pressed(KeyCode::Enter), is_key_pressed(KeyCode::Escape)) {
+                (true, _) => doc = previous,
+                (false, true) => storage::discard_autosave(),
+                (false, false) => recovered = Some(previous)
+            }
+        }
+        // While typing coordinates or answering the restore prompt the keyboard goes there
+        // instead of the shortcuts
+        let typing = coordinate_entry.is_open() || path_text_entry.is_open() || transform_entry.is_open() || prompting;
+        let shortcut = |action: &str| !typing && (is_key_pressed(config.keys.key(action)) || pointer.triggered(action));
+
+        // Stepping through the history or scrubbing the timeline replaces the whole document
+        if shortcut("timeline") {
+            timeline.toggle();
+        }
+        let restored = match (shortcut("history_back"), shortcut("history_forward")) {
+            (true, _) => history.back(),
+            (false, true) => history.forward(),
+            (false, false) if selected.is_none() => timeline.update(&mut history),
+            (false, false) => None
+        };
+        if let Some(state) = restored {
+            doc = state;
+            soft_body = None;
+            rope = None;
+            vehicle = None;
+            camera_track = None;
+            selected = None;
+            boolean_operand = None;
+            // Its points may not exist in the restored curve
+            selection = selection.map(|_| Selection::default());
+            color_picker.close();
+            coordinate_entry.close();
+        }
+        let timeline_busy = selected.is_none() && timeline.hovered(&history);
 
-    pub fn lerp(&self, other: &Self, t: f32) -> Point {
-        let pos = self.pos.lerp(other.pos, t);
-        let color = Color::from_vec(self.color.to_vec().lerp(other.color.to_vec(), t));
+        let shift_down = is_key_down(KeyCode::LeftShift) || is_key_down(KeyCode::RightShift);
+        if shortcut("layers") {
+            layers_panel.toggle();
+        }
+        let layers_busy = selected.is_none() && layers_panel.update(&mut doc, shift_down);
+        // Every curve left is hidden or locked, the active one then takes no mouse input
+        let locked_out = !doc.is_editable(doc.active);
+        // Shortcuts that change the active curve, or add curves, do nothing while it's locked
+        let edit = |action: &str| !locked_out && shortcut(action);
+
+        if shortcut("profile") {
+            match shift_down {
+                true => plot_panel.cycle(),
+                false => profile_panel.toggle()
+            }
+        }
+        // A flat profile to shape, or none
+        if shortcut("elevation") && !locked_out {
+            let curve = doc.active_mut();
+            curve.elevation = match curve.elevation {
+                Some(_) => None,
+                None => Some(Elevation::default())
+            };
+        }
 
-        Self { pos , color }
-    }
-}
+        let curve = doc.active_mut();
+        coordinate_entry.update(curve, &coords);
+        if let Some(text) = path_text_entry.update() {
+            curve.text = text;
+        }
+        let picker_busy = selected.is_none() && color_picker.update(curve);
+        let profile_busy = selected.is_none() && profile_panel.update(curve, &coords);
+        let elevation_busy = selected.is_none() && elevation_panel.update(curve);
+        let panel_busy = picker_busy || timeline_busy || layers_busy || profile_busy || elevation_busy || plot_panel.hovered() || locked_out || (selected.is_none() && knot_panel.update(curve));
+
+        if shortcut("reference") {
+            match (shift_down, &reference) {
+                (true, _) => {
+                    reference = None;
+                    adjusting_reference = false;
+                },
+                (false, Some(_)) => adjusting_reference = !adjusting_reference,
+                (false, None) => info!("Open or drop a PNG or JPEG first")
+            }
+        }
+        // Drag moves the image, the wheel scales it and shift + wheel changes its opacity
+        let reference_busy = match reference.as_mut().filter(|_| adjusting_reference) {
+            Some(image) => {
+                if pointer.primary_down && !panel_busy {
+                    image.position += vec2(mx, my) - last_world;
+                }
+                match (pointer.zoom != 1., shift_down) {
+                    (true, true) => image.change_opacity(if pointer.zoom > 1. { 0.05 } else { -0.05 }),
+                    (true, false) => image.scale_about(vec2(mx, my), pointer.zoom),
+                    (false, _) => ()
+                }
+                true
+            },
+            None => false
+        };
 
-// Calculate B(t) using De Casteljau's algorithm
-fn decasteljau(points: &[Point], t: f32) -> Point {
-    let a = points[0];
-    let b = points[1];
-    let c = points[2];
-    let d = points[3];
+        if shortcut("obstacles") {
+            match shift_down {
+                true => obstacles.clear(),
+                false => placing_obstacles = !placing_obstacles
+            }
+            dragged_obstacle = None;
+        }
+        // Clicks place an obstacle or drag the one under the cursor, the wheel resizes it
+        // and right click removes it
+        let obstacles_busy = placing_obstacles && !panel_busy;
+        let hovered_obstacle = obstacle_at(&obstacles, vec2(mx, my)).filter(|_| obstacles_busy);
+        if obstacles_busy {
+            if pointer.primary_pressed {
+                dragged_obstacle = hovered_obstacle.or_else(|| {
+                    obstacles.push(Obstacle { center: vec2(mx, my), radius: 30. / view.scale });
+                    Some(obstacles.len() - 1)
+                });
+            }
+            if let Some(i) = dragged_obstacle.filter(|_| pointer.primary_down) {
+                obstacles[i].center += vec2(mx, my) - last_world;
+            }
+            if let Some(i) = hovered_obstacle {
+                match (pointer.secondary_pressed, pointer.zoom != 1.) {
+                    (true, _) => {
+                        obstacles.remove(i);
+                        dragged_obstacle = None;
+                    },
+                    (false, true) => obstacles[i].radius *= pointer.zoom,
+                    (false, false) => ()
+                }
+            }
+        }
+        if !pointer.primary_down {
+            dragged_obstacle = None;
+        }
+        if shortcut("measure") {
+            measure = match measure {
+                Some(_) => None,
+                None => Some(Measure::default())
+            };
+        }
+        let measure_busy = measure.is_some() && !panel_busy;
+        if shortcut("select") {
+            selection = match selection {
+                Some(_) => None,
+                None => Some(Selection::default())
+            };
+        }
+        let select_busy = selection.is_some() && !panel_busy;
+        let wheel_taken = reference_busy || hovered_obstacle.is_some();
+        let panel_busy = panel_busy || reference_busy || obstacles_busy || measure_busy || select_busy;
+
+        // Start or continue moving the curve body, which takes over the mouse
+        let alt_down = is_key_down(KeyCode::LeftAlt) || is_key_down(KeyCode::RightAlt);
+        if body_drag.is_none() && selected.is_none() && !panel_busy && alt_down && pointer.primary_pressed {
+            body_drag = curve.pick_segment(vec2(mx, my), CONTROLPOINT_RADIUS)
+                .map(|(segment, _)| match shift_down && curve.knots.is_none() {
+                    true => Some(segment),
+                    false => None
+                });
+        }
+        let dragging_body = body_drag.is_some();
+        // The whole body carries the rest of the curve's group along
+        match body_drag {
+            Some(Some(segment)) => curve.translate_segment(segment, vec2(mx, my) - last_world),
+            Some(None) => doc.translate_group(doc.active, vec2(mx, my) - last_world),
+            None => ()
+        }
+        // Clicks place the ends of a measurement instead of editing while it's on
+        if let Some(measure) = measure.as_mut().filter(|_| measure_busy && pointer.primary_pressed) {
+            measure.click(&doc, vec2(mx, my), CONTROLPOINT_RADIUS);
+        }
+        // Dragging picks points or transforms the picked ones instead of editing while selecting
+        if let Some(selection) = selection.as_mut().filter(|_| select_busy) {
+            selection.update(&mut doc, vec2(mx, my), pointer.primary_pressed, pointer.primary_down, shift_down, 1. / view.scale);
+        }
+        // Holding ctrl while dragging a point snaps it to what's near the cursor
+        let ctrl_down = is_key_down(KeyCode::LeftControl) || is_key_down(KeyCode::RightControl);
+        let snap = selected.filter(|_| ctrl_down).and_then(|id| {
+            let targets = drag_targets.get_or_insert_with(|| snap_targets(&doc, doc.active, id));
+            nearest_target(targets, vec2(mx, my), config.snap_radius / view.scale)
+        });
+        // Otherwise shift keeps it to horizontal, vertical and diagonal moves, or else smart
+        // guides line it up with the other points
+        let (dragged_to, guides) = match (selected, snap) {
+            (Some(_), Some(target)) => (target.pos, Vec::new()),
+            (Some(_), None) if shift_down => (constrain_to_axes(drag_origin, vec2(mx, my)), Vec::new()),
+            (Some(id), None) if smart_guides => align(&doc, doc.active, id, vec2(mx, my), config.snap_radius / view.scale),
+            _ => (vec2(mx, my), Vec::new())
+        };
+        let curve = doc.active_mut();
+        if !pointer.primary_down {
+            body_drag = None;
+        }
+        last_world = vec2(mx, my);
 
-    let ab = a.lerp(&b, t);
-    let bc = b.lerp(&c, t);
-    let cd = c.lerp(&d, t);
+        // Collision - if we're dragging a point, move it. Otherwise, try colliding with every point
+        if let Some(id) = selected {
+            curve.move_point(id, dragged_to, carry_handles);
+        } else if !panel_busy && !dragging_body {
+            for (i, p) in curve.control.iter().enumerate() {
+                let dist = ((mx - p.pos.x).powi(2) + (my - p.pos.y).powi(2)).sqrt();
+                if dist <= CONTROLPOINT_RADIUS {
+                    selected = Some(i);
+                    drag_origin = p.pos;
+                    drag_targets = None;
+                }
+            }
+        }
 
-    let abc = ab.lerp(&bc, t);
-    let bcd = bc.lerp(&cd, t);
+        // Delete point on right click
+        if let Some(id) = selected {
+            if pointer.secondary_pressed {
+                curve.remove_point(id);
+            }
+            if edit("lock_point") {
+                curve.control[id].locked = !curve.control[id].locked;
+            }
+        }
 
-    abc.lerp(&bcd, t)
-}
+        // Text along the active curve, the arrows move its baseline off the curve
+        if edit("path_text") {
+            path_text_entry.open(&curve.text);
+        }
+        if !curve.text.is_empty() && (edit("raise_text") || edit("lower_text")) {
+            curve.text_offset += if edit("raise_text") { 2. } else { -2. };
+        }
 
-// Calculate B(t) using Bernstein's Polynomial form
-fn cubic_bezier(t: f32, points: &[Point]) -> Vec2 {
-    (points[0].pos * (-t.powi(3) + 3.*t.powi(2) - 3. * t + 1.))  +
-    (points[1].pos * (3. * t.powi(3) - 6. * t.powi(2) + 3. * t)) +
-    (points[2].pos * (-3. * t.powi(3) + 3. * t.powi(2)))         +
-    (points[3].pos * t.powi(3))
-}
+        // Shift + enter on a handle types its length and angle instead
+        if let Some(id) = selected.filter(|_| edit("edit_coordinates")) {
+            match curve.handle_polar(id, &coords).filter(|_| shift_down) {
+                Some((length, angle)) => coordinate_entry.open_polar(id, length, angle),
+                None => coordinate_entry.open(id, coords.to_math(curve.control[id].pos))
+            }
+        }
 
-// B'(t) - First derivative of Bernstein Polynomial - used to get tangent and normal
-fn velocity(points: [Point; 4], t: f32) -> Vec2 {
-    (points[0].pos * (-3.*t.powi(2) + 6. * t - 3.))   +
-    (points[1].pos * (9. * t.powi(2) - 12. * t + 3.)) +
-    (points[2].pos * (-9. * t.powi(2) + 6. * t))      +
-    (points[3].pos * 3. * t.powi(2))
-}
+        // Recolor the hovered point, or the whole curve when no point is hovered
+        if edit("color_picker") {
+            match (color_picker.is_open(), selected) {
+                (true, _) => color_picker.close(),
+                (false, Some(id)) => color_picker.open(PickerTarget::Point(id), curve.control[id].color),
+                (false, None) => {
+                    let color = curve.control.first().map_or(WHITE, |p| p.color);
+                    color_picker.open(PickerTarget::Curve, color);
+                }
+            }
+        }
+        if shortcut("close_popup") {
+            color_picker.close();
+        }
 
-// B''(t) - Second derivative of Bernstein Polynomial - used on curvature formula
-fn acceleration(points: [Point; 4], t: f32) -> Vec2 {
-    points[0].pos * (-6. * t + 6.)  +
-    points[1].pos * (18. * t - 12.) +
-    points[2].pos * (-18. * t + 6.) +
-    points[3].pos * (6. * t)
-}
+        // Split the hovered segment, equal parameter ranges or equal lengths with shift
+        let hovered_segment = match selected.is_none() && curve.knots.is_none() {
+            true => curve.pick_segment(vec2(mx, my), CONTROLPOINT_RADIUS).map(|(segment, _)| segment),
+            false => None
+        };
+        if shortcut("fewer_pieces") { split_pieces = (split_pieces - 1).max(2) };
+        if shortcut("more_pieces") { split_pieces += 1 };
+        if let Some(segment) = hovered_segment.filter(|_| edit("split_segment")) {
+            curve.subdivide_segment(segment, split_pieces, shift_down);
+        }
 
-// Used by tight bounding box to solve for each axis' derivative
-fn solve_quadratic(xs: [f32; 4]) -> Option<(f32, f32)> {
-    let [x0, x1, x2, x3] = xs;
+        // Cycle the hovered joint's enforced continuity
+        if let Some(id) = selected.filter(|_| edit("cycle_continuity")) {
+            curve.cycle_continuity(id);
+        }
 
-    let a = (-3. * x0) + (9. * x1) - (9. * x2) + (3. * x3);
-    let b = (6. * x0) - (12. * x1) + (6. * x2);
-    let c =( -3. * x0) + 3. * x1;
-    let delta = b*b - (4.*a*c);
+        // Even the hovered joint's handle lengths, or line them up with shift
+        if let Some(id) = selected.filter(|_| edit("even_handles")) {
+            curve.even_handles(id, shift_down);
+        }
 
-    if delta >= 0. {
-        let x0 = (-b + delta.sqrt()) / (2. * a);
-        let x1 = (-b - delta.sqrt()) / (2. * a);
+        // Ctrl + left click on a B-spline inserts a knot there instead of adding a point
+        // With the springs on clicking pushes the points instead of adding one
+        let mut clicked = selected.is_none() && !panel_busy && !dragging_body && soft_body.is_none() && pointer.primary_pressed;
+        if clicked && ctrl_down && curve.insert_knot_near(vec2(mx, my), CONTROLPOINT_RADIUS) {
+            clicked = false;
+        }
 
-        return Some((x0, x1));
-    }
+        // Add point on left click, or the previewed segment with the pen
+        if let Some(segment) = next_segment(curve, vec2(mx, my)).filter(|_| clicked && pen_mode) {
+            for point in &segment[1..] {
+                curve.control.push(Point::new(point.pos, color_it.next().unwrap()));
+            }
+            curve.modified = true;
+            clicked = false;
+        }
+        if clicked {
+            let new_point = Point::new(vec2(mx, my), color_it.next().unwrap());
+            curve.control.push(new_point);
+            curve.modified = true;
+        }
 
-    None
-}
+        // Un-selected the previously draggable point
+        if !pointer.primary_down {
+            selected = None;
+        }
 
-const BOUNDING_BOX_COLOR: Color = BLUE;
-struct BoundingBox {
-    point_min: Vec2,
-    point_max: Vec2,
-    point_color: Color,
-    outline_color: Color
-}
+        if shortcut("bounding_boxes") {
+            settings.draw_bounding = !settings.draw_bounding;
+        }
 
-impl BoundingBox {
-    fn draw(&self) {
-        let (pmin, pmax) = (self.point_min, self.point_max);
+        if shortcut("convex_hull") {
+            settings.draw_hull = !settings.draw_hull;
+        }
 
-        draw_circle(pmin.x, pmin.y, 5.0, self.point_color);
-        draw_circle(pmax.x, pmax.y, 5.0, self.point_color);
+        if shortcut("carry_handles") {
+            carry_handles = !carry_handles;
+        }
 
+        if shortcut("smart_guides") {
+            smart_guides = !smart_guides;
+        }
 
-        draw_line(pmin.x, pmin.y, pmin.x, pmax.y, 1., self.outline_color);
-        draw_line(pmax.x, pmin.y, pmax.x, pmax.y, 1., self.outline_color);
+        if shortcut("pen_mode") {
+            pen_mode = !pen_mode;
+        }
 
-        draw_line(pmin.x, pmin.y, pmax.x, pmin.y, 1., self.outline_color);
-        draw_line(pmin.x, pmax.y, pmax.x, pmax.y, 1., self.outline_color);
-    }
-}
+        if shortcut("profiler") {
+            profiler.enabled = !profiler.enabled;
+        }
 
-#[derive(Default)]
-struct Curve {
-    control: Vec<Point>,
-    rendered: Vec<Point>,
-    boxes: Vec<BoundingBox>,
-    modified: bool
-}
+        if shortcut("rulers") {
+            draw_rulers_mode = !draw_rulers_mode;
+        }
 
-impl Curve {
-    fn bounding_box(points: &[Point]) -> [Vec2; 2] {
-        let (mut min_x, mut min_y) = (f32::MAX, f32::MAX);
-        let (mut max_x, mut max_y) = (f32::MIN, f32::MIN);
+        if shortcut("grid") {
+            draw_grid = !draw_grid;
+        }
 
-        for p in points {
-            min_x = min_x.min(p.pos.x);
-            max_x = max_x.max(p.pos.x);
+        // Grid spacing in units, backslash goes back to following the zoom
+        if shortcut("finer_grid") {
+            grid.scale_spacing(0.5, &view, &coords);
+        }
 
-            min_y = min_y.min(p.pos.y);
-            max_y = max_y.max(p.pos.y);
+        if shortcut("coarser_grid") {
+            grid.scale_spacing(2., &view, &coords);
         }
 
-        [vec2(min_x, min_y), vec2(max_x, max_y)]
-    }
+        if shortcut("auto_grid") {
+            grid.spacing = None;
+        }
 
-    /// Take the derivative on each eaxis then build by comparing with start_anchor and end_anchor points
-    fn tight_bounding_box(points: &[Point]) -> [Vec2; 2] {
-        let [mut pmin, mut pmax] = Curve::bounding_box(&[points[0], points[3]]);
+        if shortcut("info") {
+            draw_info = !draw_info;
+        }
+
+        if shortcut("winding") {
+            draw_winding_mode = !draw_winding_mode;
+        }
 
-        let xs = [points[0].pos.x, points[1].pos.x, points[2].pos.x, points[3].pos.x];
-        if let Some((tx0, tx1)) = solve_quadratic(xs) {
-            if (0.0..1.0).contains(&tx0) {
-                let candidate = cubic_bezier(tx0, points);
-                draw_circle(candidate.x, candidate.y, 5.0, RED);
-                draw_text("x0", candidate.x, candidate.y, 20.0, YELLOW);
+        if shortcut("fill") {
+            fill_rule = match fill_rule {
+                None => Some(FillRule::NonZero),
+                Some(FillRule::NonZero) => Some(FillRule::EvenOdd),
+                Some(FillRule::EvenOdd) => None
+            };
+        }
 
-                // info!("x0 candidate: {}", candidate);
-                let candidate_x = candidate.x;
-                pmin.x = pmin.x.min(candidate_x);
-                pmax.x = pmax.x.max(candidate_x);
+        if shortcut("distance_field") {
+            draw_distance_mode = !draw_distance_mode;
+        }
+
+        if edit("close_curve") {
+            curve.closed = !curve.closed;
+            curve.modified = true;
+        }
+
+        if edit("toggle_bspline") {
+            curve.toggle_bspline();
+        }
+
+        if edit("auto_smooth") {
+            curve.auto_smooth();
+        }
+
+        if edit("toggle_clamped") {
+            curve.toggle_clamped();
+        }
+
+        // Booleans - mark the first operand, make the second one active then pick the operation
+        if shortcut("boolean_operand") {
+            boolean_operand = match boolean_operand {
+                Some(_) => None,
+                None => Some(doc.active)
+            };
+        }
+
+        if let Some(a) = boolean_operand.filter(|&a| a != doc.active) {
+            let op = if edit("union") {
+                Some(BooleanOp::Union)
+            } else if edit("intersection") {
+                Some(BooleanOp::Intersection)
+            } else if edit("difference") {
+                Some(BooleanOp::Difference)
+            } else {
+                None
+            };
+
+            if let Some(op) = op {
+                let added = doc.boolean(a, doc.active, op);
+                info!("Boolean {:?} produced {} curves", op, added);
+                boolean_operand = None;
             }
 
-            if (0.0..1.0).contains(&tx1) {
-                let candidate = cubic_bezier(tx1, points);
-                draw_circle(candidate.x, candidate.y, 5.0, RED);
-                draw_text("x1", candidate.x, candidate.y, 20.0, YELLOW);
+            // The marked curve is the template, stamped as one group along the active curve
+            if edit("stamp_pattern") {
+                let group = doc.next_group_id();
+                let mut stamps = stamp_along(doc.active(), &doc.curves[a], default_spacing(&doc.curves[a]));
+                for stamp in &mut stamps {
+                    stamp.group = Some(group);
+                }
+                info!("Stamped curve #{} {} times along #{}", a, stamps.len(), doc.active);
+                doc.append(stamps);
+                boolean_operand = None;
+            }
 
-                // info!("x1 candidate: {}", candidate);
-                let candidate_x = candidate.x;
-                pmin.x = pmin.x.min(candidate_x);
-                pmax.x = pmax.x.max(candidate_x);
+            if edit("group") {
+                doc.group(a, doc.active);
+                info!("Grouped curve #{} with #{}, {} curves in the group", a, doc.active, doc.group_members(a).len());
+                boolean_operand = None;
             }
+        } else if edit("group") {
+            doc.ungroup(doc.active);
         }
 
-        let ys = [points[0].pos.y, points[1].pos.y, points[2].pos.y, points[3].pos.y];
-        if let Some((ty0, ty1)) = solve_quadratic(ys) {
-            if (0.0..1.0).contains(&ty0) {
-                let candidate = cubic_bezier(ty0, points);
-                draw_circle(candidate.x, candidate.y, 5.0, RED);
-                draw_text("y0", candidate.x, candidate.y, 20.0, YELLOW);
+        // Mirror the active curve across a vertical axis through the cursor, or repeat it
+        // around the cursor. Either key unlinks the copies again, shift picks the fold count
+        if shortcut("symmetry") {
+            match doc.symmetry {
+                Some(_) => doc.stop_symmetry(),
+                None if !locked_out => doc.start_symmetry(SymmetryKind::Mirror { axis: mx }),
+                None => ()
+            }
+        }
+        if shortcut("radial_symmetry") {
+            match (shift_down, &doc.symmetry) {
+                (true, _) => {
+                    radial_folds = RADIAL_FOLDS[(RADIAL_FOLDS.iter().position(|&n| n == radial_folds).unwrap_or(0) + 1) % RADIAL_FOLDS.len()];
+                    info!("Radial symmetry repeats curves {} times", radial_folds);
+                },
+                (false, Some(_)) => doc.stop_symmetry(),
+                (false, None) if !locked_out => doc.start_symmetry(SymmetryKind::Radial { center: vec2(mx, my), folds: radial_folds }),
+                (false, None) => ()
+            }
+        }
 
-                // info!("y0 candidate: {}", candidate);
-                let candidate_y = candidate.y;
-                pmin.y = pmin.y.min(candidate_y);
-                pmax.y = pmax.y.max(candidate_y);
+        // Outlines of the image's dark parts become one group of closed curves
+        if edit("trace") {
+            match (shift_down, &reference) {
+                (true, _) => {
+                    let next = TRACE_THRESHOLDS.iter().position(|&t| t == trace_threshold).map_or(0, |i| (i + 1) % TRACE_THRESHOLDS.len());
+                    trace_threshold = TRACE_THRESHOLDS[next];
+                    info!("Tracing pixels darker than {:.0}%", trace_threshold * 100.);
+                },
+                (false, Some(image)) => {
+                    let group = doc.next_group_id();
+                    let mut curves = trace(image, trace_threshold, &mut color_it);
+                    for curve in &mut curves {
+                        curve.group = Some(group);
+                    }
+                    info!("Traced {} curves", curves.len());
+                    doc.append(curves);
+                    color_picker.close();
+                    coordinate_entry.close();
+                    selected = None;
+                },
+                (false, None) => info!("Open or drop a PNG or JPEG first")
             }
+        }
 
-            if (0.0..1.0).contains(&ty1) {
-                let candidate = cubic_bezier(ty1, points);
-                draw_circle(candidate.x, candidate.y, 5.0, RED);
-                draw_text("y1", candidate.x, candidate.y, 20.0, YELLOW);
+        // Turning the springs off puts the points back where they started
+        if shortcut("physics") {
+            match soft_body.take() {
+                Some(body) => if let Some(curve) = doc.curves.get_mut(body.curve) { body.restore(curve) },
+                None if locked_out => info!("The active curve is on a locked layer"),
+                None if doc.active().control.len() >= 2 => soft_body = Some(SoftBody::new(doc.active, doc.active())),
+                None => info!("The curve needs at least two points to put on springs")
+            }
+        }
+        if let Some(body) = &mut soft_body {
+            let dt = get_frame_time();
+            match doc.curves.get_mut(body.curve).filter(|curve| body.fits(curve)) {
+                Some(curve) => {
+                    // Sweeping the cursor through the curve with the button down pushes the points
+                    if pointer.primary_down && selected.is_none() && !panel_busy && dt > 0. {
+                        let velocity = (pointer.position - last_mouse) / view.scale / dt;
+                        body.impulse(curve, vec2(mx, my), 4. * CONTROLPOINT_RADIUS, velocity);
+                    }
+                    let held = selected.filter(|_| body.curve == doc.active);
+                    body.step(curve, dt, held);
+                },
+                // Points were added or removed, there's nothing to spring back to
+                None => soft_body = None
+            }
+        }
 
-                // info!("y1 candidate: {}", candidate);
-                let candidate_y = candidate.y;
-                pmin.y = pmin.y.min(candidate_y);
-                pmax.y = pmax.y.max(candidate_y);
+        // Drop a rope on the active curve at the cursor, it slides down under gravity
+        if shortcut("rope") {
+            rope = match rope {
+                Some(_) => None,
+                None => {
+                    let dropped = Rope::new(doc.active, doc.active(), vec2(mx, my), ROPE_PARTICLES, 400. / view.scale);
+                    if dropped.is_none() { info!("The curve needs a complete segment to hang a rope on") };
+                    dropped
+                }
+            };
+        }
+        if let Some(on) = &mut rope {
+            match doc.curves.get(on.curve) {
+                Some(curve) => on.step(curve, get_frame_time()),
+                None => rope = None
             }
         }
 
-        [pmin, pmax]
-    }
+        if shortcut("vehicle") {
+            vehicle = match vehicle {
+                Some(_) => None,
+                None => Some(Vehicle::new(doc.active))
+            };
+        }
+        if vehicle.as_ref().is_some_and(|car| car.curve >= doc.curves.len()) {
+            vehicle = None;
+        }
+        let distance = config.vehicle.speed * coords.pixels_per_unit * get_frame_time();
+        let vehicle_pose = vehicle.as_mut().and_then(|car| car.advance(&doc.curves[car.curve], distance));
+
+        if shortcut("bones") {
+            bones = match (shift_down, bones) {
+                (true, Some(i)) => Some((i + 1) % BONE_COUNTS.len()),
+                (true, None) => Some(0),
+                (false, Some(_)) => None,
+                (false, None) => Some(2)
+            };
+        }
 
-    fn render(&mut self, use_casteljau: bool) {
-        info!("Rendering new curve!");
-        let bezier = match use_casteljau {
-            true => decasteljau,
-            false => |points: &[Point], t| {
-                let (start, end) = (points[0], points[3]);
-                let color = Color::from_vec(start.color.to_vec().lerp(end.color.to_vec(), t));
-                Point::new(cubic_bezier(t, points), color)
-            },
-        };
+        if shortcut("evolute") {
+            show_evolute = !show_evolute;
+        }
+        // An osculating circle traveling along whichever curve is active
+        if shortcut("osculating_circle") {
+            osculating = match osculating {
+                Some(_) => None,
+                None => Some(OsculatingTour::default())
+            };
+        }
+        let distance = OSCULATING_SPEED * coords.pixels_per_unit * get_frame_time();
+        let osculating_pose = osculating.as_mut().and_then(|tour| tour.advance(doc.active(), distance));
+
+        if shortcut("offsets") {
+            offset_spacing = match (shift_down, offset_spacing) {
+                (true, Some(i)) => Some((i + 1) % OFFSET_SPACINGS.len()),
+                (true, None) => Some(0),
+                (false, Some(_)) => None,
+                (false, None) => Some(1)
+            };
+        }
 
-        let control = &mut self.control;
+        if shortcut("medial_axis") {
+            medial = match medial {
+                Some(_) => None,
+                None => Some(MedialAxisCache::default())
+            };
+        }
+        if let Some(cache) = medial.as_mut() {
+            cache.update(doc.active());
+        }
 
-        for control_window in control.windows(4).step_by(3) {
-            let a = &control_window[0];
-            let b = &control_window[1];
+        // Unwind a string from the hovered point of the active curve, or from its start
+        if edit("involute") {
+            let curve = doc.active();
+            let start = curve.pick_segment(vec2(mx, my), CONTROLPOINT_RADIUS)
+                .map_or(0., |(segment, t)| length_to(curve, segment, t));
+            match involute_curve(curve, start) {
+                Some(involute) => {
+                    info!("Unwound the involute of curve #{} from {:.2} units along it", doc.active, coords.length(start));
+                    doc.append(vec![involute]);
+                    color_picker.close();
+                    coordinate_entry.close();
+                    selected = None;
+                },
+                None => info!("The curve needs a complete segment past the starting point")
+            }
+        }
 
-            let c = &control_window[2];
-            let d = &control_window[3];
+        // Preview the active curve as a camera track at the current zoom, or export its keyframes
+        if shortcut("camera_path") {
+            match shift_down {
+                true if doc.active().segments().is_empty() => info!("The curve needs a complete segment to be a camera track"),
+                true => {
+                    let track = CameraTrack::new(doc.active, view.scale);
+                    match storage::save_file(CAMERA_PATH_FILE, track.keyframes_json(doc.active(), &coords).as_bytes()) {
+                        Ok(()) => info!("Saved {}", CAMERA_PATH_FILE),
+                        Err(error) => warn!("{}", error)
+                    }
+                },
+                false => camera_track = match camera_track {
+                    Some(_) => None,
+                    None => Some(CameraTrack::new(doc.active, view.scale))
+                }
+            }
+        }
+        if camera_track.as_ref().is_some_and(|track| track.curve >= doc.curves.len()) {
+            camera_track = None;
+        }
+        if let Some(track) = &mut camera_track {
+            track.advance(&doc.curves[track.curve], &coords, get_frame_time());
+        }
 
-            for t in (0..=2000).map(|t| t as f32*0.0005) {
+        // The copies land a little down and to the right so they don't hide the originals
+        if edit("duplicate") {
+            let offset = Vec2::splat(20. / view.scale);
+            doc.duplicate_group(doc.active, offset);
+            color_picker.close();
+            coordinate_entry.close();
+            selected = None;
+        }
 
-                // self.rendered.push(bp);
-                let new_point = bezier(control_window, t);
-                self.rendered.push(new_point);
+        if shortcut("new_curve") {
+            match doc.new_curve() {
+                true => {
+                    color_picker.close();
+                    coordinate_entry.close();
+                },
+                false => info!("The active layer is locked or hidden, pick another one for the new curve")
+            }
+        }
 
+        if shortcut("next_curve") {
+            doc.cycle_active();
+            color_picker.close();
+            coordinate_entry.close();
+            selected = None;
+        }
 
-                // Uncomment to draw normals and curvature
-                // if t % 0.5 < 0.000001 {
-                //     let vel = Curve::velocity(control_window, t);
-                //     let acc = Curve::acceleration(control_window, t);
+        // Reorder the active curve within its layer, shift sends it to the back. The other
+        // curves shift around it, so a marked boolean operand would point at the wrong one
+        if shortcut("bring_to_front") {
+            // The simulated curve's index is about to change
+            if let Some(body) = soft_body.take() {
+                body.restore(&mut doc.curves[body.curve]);
+            }
+            rope = None;
+            vehicle = None;
+            camera_track = None;
+            match shift_down {
+                true => doc.send_to_back(),
+                false => doc.bring_to_front()
+            }
+            boolean_operand = None;
+        }
 
-                //     // Tangent, normal
-                //     let t = vel.normalize();
-                //     let n = t.perp();
+        // Flip or turn the active curve's group in place
+        if shortcut("flip") && !locked_out {
+            let axes = if shift_down { vec2(1., -1.) } else { vec2(-1., 1.) };
+            doc.transform_group(doc.active, Mat3::from_scale(axes));
+        }
+        if shortcut("rotate_90") && !locked_out {
+            let angle = if shift_down { -std::f32::consts::FRAC_PI_2 } else { std::f32::consts::FRAC_PI_2 };
+            doc.transform_group(doc.active, Mat3::from_angle(angle));
+        }
+        // Or by typed amounts, rotating and scaling around a closed curve's centroid
+        if shortcut("numeric_transform") && !locked_out {
+            transform_entry.open();
+        }
+        if let Some(transform) = transform_entry.update() {
+            let matrix = transform.matrix(&coords);
+            match doc.active().shape().map(|shape| shape.centroid) {
+                Some(center) => doc.transform_group_about(doc.active, center, matrix),
+                None => doc.transform_group(doc.active, matrix)
+            }
+        }
 
-                //     let pn = bp.pos - n*50.;
-                //     let pp = bp.pos + n*50.;
-                //     draw_line(pn.x, pn.y, pp.x, pp.y, 2.0, VIOLET);
+        // Wheel zooms around the cursor, or scales the active curve's group with alt. While
+        // adjusting the reference image or over an obstacle being placed it belongs to them.
+        // Middle mouse drag pans
+        match (pointer.zoom != 1. && !wheel_taken, alt_down && !locked_out) {
+            (true, true) => doc.scale_group(doc.active, pointer.zoom),
+            (true, false) => view.zoom_at(pointer.position, pointer.zoom),
+            (false, _) => ()
+        }
+        if pointer.pan != Vec2::ZERO {
+            view.pan(pointer.pan);
+        }
+        last_mouse = mouse_position().into();
 
-                //     // Curvature
-                //     // let k = Mat2::from_cols(vel, acc).determinant() / vel.length().powi(3);
+        if shortcut("level_of_detail") {
+            settings.level_of_detail = !settings.level_of_detail;
+            doc.mark_modified();
+        }
 
-                //     // let r = 1.0/k;
-                //     // let center = bp.pos + (n*(r+10.));
-                //     // draw_circle_lines(center.x, center.y, r, 2.0, PURPLE);
-                // }
+        // Zoom to fit every curve
+        if shortcut("zoom_to_fit") {
+            if let Some(bounds) = doc.bounds() {
+                view.fit(bounds, 40.);
             }
+        }
 
-            let [point_min, point_max] = Curve::bounding_box(control_window);
-            self.boxes.push(BoundingBox {
-                point_min,
-                point_max,
-                point_color: BOUNDING_BOX_COLOR,
-                outline_color: BOUNDING_BOX_COLOR
-            });
+        if shortcut("interval_boxes") {
+            settings.interval_boxes = !settings.interval_boxes;
+            doc.mark_modified();
+        }
 
-            let [point_min, point_max] = Curve::tight_bounding_box(control_window);
-            self.boxes.push(BoundingBox {
-                point_min,
-                point_max,
-                point_color: RED,
-                outline_color: GOLD
-            });
+        if shortcut("toggle_algorithm") {
+            settings.evaluator = settings.evaluator.next();
+            doc.mark_modified();
+            let deviation = settings.evaluator.deviation(&doc.active().segments(), settings.samples_per_segment);
+            info!("Evaluating curves with {:?}, up to {:e} units off De Casteljau's", settings.evaluator, deviation);
+        }
 
-            draw_text("a", a.pos.x, a.pos.y, 42.0, YELLOW);
-            draw_text("b", b.pos.x, b.pos.y, 42.0, YELLOW);
-            draw_text("c", c.pos.x, c.pos.y, 42.0, YELLOW);
-            draw_text("d", d.pos.x, d.pos.y, 42.0, YELLOW);
+        if shortcut("flatten") {
+            settings.flatten_tolerance = match settings.flatten_tolerance {
+                Some(_) => None,
+                None => Some(DEFAULT_FLATTEN_TOLERANCE)
+            };
+            doc.mark_modified();
         }
 
-        self.modified = false;
-    }
+        #[cfg(feature = "lyon")]
+        if shortcut("lyon") {
+            lyon_mode = !lyon_mode;
+        }
 
-    fn draw(&mut self, draw_bounding: bool, use_casteljau: bool) {
-        if self.control.len() < 4 { return };
-        if self.modified {
-            self.rendered.clear();
-            self.boxes.clear();
-            self.render(use_casteljau);
+        #[cfg(feature = "exact")]
+        if shortcut("verify_exact") {
+            let others: Vec<_> = doc.curves.iter().enumerate()
+                .filter(|&(i, _)| i != doc.active)
+                .map(|(_, curve)| curve)
+                .collect();
+            let report = verify_curve(doc.active(), &others);
+            info!(
+                "Exact check: {} of {} roots off, {} of {} crossings off, widest crossing gap {:e} exactly ({:e} in floats)",
+                report.bad_roots, report.roots, report.bad_crossings, report.crossings, report.widest_gap.1, report.widest_gap.0
+            );
         }
 
-        for point in &self.rendered {
-            point.draw();
+        // Only changes how the samples are drawn, nothing to re-render
+        if shortcut("antialias") {
+            settings.antialias = !settings.antialias;
         }
 
-        if draw_bounding {
-            for bbox in &self.boxes {
-                bbox.draw();
-            }
+        if shortcut("thinner_lines") {
+            settings.change_line_width(-1.);
         }
-    }
 
-    fn draw_controls(&mut self) {
-        for control in &self.control {
-            control.draw_control();
+        if shortcut("thicker_lines") {
+            settings.change_line_width(1.);
         }
 
-        for controls in self.control.windows(4).step_by(3) {
-            let (anchor, control) = (controls[0], controls[1]);
-            let color = Color::from_vec(anchor.color.to_vec().lerp(control.color.to_vec(), 0.5));
-            draw_line(anchor.pos.x, anchor.pos.y, control.pos.x, control.pos.y, 1.0, color);
+        // Units: put the origin under the cursor, or halve/double how many pixels a unit is
+        if shortcut("set_origin") {
+            coords.origin = vec2(mx, my);
+        }
 
-            let (anchor, control) = (controls[3], controls[2]);
-            let color = Color::from_vec(anchor.color.to_vec().lerp(control.color.to_vec(), 0.5));
-            draw_line(anchor.pos.x, anchor.pos.y, control.pos.x, control.pos.y, 1.0, color);
+        if shortcut("halve_unit") {
+            coords.scale_units(0.5);
         }
-    }
-}
 
-/// Main Function - here we treat the inputs, the curve
-///  creation and call the drawing methods each frame
-#[macroquad::main("Trabalho Numéricos")]
-async fn main() {
-    let mut color_it = [ORANGE, BLUE, RED, PURPLE].into_iter().cycle();
-    let mut curve = Curve::default();
-    curve.modified = true;
+        if shortcut("double_unit") {
+            coords.scale_units(2.);
+        }
 
-    let mut selected: Option<usize> = None;
-    let mut draw_bounding = false;
-    let mut draw_grid = false;
-    let mut use_casteljau = false;
-    loop {
-        clear_background(BLACK);
+        // Halve or double the sampling density
+        if shortcut("fewer_samples") {
+            settings.scale_samples(0.5);
+            doc.mark_modified();
+        }
 
-        let (mx, my) = mouse_position();
+        if shortcut("more_samples") {
+            settings.scale_samples(2.);
+            doc.mark_modified();
+        }
 
-        // Collision - if we're dragging a point, move it. Otherwise, try colliding with every point
-        if let Some(id) = selected {
-            curve.control[id].pos = vec2(mx, my);
-            curve.modified = true;
-        } else {
-            for (i, p) in curve.control.iter().enumerate() {
-                let dist = ((mx - p.pos.x).powi(2) + (my - p.pos.y).powi(2)).sqrt();
-                if dist <= CONTROLPOINT_RADIUS {
-                    selected = Some(i);
+        if shortcut("save_project") {
+            match storage::save_file(PROJECT_FILE, project::to_json(&doc).as_bytes()) {
+                Ok(()) => info!("Saved {}", PROJECT_FILE),
+                Err(error) => warn!("{}", error)
+            }
+        }
+
+        if shortcut("open_project") {
+            if let Err(error) = storage::request_open(PROJECT_FILE) {
+                warn!("{}", error);
+            }
+        }
+
+        // Paste-able into a path's d attribute or a vector editor
+        // Shift crops it to the view, cutting the segments at the screen's edges
+        if shortcut("copy_svg") {
+            let d = match shift_down {
+                true => clipped_path_data(doc.active(), view.visible_rect()),
+                false => path_data(doc.active())
+            };
+            match d.is_empty() {
+                true => info!("Nothing to copy, the active curve has no complete segment{}", if shift_down { " in view" } else { "" }),
+                false => {
+                    unsafe { get_internal_gl() }.quad_context.clipboard_set(&d);
+                    info!("Copied the SVG path of curve #{}", doc.active);
                 }
             }
         }
 
-        // Delete point on right click
-        if let Some(id) = selected {
-            if is_mouse_button_pressed(MouseButton::Right) {
-                curve.control.remove(id);
-                curve.modified = true;
+        // Control points as an array for code, shift picks the language
+        if shortcut("copy_code") {
+            match shift_down {
+                true => {
+                    code_syntax = code_syntax.next();
+                    info!("Exporting control points as {}", code_syntax.label());
+                },
+                false if doc.active().control.is_empty() => info!("Nothing to export, the active curve has no points"),
+                false => {
+                    let code = control_points_code(doc.active(), &coords, code_syntax);
+                    unsafe { get_internal_gl() }.quad_context.clipboard_set(&code);
+                    info!("{}", code);
+                }
             }
         }
 
-        // Add point on left click
-        if selected.is_none() {
-            if is_mouse_button_pressed(MouseButton::Left) {
-                let new_point = Point::new(vec2(mx, my), color_it.next().unwrap());
-                curve.control.push(new_point);
-                curve.modified = true;
+        // Signed distance field of the active closed curve, shift picks the resolution
+        if shortcut("export_sdf") {
+            match shift_down {
+                true => {
+                    sdf_resolution = (sdf_resolution + 1) % SDF_RESOLUTIONS.len();
+                    info!("Exporting distance fields at {0}x{0}", SDF_RESOLUTIONS[sdf_resolution]);
+                },
+                false => {
+                    let size = SDF_RESOLUTIONS[sdf_resolution];
+                    let spread = size / 16;
+                    match signed_distance_field(doc.active(), size, spread) {
+                        Some(field) => {
+                            let name = format!("sdf_{}.png", size);
+                            match storage::save_file(&name, &field.to_png(spread)) {
+                                Ok(()) => info!("Saved {}", name),
+                                Err(error) => warn!("{}", error)
+                            }
+                        },
+                        None => info!("Only closed curves have a distance field to export")
+                    }
+                }
             }
         }
 
-        // Un-selected the previously draggable point
-        if !is_mouse_button_down(MouseButton::Left) {
+        // Every visible curve as a chain of points for game engines' colliders
+        if shortcut("export_collider") {
+            let json = collider_json(doc.draw_order().into_iter().map(|i| &doc.curves[i]), &coords, config.collider);
+            match storage::save_file(COLLIDER_FILE, json.as_bytes()) {
+                Ok(()) => info!("Saved {}", COLLIDER_FILE),
+                Err(error) => warn!("{}", error)
+            }
+        }
+
+        // Opening in the browser finishes some frames after asking, or after a drop.
+        // Projects replace the document, SVG paths and CSV points are added to it
+        let opened = storage::take_opened();
+        let extension = opened.as_ref()
+            .and_then(|file| file.name.rsplit_once('.'))
+            .map(|(_, extension)| extension.to_lowercase());
+        if let Some(file) = opened.as_ref().filter(|_| extension.is_some_and(|e| IMAGE_EXTENSIONS.contains(&e.as_str()))) {
+            // Half the window tall, where the user is looking
+            let [min, max] = view.visible_rect();
+            match ReferenceImage::decode(&file.data, (min + max) / 2., (max.y - min.y) / 2.) {
+                Ok(image) => {
+                    info!("Opened {} as the reference image", file.name);
+                    reference = Some(image);
+                    adjusting_reference = true;
+                },
+                Err(error) => warn!("{}: {}", file.name, error)
+            }
+        } else if let Some(file) = opened {
+            match open_file(&file.name, &file.text(), &coords, &mut color_it) {
+                Ok(Opened::Project(opened)) => doc = opened,
+                Ok(Opened::Curves(curves)) => {
+                    info!("Opened {} curves from {}", curves.len(), file.name);
+                    doc.append(curves);
+                },
+                Err(error) => warn!("{}: {}", file.name, error)
+            }
             selected = None;
+            boolean_operand = None;
+            soft_body = None;
+            rope = None;
+            vehicle = None;
+            camera_track = None;
+            color_picker.close();
+            coordinate_entry.close();
+            history_pending = true;
         }
 
-        if is_key_pressed(KeyCode::B) {
-            draw_bounding = !draw_bounding;
+        doc.sync_symmetry();
+
+        // One state per finished edit, so a whole drag is a single step
+        history_pending |= pointer.primary_pressed || pointer.secondary_pressed || get_last_key_pressed().is_some();
+        if history_pending && !pointer.primary_down && soft_body.is_none() {
+            history.record(&doc);
+            history_pending = false;
         }
 
-        if is_key_pressed(KeyCode::G) {
-            draw_grid = !draw_grid;
+        // Not while the last session's autosave could still be restored, it'd be overwritten,
+        // or while the springs move the points
+        if recovered.is_none() && soft_body.is_none() && get_time() - last_autosave.0 > AUTOSAVE_INTERVAL {
+            let text = project::to_json(&doc);
+            if text != last_autosave.1 {
+                storage::autosave(&text);
+            }
+            last_autosave = (get_time(), text);
         }
 
-        if is_key_pressed(KeyCode::M) {
-            use_casteljau = !use_casteljau;
-            info!("Mode toggled! Casteljau: {}", use_casteljau);
+        // Sampling depends on the zoom with level of detail or flattening, or the dots size
+        settings.pixels_per_unit = view.scale;
+        let visible = view.visible_rect();
+        settings.visible = Some(visible);
+        // Safe as long as nothing else holds the context, which is only borrowed here
+        let dpi_scale = unsafe { get_internal_gl() }.quad_context.dpi_scale();
+        if dpi_scale != settings.dpi_scale {
+            settings.dpi_scale = dpi_scale;
+            doc.mark_modified();
+        }
+        let zoom_change = view.scale / sampled_scale;
+        if !(0.8..1.25).contains(&zoom_change) {
+            sampled_scale = view.scale;
+            doc.mark_modified();
         }
 
         // Everything is rendered here - the order matters!
-        if draw_grid { draw_grid2d() };
-        curve.draw_controls();
-        curve.draw(draw_bounding, use_casteljau);
+        profiler.end();
+        if draw_grid { grid.draw(&view, &coords, &settings.theme) };
+        if draw_distance_mode { draw_distance_field(&doc, &view, &coords) };
+        if draw_winding_mode { draw_winding(&doc, &view) };
+        set_camera(&view.camera());
+        if let Some(image) = &reference { image.draw() };
+        profiler.begin(Phase::Sampling);
+        // Curves on hidden layers are neither sampled nor drawn
+        let draw_order = doc.draw_order();
+        for &i in &draw_order {
+            doc.curves[i].update(&settings);
+        }
+        profiler.begin(Phase::Drawing);
+        profiler.samples = doc.curves.iter().map(|curve| curve.rendered.len()).sum();
+        // A fill row per physical pixel, without the parts of tall curves that are off screen
+        let row_height = 1. / (settings.pixels_per_unit * settings.dpi_scale);
+        #[cfg(feature = "lyon")]
+        let mut lyon_triangles = 0;
+        for &i in &draw_order {
+            let locked = doc.layer_of(i).locked;
+            let curve = &mut doc.curves[i];
+            if let Some(rule) = fill_rule {
+                let color = curve.control.first().map_or(WHITE, |p| p.color);
+                draw_fill(curve, rule, settings.fill_tolerance(), row_height, visible, Color { a: 0.35, ..color });
+            }
+            // Locked curves can't be edited, so their handles would only get in the way
+            if !locked { curve.draw_controls(&settings) };
+            #[cfg(feature = "lyon")]
+            if lyon_mode {
+                // Same tolerance as the editor's flattening, so both can be compared
+                let tolerance = settings.fill_tolerance();
+                let color = curve.control.first().map_or(WHITE, |p| p.color);
+                if let Some(fill) = lyon_render::fill(curve, tolerance) {
+                    fill.draw(Color { a: 0.3, ..color });
+                    lyon_triangles += fill.triangles();
+                }
+                if let Some(stroke) = lyon_render::stroke(curve, settings.world_line_width(), tolerance) {
+                    stroke.draw(color);
+                    lyon_triangles += stroke.triangles();
+                }
+                continue;
+            }
+            curve.draw(&settings);
+            draw_elevation(curve, settings.fill_tolerance(), 2. * settings.world_line_width());
+        }
+        if let Some(rope) = &rope { rope.draw(settings.world_line_width(), ORANGE) };
+        if let Some(count) = bones.map(|i| BONE_COUNTS[i]) {
+            draw_chain(&pose_chain(doc.active(), count), 1.5 / view.scale, YELLOW);
+        }
+        if show_evolute {
+            draw_evolute(&evolute(doc.active()), settings.world_line_width(), VIOLET);
+        }
+        if let Some(axis) = medial.as_ref().and_then(|cache| cache.axis()) {
+            draw_medial_axis(axis, settings.world_line_width(), GOLD);
+        }
+        if let Some(spacing) = offset_spacing.map(|i| OFFSET_SPACINGS[i] * coords.pixels_per_unit) {
+            let family = offset_family(doc.active(), spacing, OFFSETS_PER_SIDE);
+            draw_offsets(&family, settings.world_line_width(), SKYBLUE, PINK);
+        }
+        if let (Some(pose), Some(max_radius)) = (&osculating_pose, max_radius(doc.active())) {
+            draw_osculating(pose, max_radius, 1.5 / view.scale, PURPLE);
+        }
+        if let Some(track) = &camera_track {
+            if let Some(center) = track.position(&doc.curves[track.curve], &coords, track.time) {
+                draw_viewport(center, screen, track.zoom, 1.5 / view.scale, SKYBLUE);
+            }
+        }
+        // Where the path turns tighter than the vehicle can, and the vehicle itself
+        let wheelbase = config.vehicle.wheelbase * coords.pixels_per_unit;
+        let max_curvature = 1. / (config.vehicle.min_turn_radius * coords.pixels_per_unit);
+        if let (Some(car), Some(pose)) = (&vehicle, &vehicle_pose) {
+            for stretch in tight_stretches(&doc.curves[car.curve], max_curvature) {
+                for pair in stretch.windows(2) {
+                    draw_line(pair[0].x, pair[0].y, pair[1].x, pair[1].y, 3. * settings.world_line_width(), Color::new(1., 0.5, 0., 0.8));
+                }
+            }
+            let color = match pose.curvature.abs() > max_curvature {
+                true => RED,
+                false => GREEN
+            };
+            draw_vehicle(pose, wheelbase, pose.steering(wheelbase), 1.5 / view.scale, color);
+        }
+        // Lines between samples that pass through an obstacle are drawn over in red
+        let mut collision_count = 0;
+        if !obstacles.is_empty() {
+            draw_obstacles(&obstacles, 1. / view.scale);
+            for &i in &draw_order {
+                let samples: Vec<Vec2> = doc.curves[i].flatten(settings.fill_tolerance()).iter().map(|p| p.pos).collect();
+                for stretch in collisions(&samples, &obstacles) {
+                    collision_count += 1;
+                    for pair in samples[stretch].windows(2) {
+                        draw_line(pair[0].x, pair[0].y, pair[1].x, pair[1].y, 3. * settings.world_line_width(), RED);
+                    }
+                }
+            }
+        }
+        if selected.is_none() && !knot_panel.hovered() && !profile_panel.hovered() && !elevation_panel.hovered(doc.active()) && !plot_panel.hovered() && !color_picker.hovered() {
+            match pen_mode && next_segment(doc.active(), vec2(mx, my)).is_some() {
+                true => draw_next_segment(doc.active(), vec2(mx, my), &settings),
+                false => draw_partial_segment(doc.active(), vec2(mx, my), &settings)
+            }
+        }
+        let symmetry_color = Color::new(1., 0.6, 0.9, 0.6);
+        match doc.symmetry.as_ref().map(|symmetry| symmetry.kind) {
+            Some(SymmetryKind::Mirror { axis }) => {
+                draw_line(axis, visible[0].y, axis, visible[1].y, 1. / view.scale, symmetry_color);
+            },
+            Some(SymmetryKind::Radial { center, folds }) => {
+                // A spoke between each pair of copies, long enough to leave the screen
+                let length = (visible[1] - visible[0]).length();
+                for k in 0..folds {
+                    let angle = std::f32::consts::TAU * (k as f32 + 0.5) / folds as f32;
+                    let end = center + Vec2::from_angle(angle) * length;
+                    draw_line(center.x, center.y, end.x, end.y, 1. / view.scale, symmetry_color);
+                }
+            },
+            None => ()
+        }
+        if let Some(measure) = &measure {
+            measure.draw(vec2(mx, my), 1. / view.scale);
+        }
+        if let Some(selection) = selection.as_ref().filter(|s| s.curve == doc.active) {
+            selection.draw(doc.active(), 1. / view.scale);
+        }
+        for &guide in &guides {
+            draw_guide(guide, 1. / view.scale);
+        }
+        if let Some(target) = snap {
+            draw_snap(target, 1. / view.scale);
+        }
+        // Box around the active curve's group, so it's clear what moves along with it
+        if let Some([min, max]) = doc.group_bounds(doc.active).filter(|_| doc.group_members(doc.active).len() > 1) {
+            let pad = 6. / view.scale;
+            let size = max - min + 2. * pad;
+            draw_rectangle_lines(min.x - pad, min.y - pad, size.x, size.y, 1. / view.scale, SKYBLUE);
+        }
+        if let Some(shape) = doc.active().shape().filter(|_| draw_info) {
+            let (c, r) = (shape.centroid, 8. / view.scale);
+            draw_line(c.x - r, c.y, c.x + r, c.y, 2. / view.scale, WHITE);
+            draw_line(c.x, c.y - r, c.x, c.y + r, 2. / view.scale, WHITE);
+        }
+        set_default_camera();
+        for &i in &draw_order {
+            let curve = &doc.curves[i];
+            if curve.text.is_empty() { continue };
+            let color = curve.control.first().map_or(WHITE, |p| p.color);
+            draw_text_on_path(curve, &curve.text, curve.text_offset, &view, color);
+        }
+        if let Some((lines, end)) = measure.as_ref().and_then(|m| Some((m.readout(&coords)?, m.second?.pos))) {
+            draw_text_box(&lines, view.world_to_screen(end) + vec2(12., 12.));
+        }
+        if draw_rulers_mode { draw_rulers(&view, &coords, vec2(mx, my)) };
+        if draw_info { draw_hud(&coords.metrics(&doc.active().metrics())) };
+        let status = match settings.flatten_tolerance {
+            Some(tolerance) => format!("flattened within {} px", tolerance),
+            None if settings.level_of_detail => "samples/segment: level of detail".to_string(),
+            None => format!("samples/segment: {} ({}/{})", settings.samples_per_segment, config.keys.label("more_samples"), config.keys.label("fewer_samples"))
+        };
+        let status = format!("{} | 1 unit = {} px", status, coords.pixels_per_unit);
+        let status = match doc.symmetry.as_ref().map(|symmetry| symmetry.kind) {
+            Some(SymmetryKind::Mirror { axis }) => format!("mirror: x = {:.2} | {}", coords.to_math(vec2(axis, 0.)).x, status),
+            Some(SymmetryKind::Radial { center, folds }) => {
+                let center = coords.to_math(center);
+                format!("radial: {} around ({:.2}, {:.2}) | {}", folds, center.x, center.y, status)
+            },
+            None => status
+        };
+        let status = match &camera_track {
+            Some(track) => format!("camera: {:.1} of {:.1} s | {}", track.time, track.duration(&doc.curves[track.curve], &coords), status),
+            None => status
+        };
+        let status = match &vehicle_pose {
+            Some(pose) => {
+                let heading = coords.to_math(pose.pos + pose.heading) - coords.to_math(pose.pos);
+                let side = if pose.curvature > 0. { "right" } else { "left" };
+                let warning = if pose.curvature.abs() > max_curvature { " - too tight!" } else { "" };
+                format!("vehicle: heading {:.0}°, steering {:.1}° {} of {:.1}°{} | {}", heading.y.atan2(heading.x).to_degrees(),
+                    pose.steering(wheelbase).abs().to_degrees(), side, config.vehicle.max_steering().to_degrees(), warning, status)
+            },
+            None => status
+        };
+        let status = match (&osculating_pose, max_radius(doc.active())) {
+            (Some(pose), Some(max_radius)) if pose.curvature.abs() * max_radius > 1. => {
+                format!("osculating radius {:.2} | {}", coords.length(1. / pose.curvature.abs()), status)
+            },
+            (Some(_), Some(_)) => format!("osculating radius: straight | {}", status),
+            _ => status
+        };
+        let status = match medial.as_ref().map(|cache| cache.axis()) {
+            Some(Some(axis)) => format!("medial axis: {} points | {}", axis.points.len(), status),
+            Some(None) => format!("medial axis: needs a closed curve | {}", status),
+            None => status
+        };
+        let status = match offset_spacing {
+            Some(i) => format!("offsets every {} units | {}", OFFSET_SPACINGS[i], status),
+            None => status
+        };
+        let status = match placing_obstacles || !obstacles.is_empty() {
+            true => format!("obstacles: {}, {} collisions | {}", obstacles.len(), collision_count, status),
+            false => status
+        };
+        let status = match &selection {
+            Some(selection) => format!("selection: {} points | {}", selection.points.len(), status),
+            None => status
+        };
+        let status = match soft_body.as_ref() {
+            Some(body) => format!("springs on curve #{} | {}", body.curve, status),
+            None => status
+        };
+        let status = match reference.as_ref().filter(|_| adjusting_reference) {
+            Some(image) => format!("reference: {:.0}%, drag / wheel / shift + wheel | {}", image.opacity * 100., status),
+            None => status
+        };
+        let status = match fill_rule {
+            Some(rule) => format!("fill: {} | {}", rule.label(), status),
+            None => status
+        };
+        #[cfg(feature = "lyon")]
+        let status = match lyon_mode {
+            true => format!("lyon: {} triangles | {}", lyon_triangles, status),
+            false => status
+        };
+        draw_text(&status, screen_width() - measure_text(&status, None, 18, 1.).width - 10., 20., 18., GRAY);
+        #[cfg(feature = "gamepad")]
+        if let Some(cursor) = gamepad.cursor() {
+            draw_circle_lines(cursor.x, cursor.y, 8., 2., WHITE);
+            draw_circle(cursor.x, cursor.y, 2., WHITE);
+        }
+        profiler.finish_frame();
+        profiler.draw();
+        knot_panel.draw(doc.active(), &config.keys.label("toggle_clamped"));
+        profile_panel.draw(doc.active(), &coords, selected);
+        elevation_panel.draw(doc.active(), &coords);
+        plot_panel.draw(doc.active(), &coords);
+        timeline.draw(&history);
+        layers_panel.draw(&doc);
+        color_picker.draw();
+        coordinate_entry.draw();
+        path_text_entry.draw();
+        transform_entry.draw(if doc.active().closed { "centroid" } else { "center of its bounds" });
+        if let Some(id) = selected.filter(|&id| id < doc.active().control.len()) {
+            draw_text_box(&doc.active().describe_point(id, &coords), pointer.position + vec2(16., 16.));
+        }
+        // Say how far the active curve is from its next complete segment
+        let needed = doc.active().points_needed();
+        if needed > 0 {
+            let hint = match doc.active().validate() {
+                Err(error) => format!("{} to draw the curve", error),
+                Ok(()) if needed == 1 => "1 more point completes the next segment".to_string(),
+                Ok(()) => format!("{} more points complete the next segment", needed)
+            };
+            draw_text_box(&[hint], vec2(10., screen_height() - 120.));
+        }
+        if let Some(segment) = hovered_segment {
+            let keys = &config.keys;
+            let hint = format!("segment #{} - {} splits into {} pieces (shift for equal lengths, {}/{} to change)", segment, keys.label("split_segment"), split_pieces, keys.label("fewer_pieces"), keys.label("more_pieces"));
+            draw_text_box(&[hint], vec2(10., screen_height() - 80.));
+        }
+        if let Some(previous) = &recovered {
+            let hint = format!("restore the unsaved session with {} curves? enter restores, esc discards it", previous.curves.len());
+            draw_text_box(&[hint], vec2(10., screen_height() - 160.));
+        }
+        if let Some(a) = boolean_operand {
+            let key = |action: &str| config.keys.label(action);
+            let hint = match a == doc.active {
+                true => format!("boolean: curve #{} marked, {} to the other operand", a, key("next_curve")),
+                false => format!(
+                    "boolean: #{} with #{} - {} union, {} intersection, {} difference, {} groups them, {} stamps #{} along",
+                    a, doc.active, key("union"), key("intersection"), key("difference"), key("group"), key("stamp_pattern"), a
+                )
+            };
+            draw_text_box(&[hint], vec2(10., screen_height() - 40.));
+        }
         next_frame().await;
     }
 }
-
-/// Draw a grid centered at (0, 0, 0)
-pub fn draw_grid2d() {
-    let wmid = screen_width()/2.0;
-    let hmid = screen_height()/2.0;
-
-    let x_step = (screen_width()/16.0) as usize;
-    let y_step = (screen_height()/9.0) as usize;
-
-    for i in (0..=wmid as i32).rev().step_by(x_step)  {
-        draw_line(
-        i as f32, 0.,
-            i as f32, screen_height(),
-            1.0,
-            GREEN,
-        );
-    }
-
-    for i in (wmid as i32..screen_width() as i32).step_by(x_step)  {
-        draw_line(
-        i as f32, 0.,
-            i as f32, screen_height(),
-            1.0,
-            GREEN,
-        );
-    }
-
-    for i in (0..=hmid as i32).rev().step_by(y_step)  {
-        draw_line(
-            0., i as f32,
-            screen_width(), i as f32,
-                1.0,
-                GREEN,
-            );
-    }
-
-    for i in (hmid as i32..screen_width() as i32).step_by(y_step)  {
-        draw_line(
-            0., i as f32,
-            screen_width(), i as f32,
-                1.0,
-                GREEN,
-        );
-    }
-
-    draw_circle(wmid, hmid, 5.0, YELLOW);
-    draw_line(0.0, hmid, screen_width(), hmid, 1.0, YELLOW);
-    draw_line(wmid, 0.0, wmid, screen_height(), 1.0, YELLOW);
-}
\ No newline at end of file
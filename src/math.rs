@@ -0,0 +1,546 @@
+use macroquad::prelude::*;
+use numerical_curves_core::{bezier, length, roots, Vector2};
+
+use crate::point::Point;
+use crate::segment::CubicSegment;
+
+// Positions of the first four points, the segment as the core crate takes it
+fn positions(points: &[Point]) -> [Vector2<f32>; 4] {
+    [points[0].pos.into(), points[1].pos.into(), points[2].pos.into(), points[3].pos.into()]
+}
+
+// Calculate B(t) using De Casteljau's algorithm, panics on fewer than 4 points - validate
+// them with CubicSegment::try_new first
+pub fn decasteljau(points: &[Point], t: f32) -> Point {
+    bezier::decasteljau([points[0], points[1], points[2], points[3]], t)
+}
+
+// Calculate B(t) using Bernstein's Polynomial form, nested Horner style in s = 1 - t and t
+// so each term reuses the products of the one before instead of taking powers
+pub fn cubic_bezier(t: f32, points: &[Point]) -> Vec2 {
+    bezier::eval(positions(points), t).into()
+}
+
+// B'(t) - First derivative of Bernstein Polynomial - used to get tangent and normal
+pub fn velocity(points: [Point; 4], t: f32) -> Vec2 {
+    bezier::velocity(positions(&points), t).into()
+}
+
+// B''(t) - Second derivative of Bernstein Polynomial - used on curvature formula
+pub fn acceleration(points: [Point; 4], t: f32) -> Vec2 {
+    bezier::acceleration(positions(&points), t).into()
+}
+
+// B(t) with each power of t taken on its own, kept to compare against the nested form
+pub fn cubic_bezier_powers(t: f32, points: &[Point]) -> Vec2 {
+    (points[0].pos * (-t.powi(3) + 3.*t.powi(2) - 3. * t + 1.))  +
+    (points[1].pos * (3. * t.powi(3) - 6. * t.powi(2) + 3. * t)) +
+    (points[2].pos * (-3. * t.powi(3) + 3. * t.powi(2)))         +
+    (points[3].pos * t.powi(3))
+}
+
+// B'(t) from the powers of t, as `cubic_bezier_powers`
+pub fn velocity_powers(points: [Point; 4], t: f32) -> Vec2 {
+    (points[0].pos * (-3.*t.powi(2) + 6. * t - 3.))   +
+    (points[1].pos * (9. * t.powi(2) - 12. * t + 3.)) +
+    (points[2].pos * (-9. * t.powi(2) + 6. * t))      +
+    (points[3].pos * 3. * t.powi(2))
+}
+
+// B''(t) from the expanded polynomial, as `cubic_bezier_powers`
+pub fn acceleration_powers(points: [Point; 4], t: f32) -> Vec2 {
+    points[0].pos * (-6. * t + 6.)  +
+    points[1].pos * (18. * t - 12.) +
+    points[2].pos * (-18. * t + 6.) +
+    points[3].pos * (6. * t)
+}
+
+// Used by tight bounding box to solve for each axis' derivative
+pub fn solve_quadratic(xs: [f32; 4]) -> Option<(f32, f32)> {
+    let [x0, x1, x2, x3] = xs;
+
+    let a = (-3. * x0) + (9. * x1) - (9. * x2) + (3. * x3);
+    let b = (6. * x0) - (12. * x1) + (6. * x2);
+    let c =( -3. * x0) + 3. * x1;
+    let delta = b*b - (4.*a*c);
+
+    // Symmetric control points leave the derivative linear, with its single root
+    if a.abs() < 1e-6 * (b.abs() + c.abs()) {
+        if b == 0. { return None };
+        let t = -c / b;
+        return Some((t, t));
+    }
+
+    if delta >= 0. {
+        let x0 = (-b + delta.sqrt()) / (2. * a);
+        let x1 = (-b - delta.sqrt()) / (2. * a);
+
+        return Some((x0, x1));
+    }
+
+    None
+}
+
+/// Convex hull of a point set using Andrew's monotone chain. A bezier segment always
+/// lies inside the hull of its control points, a tighter guarantee than the AABB
+pub fn convex_hull(points: &[Vec2]) -> Vec<Vec2> {
+    let mut sorted = points.to_vec();
+    sorted.sort_by(|a, b| a.x.total_cmp(&b.x).then(a.y.total_cmp(&b.y)));
+    sorted.dedup();
+
+    if sorted.len() < 3 { return sorted };
+
+    // z component of (a - o) x (b - o), positive when o->a->b turns counter-clockwise
+    let cross = |o: Vec2, a: Vec2, b: Vec2| (a - o).perp_dot(b - o);
+
+    let mut hull: Vec<Vec2> = Vec::with_capacity(sorted.len() * 2);
+    for &p in sorted.iter() {
+        while hull.len() >= 2 && cross(hull[hull.len() - 2], hull[hull.len() - 1], p) <= 0. {
+            hull.pop();
+        }
+        hull.push(p);
+    }
+
+    let lower_len = hull.len() + 1;
+    for &p in sorted.iter().rev().skip(1) {
+        while hull.len() >= lower_len && cross(hull[hull.len() - 2], hull[hull.len() - 1], p) <= 0. {
+            hull.pop();
+        }
+        hull.push(p);
+    }
+
+    // Last point is the same as the first one
+    hull.pop();
+    hull
+}
+
+/// Minimum-area enclosing rectangle as its 4 corners. The optimal rectangle always has a
+/// side collinear with a hull edge, so every edge of the hull is tried as a caliper
+pub fn min_area_rect(points: &[Vec2]) -> Option<[Vec2; 4]> {
+    let hull = convex_hull(points);
+    if hull.len() < 3 { return None };
+
+    let mut best: Option<(f32, [Vec2; 4])> = None;
+    for (i, &a) in hull.iter().enumerate() {
+        let b = hull[(i + 1) % hull.len()];
+        let axis = (b - a).normalize_or_zero();
+        if axis == Vec2::ZERO { continue };
+        let normal = axis.perp();
+
+        let (mut min_u, mut max_u) = (f32::MAX, f32::MIN);
+        let (mut min_v, mut max_v) = (f32::MAX, f32::MIN);
+        for p in &hull {
+            let (u, v) = (p.dot(axis), p.dot(normal));
+            min_u = min_u.min(u);
+            max_u = max_u.max(u);
+            min_v = min_v.min(v);
+            max_v = max_v.max(v);
+        }
+
+        let area = (max_u - min_u) * (max_v - min_v);
+        if best.is_none_or(|(best_area, _)| area < best_area) {
+            let corner = |u: f32, v: f32| axis * u + normal * v;
+            let rect = [
+                corner(min_u, min_v), corner(max_u, min_v),
+                corner(max_u, max_v), corner(min_u, max_v)
+            ];
+            best = Some((area, rect));
+        }
+    }
+
+    best.map(|(_, rect)| rect)
+}
+
+/// Whether two boxes given by their min and max corners share any point
+pub fn boxes_overlap([amin, amax]: [Vec2; 2], [bmin, bmax]: [Vec2; 2]) -> bool {
+    amin.x <= bmax.x && bmin.x <= amax.x && amin.y <= bmax.y && bmin.y <= amax.y
+}
+
+/// Whether the `inner` box is entirely within the `outer` one
+pub fn box_contains([omin, omax]: [Vec2; 2], [imin, imax]: [Vec2; 2]) -> bool {
+    omin.cmple(imin).all() && imax.cmple(omax).all()
+}
+
+/// Split a segment at t with De Casteljau's algorithm, both halves keep the same shape
+pub fn split(points: &[Point], t: f32) -> ([Point; 4], [Point; 4]) {
+    bezier::split([points[0], points[1], points[2], points[3]], t)
+}
+
+/// Parameter of the point on the segment closest to `target`, along with that point.
+/// A coarse scan picks the starting guess then Newton's method refines the root of
+/// (B(t) - target) . B'(t)
+pub fn closest_param(points: &[Point], target: Vec2) -> (f32, Vec2) {
+    let (t, pos) = bezier::closest_param(positions(points), target.into());
+    (t, pos.into())
+}
+
+/// Two cubics cross at most this many times unless they overlap along a stretch
+pub const MAX_INTERSECTIONS: usize = 9;
+
+/// Parameters (ta, tb) where the two segments cross, found by recursively subdividing
+/// both while their control boxes overlap until they're smaller than `tolerance`. Segments
+/// running along each other stop at MAX_INTERSECTIONS points of the shared stretch
+pub fn intersections(a: &[Point], b: &[Point], tolerance: f32) -> Vec<(f32, f32)> {
+    fn control_box(points: &[Point]) -> [Vec2; 2] {
+        points.iter().fold([Vec2::splat(f32::MAX), Vec2::splat(f32::MIN)], |[pmin, pmax], p| {
+            [pmin.min(p.pos), pmax.max(p.pos)]
+        })
+    }
+
+    fn recurse(
+        a: [Point; 4], (a0, a1): (f32, f32),
+        b: [Point; 4], (b0, b1): (f32, f32),
+        tolerance: f32, depth: u32, hits: &mut Vec<(f32, f32)>
+    ) {
+        let [amin, amax] = control_box(&a);
+        let [bmin, bmax] = control_box(&b);
+        let overlaps = amin.x <= bmax.x && bmin.x <= amax.x && amin.y <= bmax.y && bmin.y <= amax.y;
+        if !overlaps || hits.len() >= MAX_INTERSECTIONS { return };
+
+        let small = (amax - amin).max_element() < tolerance && (bmax - bmin).max_element() < tolerance;
+        if small || depth > 24 {
+            let hit = ((a0 + a1) / 2., (b0 + b1) / 2.);
+
+            // Neighbouring leaves usually find the same crossing, keep only the first one
+            let duplicate = hits.iter().any(|(ta, tb)| (ta - hit.0).abs() < 1e-3 && (tb - hit.1).abs() < 1e-3);
+            if !duplicate { hits.push(hit) };
+            return;
+        }
+
+        let (a_left, a_right) = split(&a, 0.5);
+        let (b_left, b_right) = split(&b, 0.5);
+        let (am, bm) = ((a0 + a1) / 2., (b0 + b1) / 2.);
+
+        recurse(a_left, (a0, am), b_left, (b0, bm), tolerance, depth + 1, hits);
+        recurse(a_left, (a0, am), b_right, (bm, b1), tolerance, depth + 1, hits);
+        recurse(a_right, (am, a1), b_left, (b0, bm), tolerance, depth + 1, hits);
+        recurse(a_right, (am, a1), b_right, (bm, b1), tolerance, depth + 1, hits);
+    }
+
+    let mut hits = Vec::new();
+    let a = [a[0], a[1], a[2], a[3]];
+    let b = [b[0], b[1], b[2], b[3]];
+    recurse(a, (0., 1.), b, (0., 1.), tolerance, 0, &mut hits);
+
+    hits
+}
+
+pub(crate) use numerical_curves_core::length::GAUSS_LEGENDRE_5;
+
+/// Arc length between t0 and t1, integrating |B'(t)| with composite Gauss-Legendre quadrature
+pub fn arc_length_between(points: [Point; 4], t0: f32, t1: f32) -> f32 {
+    length::arc_length_between(positions(&points), t0, t1)
+}
+
+/// Arc length of the whole segment
+pub fn arc_length(points: [Point; 4]) -> f32 {
+    length::arc_length(positions(&points))
+}
+
+/// Signed curvature k(t) = (B' x B'') / |B'|^3, zero where the speed vanishes
+pub fn curvature(points: [Point; 4], t: f32) -> f32 {
+    bezier::curvature(positions(&points), t)
+}
+
+pub use numerical_curves_core::bezier::BEZIER_MATRIX;
+
+/// Power basis coefficients [c0, c1, c2, c3] so that B(t) = c0 + c1 t + c2 t^2 + c3 t^3,
+/// the product M P of the basis matrix with the control points
+pub fn power_basis(points: [Point; 4]) -> [Vec2; 4] {
+    bezier::power_basis(positions(&points)).map(Vec2::from)
+}
+
+/// B(t) from the coefficients of `power_basis`, the row [1 t t^2 t^3] times M P. With them
+/// worked out once per segment each sample is the two powers and three fused multiply-adds
+pub fn eval_power_basis(coefficients: &[Vec2; 4], t: f32) -> Vec2 {
+    bezier::eval_power_basis(&coefficients.map(Vector2::from), t).into()
+}
+
+/// Real roots of c0 + c1 t + c2 t^2 + c3 t^3, falling back to lower degrees when the
+/// leading coefficients vanish. Uses the trigonometric method for three real roots
+pub fn cubic_roots(coefficients: [f32; 4]) -> Vec<f32> {
+    // In f64, the cubic formula loses too much in f32
+    roots::cubic_roots(coefficients.map(f64::from)).into_iter().map(|t| t as f32).collect()
+}
+
+/// Parameters in [0, 1] where the segment crosses the line through `origin` along `dir`,
+/// the roots of (B(t) - origin) . n for the line's normal n
+pub fn line_crossings(points: [Point; 4], origin: Vec2, dir: Vec2) -> Vec<f32> {
+    let normal = dir.perp();
+    let [c0, c1, c2, c3] = power_basis(points);
+    let coefficients = [(c0 - origin).dot(normal), c1.dot(normal), c2.dot(normal), c3.dot(normal)];
+
+    cubic_roots(coefficients).into_iter()
+        .filter(|t| (-1e-6..=1. + 1e-6).contains(t))
+        .map(|t| t.clamp(0., 1.))
+        .collect()
+}
+
+/// Thomas algorithm for a tridiagonal system, `lower[i]`/`upper[i]` multiply x[i-1]/x[i+1]
+/// on row i (`lower[0]` and the last `upper` are ignored)
+pub fn solve_tridiagonal(lower: &[f32], diagonal: &[f32], upper: &[f32], rhs: &[Vec2]) -> Vec<Vec2> {
+    let n = diagonal.len();
+    let mut c = vec![0.; n];
+    let mut d = vec![Vec2::ZERO; n];
+
+    c[0] = upper[0] / diagonal[0];
+    d[0] = rhs[0] / diagonal[0];
+    for i in 1..n {
+        let m = diagonal[i] - lower[i] * c[i - 1];
+        c[i] = if i + 1 < n { upper[i] / m } else { 0. };
+        d[i] = (rhs[i] - lower[i] * d[i - 1]) / m;
+    }
+
+    for i in (0..n - 1).rev() {
+        d[i] = d[i] - c[i] * d[i + 1];
+    }
+
+    d
+}
+
+/// Handles (first, second) of each segment of the natural C2 cubic spline through `anchors`.
+/// C1 and C2 at every join plus zero curvature at both ends gives a tridiagonal system on the
+/// first handles, the second handles follow from them
+pub fn smooth_handles(anchors: &[Vec2]) -> Vec<(Vec2, Vec2)> {
+    let n = anchors.len().saturating_sub(1);
+    if n == 0 { return Vec::new() };
+    if n == 1 {
+        let (a, b) = (anchors[0], anchors[1]);
+        return vec![(a.lerp(b, 1. / 3.), a.lerp(b, 2. / 3.))];
+    }
+
+    let k = anchors;
+    let (mut lower, mut diagonal, upper) = (vec![1.; n], vec![4.; n], vec![1.; n]);
+    let mut rhs: Vec<Vec2> = (0..n).map(|i| 4. * k[i] + 2. * k[i + 1]).collect();
+
+    diagonal[0] = 2.;
+    rhs[0] = k[0] + 2. * k[1];
+    lower[n - 1] = 2.;
+    diagonal[n - 1] = 7.;
+    rhs[n - 1] = 8. * k[n - 1] + k[n];
+
+    let first = solve_tridiagonal(&lower, &diagonal, &upper, &rhs);
+
+    (0..n).map(|i| {
+        let second = match i + 1 < n {
+            true => 2. * k[i + 1] - first[i + 1],
+            false => (k[n] + first[n - 1]) / 2.
+        };
+        (first[i], second)
+    }).collect()
+}
+
+/// De Casteljau's algorithm for a bezier of any degree (points.len() - 1)
+pub fn decasteljau_n(points: &[Point], t: f32) -> Point {
+    let mut level = points.to_vec();
+    for len in (1..points.len()).rev() {
+        for i in 0..len {
+            level[i] = level[i].lerp(&level[i + 1], t);
+        }
+    }
+
+    level[0]
+}
+
+/// Cubic approximation of a higher degree bezier and how far it strays
+pub struct DegreeReduction {
+    pub cubic: [Point; 4],
+    /// Largest distance between the two curves at equal parameters
+    pub max_error: f32
+}
+
+/// Approximate a bezier of degree >= 3 with a cubic keeping both end points. The inner
+/// handles are the least squares fit over uniformly sampled parameters, which for each
+/// axis is a 2x2 linear system on the unknown handles
+pub fn reduce_degree(points: &[Point]) -> Option<DegreeReduction> {
+    if points.len() < 4 { return None };
+    let (start, end) = (points[0], points[points.len() - 1]);
+
+    const SAMPLES: usize = 64;
+    let ts = (0..=SAMPLES).map(|i| i as f32 / SAMPLES as f32);
+
+    // Normal equations of min Σ |b1 P1 + b2 P2 - (target - b0 P0 - b3 P3)|²
+    let (mut a11, mut a12, mut a22) = (0., 0., 0.);
+    let (mut r1, mut r2) = (Vec2::ZERO, Vec2::ZERO);
+    for t in ts.clone() {
+        let s = 1. - t;
+        let (b0, b1, b2, b3) = (s * s * s, 3. * s * s * t, 3. * s * t * t, t * t * t);
+        let residual = decasteljau_n(points, t).pos - b0 * start.pos - b3 * end.pos;
+
+        a11 += b1 * b1;
+        a12 += b1 * b2;
+        a22 += b2 * b2;
+        r1 += b1 * residual;
+        r2 += b2 * residual;
+    }
+
+    let det = a11 * a22 - a12 * a12;
+    if det.abs() < f32::EPSILON { return None };
+    let p1 = (a22 * r1 - a12 * r2) / det;
+    let p2 = (a11 * r2 - a12 * r1) / det;
+
+    let cubic = [
+        start,
+        Point::new(p1, start.lerp(&end, 1. / 3.).color),
+        Point::new(p2, start.lerp(&end, 2. / 3.).color),
+        end
+    ];
+
+    let max_error = (0..=SAMPLES * 4)
+        .map(|i| i as f32 / (SAMPLES * 4) as f32)
+        .map(|t| cubic_bezier(t, &cubic).distance(decasteljau_n(points, t).pos))
+        .fold(0., f32::max);
+
+    Some(DegreeReduction { cubic, max_error })
+}
+
+/// Parameter where the arc length measured from t = 0 reaches `length`. Newton's method on
+/// s(t) - length, since s'(t) is the speed, falling back to bisection when a step leaves the bracket
+pub fn param_at_length(points: [Point; 4], length: f32) -> f32 {
+    length::param_at_length(positions(&points), length)
+}
+
+/// Split a segment at the given increasing parameters into independent cubics
+pub fn split_at_params(points: [Point; 4], ts: &[f32]) -> Vec<[Point; 4]> {
+    let mut pieces = Vec::with_capacity(ts.len() + 1);
+    let (mut rest, mut consumed) = (points, 0.);
+
+    for &t in ts {
+        // Remap t into the remaining piece, which starts at `consumed`
+        let (left, right) = split(&rest, (t - consumed) / (1. - consumed));
+        pieces.push(left);
+        rest = right;
+        consumed = t;
+    }
+    pieces.push(rest);
+
+    pieces
+}
+
+/// Split a segment into `n` pieces spanning equal parameter ranges
+pub fn subdivide(points: [Point; 4], n: usize) -> Vec<[Point; 4]> {
+    let ts: Vec<f32> = (1..n).map(|i| i as f32 / n as f32).collect();
+    split_at_params(points, &ts)
+}
+
+/// Split a segment into `n` pieces of equal arc length
+pub fn subdivide_by_length(points: [Point; 4], n: usize) -> Vec<[Point; 4]> {
+    let total = arc_length(points);
+    let ts: Vec<f32> = (1..n).map(|i| param_at_length(points, total * i as f32 / n as f32)).collect();
+    split_at_params(points, &ts)
+}
+
+/// Pieces needed so that joining uniformly spaced samples of the segment with straight lines
+/// never strays more than `tolerance` from it. Wang's formula bounds the deviation by the
+/// second differences of the control points, which bound |B''(t)|
+pub fn flattening_steps(points: &[Point], tolerance: f32) -> usize {
+    bezier::flattening_steps(positions(points), tolerance)
+}
+
+/// Polyline through the segment within `tolerance` of the true curve, end points included
+pub fn flatten(points: &[Point], tolerance: f32) -> Vec<Point> {
+    let steps = flattening_steps(points, tolerance);
+    (0..=steps).map(|i| decasteljau(points, i as f32 / steps as f32)).collect()
+}
+
+/// `steps + 1` evenly spaced points of the segment by forward differencing: after the setup
+/// each sample is three additions, at the cost of rounding error growing along the curve
+pub fn forward_differences(points: [Point; 4], steps: usize) -> Vec<Vec2> {
+    CubicSegment::new(points).iter_samples(steps.max(1) + 1).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segment(positions: [Vec2; 4]) -> [Point; 4] {
+        positions.map(|pos| Point::new(pos, WHITE))
+    }
+
+    #[test]
+    fn crossing_lines_meet_once() {
+        let a = segment([vec2(0., 0.), vec2(1., 1.), vec2(2., 2.), vec2(3., 3.)]);
+        let b = segment([vec2(0., 3.), vec2(1., 2.), vec2(2., 1.), vec2(3., 0.)]);
+        let hits = intersections(&a, &b, 1e-3);
+        assert_eq!(hits.len(), 1);
+        assert!((hits[0].0 - 0.5).abs() < 1e-2 && (hits[0].1 - 0.5).abs() < 1e-2);
+    }
+
+    #[test]
+    fn coincident_segments_stop_at_the_cap() {
+        let a = segment([vec2(0., 0.), vec2(10., 40.), vec2(30., -40.), vec2(40., 0.)]);
+        let hits = intersections(&a, &a, 1e-3);
+        assert!(!hits.is_empty() && hits.len() <= MAX_INTERSECTIONS);
+    }
+
+    // The natural spline through the anchors as bezier segments
+    fn smooth_segments(anchors: &[Vec2]) -> Vec<[Point; 4]> {
+        smooth_handles(anchors).into_iter().enumerate()
+            .map(|(i, (first, second))| segment([anchors[i], first, second, anchors[i + 1]]))
+            .collect()
+    }
+
+    fn assert_natural_c2(anchors: &[Vec2]) {
+        let segments = smooth_segments(anchors);
+        assert_eq!(segments.len(), anchors.len() - 1);
+        let close = |a: Vec2, b: Vec2| a.distance(b) < 1e-2 * a.length().max(b.length()).max(1.);
+
+        for pair in segments.windows(2) {
+            assert!(close(velocity(pair[0], 1.), velocity(pair[1], 0.)));
+            assert!(close(acceleration(pair[0], 1.), acceleration(pair[1], 0.)));
+        }
+        assert!(acceleration(segments[0], 0.).length() < 1e-2);
+        assert!(acceleration(segments[segments.len() - 1], 1.).length() < 1e-2);
+    }
+
+    #[test]
+    fn smooth_handles_are_natural_c2() {
+        assert_natural_c2(&[vec2(0., 0.), vec2(30., 10.)]);
+        assert_natural_c2(&[vec2(0., 0.), vec2(30., 40.), vec2(70., 0.)]);
+        assert_natural_c2(&[vec2(0., 0.), vec2(30., 40.), vec2(70., 0.), vec2(90., 50.), vec2(60., 80.)]);
+        assert!(smooth_handles(&[vec2(1., 2.)]).is_empty());
+    }
+
+    // One degree up, the same curve with one more control point
+    fn elevate(points: &[Point]) -> Vec<Point> {
+        let n = points.len();
+        let mut elevated = vec![points[0]];
+        for i in 1..n {
+            let a = i as f32 / n as f32;
+            elevated.push(points[i].lerp(&points[i - 1], a));
+        }
+        elevated.push(points[n - 1]);
+        elevated
+    }
+
+    #[test]
+    fn elevated_cubic_reduces_back_exactly() {
+        let cubic = segment([vec2(0., 0.), vec2(20., 60.), vec2(70., -30.), vec2(90., 20.)]);
+        let quintic = elevate(&elevate(&cubic));
+        assert_eq!(quintic.len(), 6);
+
+        let reduction = reduce_degree(&quintic).unwrap();
+        assert!(reduction.max_error < 1e-3);
+        for (p, q) in reduction.cubic.iter().zip(&cubic) {
+            assert!(p.pos.distance(q.pos) < 1e-3);
+        }
+    }
+
+    #[test]
+    fn reduction_error_bounds_the_distance() {
+        let quintic = [vec2(0., 0.), vec2(10., 80.), vec2(40., -60.), vec2(60., 90.), vec2(80., -20.), vec2(100., 10.)]
+            .map(|pos| Point::new(pos, WHITE));
+        let reduction = reduce_degree(&quintic).unwrap();
+        assert!(reduction.max_error > 1.);
+        assert_eq!(reduction.cubic[0].pos, quintic[0].pos);
+        assert_eq!(reduction.cubic[3].pos, quintic[5].pos);
+
+        // Parameters off the grid the error was measured on
+        for i in 0..=997 {
+            let t = i as f32 / 997.;
+            let distance = cubic_bezier(t, &reduction.cubic).distance(decasteljau_n(&quintic, t).pos);
+            assert!(distance <= reduction.max_error * 1.01 + 1e-3);
+        }
+        assert!(reduce_degree(&quintic[..3]).is_none());
+    }
+}
@@ -0,0 +1,102 @@
+use macroquad::prelude::*;
+
+use crate::coords::CoordinateSystem;
+use crate::document::Document;
+
+/// One end of a measurement
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MeasureEnd {
+    pub pos: Vec2,
+    /// From its anchor to the handle, when the end is one
+    pub handle: Option<Vec2>,
+    /// Whether the end is the point of a curve closest to the other end
+    pub on_curve: bool
+}
+
+/// Distance between two clicked points, or from a point to the nearest spot of a clicked
+/// curve, and the angle between two clicked handles. A third click starts over
+#[derive(Clone, Debug, Default)]
+pub struct Measure {
+    pub first: Option<MeasureEnd>,
+    pub second: Option<MeasureEnd>
+}
+
+// What a click at `p` lands on: a control point of a visible curve within `radius`, or with
+// `from` set the point on a curve passing within `radius` closest to `from`
+fn end_at(doc: &Document, p: Vec2, radius: f32, from: Option<Vec2>) -> MeasureEnd {
+    let visible: Vec<_> = doc.draw_order().into_iter().filter(|&i| doc.layer_of(i).visible).collect();
+
+    for &i in visible.iter().rev() {
+        let control = &doc.curves[i].control;
+        let Some(j) = control.iter().rposition(|q| q.pos.distance(p) <= radius) else { continue };
+
+        let anchor = ((j + 1) / 3 * 3).min(control.len() - 1);
+        let handle = match j % 3 == 0 || doc.curves[i].knots.is_some() {
+            true => None,
+            false => Some(control[j].pos - control[anchor].pos)
+        };
+        return MeasureEnd { pos: control[j].pos, handle, on_curve: false };
+    }
+
+    let closest_on_curve = from.and_then(|from| {
+        let nearer = |a: &Vec2, b: &Vec2| a.distance(from).total_cmp(&b.distance(from));
+        visible.iter()
+            .map(|&i| &doc.curves[i])
+            .filter(|curve| curve.pick_segment(p, radius).is_some())
+            .filter_map(|curve| curve.segment_caches().iter().map(|segment| segment.project(from).1).min_by(nearer))
+            .min_by(nearer)
+    });
+    match closest_on_curve {
+        Some(pos) => MeasureEnd { pos, handle: None, on_curve: true },
+        None => MeasureEnd { pos: p, handle: None, on_curve: false }
+    }
+}
+
+impl Measure {
+    /// Place the next end at a click on `p`, `radius` is how far a point or curve can be
+    /// from it to count as clicked
+    pub fn click(&mut self, doc: &Document, p: Vec2, radius: f32) {
+        match (self.first, self.second) {
+            (Some(first), None) => self.second = Some(end_at(doc, p, radius, Some(first.pos))),
+            _ => *self = Measure { first: Some(end_at(doc, p, radius, None)), second: None }
+        }
+    }
+
+    /// Lines describing the finished measurement in units, None until both ends are placed
+    pub fn readout(&self, coords: &CoordinateSystem) -> Option<Vec<String>> {
+        let (first, second) = (self.first?, self.second?);
+        let delta = coords.to_math(second.pos) - coords.to_math(first.pos);
+        let to = match second.on_curve {
+            true => "distance to curve",
+            false => "distance"
+        };
+
+        let mut lines = vec![
+            format!("{}: {:.3}", to, delta.length()),
+            format!("dx: {:.3}, dy: {:.3}", delta.x, delta.y)
+        ];
+        if let (Some(a), Some(b)) = (first.handle, second.handle) {
+            let cos = a.normalize_or_zero().dot(b.normalize_or_zero()).clamp(-1., 1.);
+            lines.push(format!("angle between handles: {:.2}°", cos.acos().to_degrees()));
+        }
+        Some(lines)
+    }
+
+    /// The measured line, or the one from the first end to `cursor` while the second end is
+    /// still to be placed, and handle directions as rays from their anchors. `scale` is world
+    /// units per pixel
+    pub fn draw(&self, cursor: Vec2, scale: f32) {
+        let color = Color::new(1., 0.85, 0.2, 0.9);
+        let Some(first) = self.first else { return };
+        let second = self.second.map_or(cursor, |end| end.pos);
+
+        draw_line(first.pos.x, first.pos.y, second.x, second.y, 1.5 * scale, color);
+        for end in [Some(first), self.second].into_iter().flatten() {
+            draw_circle(end.pos.x, end.pos.y, 3. * scale, color);
+            if let Some(handle) = end.handle {
+                let anchor = end.pos - handle;
+                draw_line(anchor.x, anchor.y, end.pos.x, end.pos.y, 2.5 * scale, Color::new(1., 0.85, 0.2, 0.5));
+            }
+        }
+    }
+}
@@ -0,0 +1,109 @@
+use macroquad::prelude::*;
+
+use crate::bvh::Bvh;
+use crate::curve::Curve;
+
+/// Cells across the longer side of the shape's bounds
+pub const MEDIAL_AXIS_RESOLUTION: usize = 96;
+// Neighbouring cells whose directions to the edge are further apart than this, in radians,
+// straddle the axis. Lower angles also pick up branches from every slight bump of the edge
+const MIN_SPREAD_ANGLE: f32 = 1.2;
+
+/// Approximate medial axis of a closed shape, the centers of the circles that touch its
+/// edge in two or more places, as points with the radius there and lines between neighbours
+pub struct MedialAxis {
+    pub points: Vec<Vec2>,
+    pub radii: Vec<f32>,
+    /// Pairs of indices into `points`
+    pub edges: Vec<(usize, usize)>
+}
+
+/// Medial axis of the closed `curve` from the ridges of its distance field on a grid of
+/// `resolution` cells across. A cell is on a ridge where the closest edge point jumps to
+/// another part of the edge from it to a shallower neighbour. None for open curves
+pub fn medial_axis(curve: &Curve, resolution: usize) -> Option<MedialAxis> {
+    let [min, max] = curve.bounds().filter(|_| curve.closed && resolution > 1)?;
+    let cell = (max - min).max_element().max(f32::EPSILON) / resolution as f32;
+    let (columns, rows) = (((max.x - min.x) / cell).ceil() as usize, ((max.y - min.y) / cell).ceil() as usize);
+    let bvh = Bvh::new(std::slice::from_ref(curve));
+    let center = |column: usize, row: usize| min + (vec2(column as f32, row as f32) + 0.5) * cell;
+
+    // Unit direction to the closest edge point and the distance to it, for cells inside. On
+    // cells touching the edge the direction says nothing, they're left out
+    let closest: Vec<Option<(Vec2, f32)>> = (0..columns * rows)
+        .map(|i| {
+            let p = center(i % columns, i / columns);
+            if curve.winding_number(p) == 0 { return None };
+            let hit = bvh.closest(p).filter(|hit| hit.distance > cell)?;
+            Some(((hit.pos - p).try_normalize()?, hit.distance))
+        })
+        .collect();
+
+    let mut index: Vec<Option<usize>> = vec![None; columns * rows];
+    let mut axis = MedialAxis { points: Vec::new(), radii: Vec::new(), edges: Vec::new() };
+    for row in 0..rows {
+        for column in 0..columns {
+            let Some((direction, distance)) = closest[row * columns + column] else { continue };
+            let neighbours = [(column.wrapping_sub(1), row), (column + 1, row), (column, row.wrapping_sub(1)), (column, row + 1)];
+            let on_ridge = neighbours.into_iter()
+                .filter(|&(c, r)| c < columns && r < rows)
+                .filter_map(|(c, r)| closest[r * columns + c])
+                .any(|(other, other_distance)| other_distance <= distance && direction.angle_between(other).abs() > MIN_SPREAD_ANGLE);
+            if on_ridge {
+                index[row * columns + column] = Some(axis.points.len());
+                axis.points.push(center(column, row));
+                axis.radii.push(distance);
+            }
+        }
+    }
+
+    // Join every ridge cell to the ones after it among its eight neighbours
+    for row in 0..rows {
+        for column in 0..columns {
+            let Some(a) = index[row * columns + column] else { continue };
+            let after = [(column + 1, row), (column.wrapping_sub(1), row + 1), (column, row + 1), (column + 1, row + 1)];
+            for (c, r) in after.into_iter().filter(|&(c, r)| c < columns && r < rows) {
+                if let Some(b) = index[r * columns + c] {
+                    axis.edges.push((a, b));
+                }
+            }
+        }
+    }
+
+    Some(axis)
+}
+
+/// The medial axis of the active curve, redone only when its points change since it takes
+/// too long to compute every frame
+#[derive(Default)]
+pub struct MedialAxisCache {
+    // Control points and closedness the axis was computed for
+    key: (Vec<Vec2>, bool),
+    axis: Option<MedialAxis>
+}
+
+impl MedialAxisCache {
+    /// The axis of `curve`, None for open curves
+    pub fn update(&mut self, curve: &Curve) -> Option<&MedialAxis> {
+        let key = (curve.control.iter().map(|p| p.pos).collect(), curve.closed);
+        if key != self.key {
+            self.axis = medial_axis(curve, MEDIAL_AXIS_RESOLUTION);
+            self.key = key;
+        }
+        self.axis.as_ref()
+    }
+
+    pub fn axis(&self) -> Option<&MedialAxis> {
+        self.axis.as_ref()
+    }
+}
+
+/// The axis as lines, thicker and more opaque where the shape is wider, with the world camera set
+pub fn draw_medial_axis(axis: &MedialAxis, line_width: f32, color: Color) {
+    let widest = axis.radii.iter().copied().fold(f32::EPSILON, f32::max);
+    for &(a, b) in &axis.edges {
+        let (p, q) = (axis.points[a], axis.points[b]);
+        let depth = (axis.radii[a] + axis.radii[b]) / 2. / widest;
+        draw_line(p.x, p.y, q.x, q.y, line_width * (1. + depth), Color { a: color.a * (0.4 + 0.6 * depth), ..color });
+    }
+}
@@ -0,0 +1,61 @@
+use std::ops::Range;
+
+use macroquad::prelude::*;
+
+/// Circle a path has to stay out of
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Obstacle {
+    pub center: Vec2,
+    pub radius: f32
+}
+
+// Distance from `p` to the line segment between `a` and `b`
+fn segment_distance(p: Vec2, a: Vec2, b: Vec2) -> f32 {
+    let ab = b - a;
+    let t = match ab.length_squared() > 0. {
+        true => ((p - a).dot(ab) / ab.length_squared()).clamp(0., 1.),
+        false => 0.
+    };
+    p.distance(a + ab * t)
+}
+
+impl Obstacle {
+    /// Signed distance from the edge, negative inside
+    pub fn distance(&self, p: Vec2) -> f32 {
+        self.center.distance(p) - self.radius
+    }
+
+    /// Whether the line between `a` and `b` passes through the circle
+    pub fn hits_line(&self, a: Vec2, b: Vec2) -> bool {
+        segment_distance(self.center, a, b) < self.radius
+    }
+}
+
+/// Stretches of the polyline through `samples` that pass through an obstacle, as ranges
+/// of sample indices with the points on both ends of each offending line
+pub fn collisions(samples: &[Vec2], obstacles: &[Obstacle]) -> Vec<Range<usize>> {
+    let mut stretches: Vec<Range<usize>> = Vec::new();
+    for (i, pair) in samples.windows(2).enumerate() {
+        if !obstacles.iter().any(|obstacle| obstacle.hits_line(pair[0], pair[1])) { continue };
+        match stretches.last_mut() {
+            Some(stretch) if stretch.end == i + 1 => stretch.end = i + 2,
+            _ => stretches.push(i..i + 2)
+        }
+    }
+
+    stretches
+}
+
+/// Index of the obstacle under `p`, the topmost (last placed) one when they overlap
+pub fn obstacle_at(obstacles: &[Obstacle], p: Vec2) -> Option<usize> {
+    obstacles.iter().rposition(|obstacle| obstacle.distance(p) <= 0.)
+}
+
+/// Every obstacle as a shaded disk, with the world camera set
+pub fn draw_obstacles(obstacles: &[Obstacle], line_width: f32) {
+    for obstacle in obstacles {
+        let Vec2 { x, y } = obstacle.center;
+        draw_circle(x, y, obstacle.radius, Color::new(1., 0.3, 0.2, 0.2));
+        draw_circle_lines(x, y, obstacle.radius, line_width, Color::new(1., 0.3, 0.2, 0.8));
+    }
+}
@@ -0,0 +1,103 @@
+use macroquad::prelude::*;
+
+use crate::curve::Curve;
+use crate::math::{cubic_bezier, velocity};
+
+/// Spacings between offsets, in units, that shift + the offsets key cycles through
+pub const OFFSET_SPACINGS: [f32; 4] = [0.1, 0.25, 0.5, 1.];
+/// Offsets drawn on each side of the curve
+pub const OFFSETS_PER_SIDE: usize = 5;
+const SAMPLES_PER_SEGMENT: usize = 48;
+// Offset points this much closer to the curve than their distance are in a swallowtail,
+// the slack covers the polyline cutting corners of the curve
+const TRIM_SLACK: f32 = 0.02;
+
+/// One curve of the family, `distance` world units to the left of the curve (negative to the
+/// right) as the pieces that survive trimming
+pub struct Offset {
+    pub distance: f32,
+    pub pieces: Vec<Vec<Vec2>>
+}
+
+// Positions and unit normals along every segment, the joints only once
+fn samples(curve: &Curve) -> Vec<(Vec2, Vec2)> {
+    let mut samples: Vec<(Vec2, Vec2)> = Vec::new();
+    for segment in curve.segments() {
+        let first = match samples.is_empty() {
+            true => 0,
+            false => 1
+        };
+        for i in first..=SAMPLES_PER_SEGMENT {
+            let t = i as f32 / SAMPLES_PER_SEGMENT as f32;
+            // Where the curve stops for a moment the previous direction stands in
+            let normal = velocity(segment, t).try_normalize()
+                .map(|tangent| tangent.perp())
+                .or_else(|| samples.last().map(|&(_, normal)| normal))
+                .unwrap_or(Vec2::Y);
+            samples.push((cubic_bezier(t, &segment), normal));
+        }
+    }
+
+    samples
+}
+
+// Distance from `p` to the polyline, stopping early once it's known to be below `limit`
+fn distance_below(p: Vec2, polyline: &[Vec2], limit: f32) -> bool {
+    polyline.windows(2).any(|pair| {
+        let (a, ab) = (pair[0], pair[1] - pair[0]);
+        let t = match ab.length_squared() > 0. {
+            true => ((p - a).dot(ab) / ab.length_squared()).clamp(0., 1.),
+            false => 0.
+        };
+        p.distance(a + ab * t) < limit
+    })
+}
+
+/// Offsets at ±`spacing`, ±2 `spacing` and so on `count` times to each side of `curve`, in
+/// world units. Past the radius of curvature a raw offset folds over itself into a cusp and
+/// a loop, any of its points that ends up closer to the curve than the offset distance is
+/// trimmed, which also removes the parts that run into other stretches of the curve
+pub fn offset_family(curve: &Curve, spacing: f32, count: usize) -> Vec<Offset> {
+    let samples = samples(curve);
+    if samples.len() < 2 || spacing <= 0. { return Vec::new() };
+    let polyline: Vec<Vec2> = samples.iter().map(|&(pos, _)| pos).collect();
+
+    (1..=count)
+        .flat_map(|k| [k as f32 * spacing, -(k as f32) * spacing])
+        .map(|distance| {
+            let limit = distance.abs() * (1. - TRIM_SLACK);
+            let mut pieces: Vec<Vec<Vec2>> = Vec::new();
+            let mut open = false;
+            for &(pos, normal) in &samples {
+                let offset = pos - normal * distance;
+                let kept = !distance_below(offset, &polyline, limit);
+                match pieces.last_mut().filter(|_| open) {
+                    Some(piece) if kept => piece.push(offset),
+                    None if kept => pieces.push(vec![offset]),
+                    _ => ()
+                }
+                open = kept;
+            }
+            pieces.retain(|piece| piece.len() > 1);
+            Offset { distance, pieces }
+        })
+        .collect()
+}
+
+/// Every offset as lines, fading with the distance, with the world camera set. Left offsets
+/// take `left` and right ones `right`
+pub fn draw_offsets(offsets: &[Offset], line_width: f32, left: Color, right: Color) {
+    let farthest = offsets.iter().map(|offset| offset.distance.abs()).fold(0., f32::max);
+    for offset in offsets {
+        let color = match offset.distance > 0. {
+            true => left,
+            false => right
+        };
+        let color = Color { a: color.a * (1. - 0.7 * offset.distance.abs() / farthest), ..color };
+        for piece in &offset.pieces {
+            for pair in piece.windows(2) {
+                draw_line(pair[0].x, pair[0].y, pair[1].x, pair[1].y, line_width, color);
+            }
+        }
+    }
+}
@@ -0,0 +1,67 @@
+use macroquad::prelude::*;
+
+use crate::curve::Curve;
+use crate::math::arc_length;
+use crate::vehicle::{pose_at, Pose};
+
+// Radii past this many times the curve's size are drawn as the tangent line, the circle
+// they approach as the curvature goes to zero
+const MAX_RADIUS_FACTOR: f32 = 10.;
+
+/// Best fitting circle of a curve at one of its points, or its tangent line where the
+/// curve is straight enough for the circle to be unbounded
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Osculating {
+    /// Center p + n / κ and radius 1 / |κ|
+    Circle { center: Vec2, radius: f32 },
+    Line { point: Vec2, direction: Vec2 }
+}
+
+/// The osculating circle at `pose`, the line when its radius would pass `max_radius`
+pub fn osculating_at(pose: &Pose, max_radius: f32) -> Osculating {
+    match pose.curvature.abs() * max_radius > 1. {
+        true => Osculating::Circle { center: pose.pos + pose.heading.perp() / pose.curvature, radius: 1. / pose.curvature.abs() },
+        false => Osculating::Line { point: pose.pos, direction: pose.heading }
+    }
+}
+
+/// Largest radius drawn as a circle for `curve`, None without a complete segment
+pub fn max_radius(curve: &Curve) -> Option<f32> {
+    curve.bounds().map(|[min, max]| (max - min).length().max(1.) * MAX_RADIUS_FACTOR)
+}
+
+/// An osculating circle traveling along the active curve by arc length, starting over at the end
+#[derive(Clone, Copy, Debug, Default)]
+pub struct OsculatingTour {
+    /// World units from the start of the curve
+    pub travelled: f32
+}
+
+impl OsculatingTour {
+    /// Travel `distance` world units further along `curve`, returns where the circle touches it
+    pub fn advance(&mut self, curve: &Curve, distance: f32) -> Option<Pose> {
+        let length: f32 = curve.segments().into_iter().map(arc_length).sum();
+        if length <= 0. { return None };
+        self.travelled = (self.travelled + distance) % length;
+        pose_at(curve, self.travelled)
+    }
+}
+
+/// The circle, or the line, with its center and the radius to the point of contact, with
+/// the world camera set. Circles fade out as they grow towards `max_radius`, so the switch
+/// to the line isn't a jump
+pub fn draw_osculating(pose: &Pose, max_radius: f32, line_width: f32, color: Color) {
+    draw_circle(pose.pos.x, pose.pos.y, 3. * line_width, color);
+    match osculating_at(pose, max_radius) {
+        Osculating::Circle { center, radius } => {
+            let fade = Color { a: color.a * (1. - radius / max_radius).clamp(0.2, 1.), ..color };
+            draw_circle_lines(center.x, center.y, radius, line_width, fade);
+            draw_line(center.x, center.y, pose.pos.x, pose.pos.y, line_width, Color { a: fade.a * 0.5, ..color });
+            draw_circle(center.x, center.y, 2. * line_width, fade);
+        },
+        Osculating::Line { point, direction } => {
+            let (a, b) = (point - direction * max_radius, point + direction * max_radius);
+            draw_line(a.x, a.y, b.x, b.y, line_width, Color { a: color.a * 0.2, ..color });
+        }
+    }
+}
@@ -0,0 +1,76 @@
+use macroquad::prelude::*;
+
+use crate::analysis::winding_number;
+use crate::coords::{nice_step, tick_label, CoordinateSystem};
+use crate::document::Document;
+use crate::hud::draw_text_box;
+use crate::view::View;
+
+const WINDING_CELL_SIZE: f32 = 8.0;
+const DISTANCE_CELL_SIZE: f32 = 6.0;
+// Distance bands are a round number of units at least this many pixels wide
+const MIN_BAND_WIDTH: f32 = 16.0;
+
+/// Shade the window by the winding number of each cell's center (blue for positive, red for
+/// negative, stronger the more turns) and show the value under the cursor. Drawn in screen space
+pub fn draw_winding(doc: &Document, view: &View) {
+    // Gathered once, Curve::winding_number would rebuild the segments for every cell
+    let closed: Vec<_> = doc.curves.iter().filter(|c| c.closed).map(|c| c.segments()).collect();
+    let winding_at = |p: Vec2| closed.iter().map(|segments| winding_number(segments, p)).sum::<i32>();
+
+    let (columns, rows) = ((screen_width() / WINDING_CELL_SIZE) as i32, (screen_height() / WINDING_CELL_SIZE) as i32);
+
+    for row in 0..=rows {
+        for column in 0..=columns {
+            let screen = vec2(column as f32, row as f32) * WINDING_CELL_SIZE;
+            let winding = winding_at(view.screen_to_world(screen + WINDING_CELL_SIZE / 2.));
+            if winding == 0 { continue };
+
+            let alpha = (0.15 * winding.abs() as f32).min(0.6);
+            let color = if winding > 0 { Color::new(0.2, 0.4, 1., alpha) } else { Color::new(1., 0.2, 0.2, alpha) };
+            draw_rectangle(screen.x, screen.y, WINDING_CELL_SIZE, WINDING_CELL_SIZE, color);
+        }
+    }
+
+    let mouse: Vec2 = mouse_position().into();
+    let winding = winding_at(view.screen_to_world(mouse));
+    let inside = |yes: bool| if yes { "inside" } else { "outside" };
+    let lines = [
+        format!("winding: {}", winding),
+        format!("nonzero: {}", inside(winding != 0)),
+        format!("even-odd: {}", inside(winding % 2 != 0)),
+    ];
+    draw_text_box(&lines, mouse + vec2(16., 16.));
+}
+
+/// Shade the window by each cell's distance to the nearest curve, in alternating bands a
+/// round number of units wide that fade farther out, and show the distance under the cursor
+pub fn draw_distance_field(doc: &Document, view: &View, coords: &CoordinateSystem) {
+    let bvh = doc.bvh();
+    if bvh.is_empty() { return };
+
+    let band = nice_step(MIN_BAND_WIDTH / (coords.pixels_per_unit * view.scale));
+    let distance_at = |p: Vec2| bvh.closest(view.screen_to_world(p)).map_or(0., |hit| coords.length(hit.distance));
+    let (columns, rows) = ((screen_width() / DISTANCE_CELL_SIZE) as i32, (screen_height() / DISTANCE_CELL_SIZE) as i32);
+
+    for row in 0..=rows {
+        for column in 0..=columns {
+            let screen = vec2(column as f32, row as f32) * DISTANCE_CELL_SIZE;
+            let bands = distance_at(screen + DISTANCE_CELL_SIZE / 2.) / band;
+
+            let strength = match (bands as u32).is_multiple_of(2) {
+                true => 0.35,
+                false => 0.15
+            };
+            let alpha = strength / (1. + 0.1 * bands);
+            draw_rectangle(screen.x, screen.y, DISTANCE_CELL_SIZE, DISTANCE_CELL_SIZE, Color::new(0.2, 0.9, 0.6, alpha));
+        }
+    }
+
+    let mouse: Vec2 = mouse_position().into();
+    let lines = [
+        format!("distance: {}", tick_label(distance_at(mouse), band / 100.)),
+        format!("bands: {} units", tick_label(band, band))
+    ];
+    draw_text_box(&lines, mouse + vec2(16., 16.));
+}
@@ -0,0 +1,44 @@
+use macroquad::prelude::*;
+
+use crate::curve::Curve;
+use crate::math::{arc_length, cubic_bezier, param_at_length, velocity};
+use crate::point::Point;
+
+// Stamps never get closer than this many world units, so tiny templates don't explode
+const MIN_SPACING: f32 = 4.;
+
+/// Distance between stamps of `template` when none is given, its width and half again
+pub fn default_spacing(template: &Curve) -> f32 {
+    let width = template.bounds().map_or(0., |[min, max]| max.x - min.x);
+    (width * 1.5).max(MIN_SPACING)
+}
+
+/// Copies of `template` every `spacing` world units of arc length along `path`, starting
+/// half a spacing in. Each copy is centered on the path with the template's x axis turned
+/// to follow the tangent there. Empty when either curve has no complete segment
+pub fn stamp_along(path: &Curve, template: &Curve, spacing: f32) -> Vec<Curve> {
+    let Some([min, max]) = template.bounds() else { return Vec::new() };
+    let center = (min + max) / 2.;
+    let spacing = spacing.max(MIN_SPACING);
+
+    let mut stamps = Vec::new();
+    let mut next = spacing / 2.;
+    let mut travelled = 0.;
+    for segment in path.segments() {
+        let length = arc_length(segment);
+        while next <= travelled + length {
+            let t = param_at_length(segment, next - travelled);
+            let tangent = velocity(segment, t).try_normalize().unwrap_or(Vec2::X);
+            let pos = cubic_bezier(t, &segment);
+
+            let control = template.control.iter()
+                .map(|p| Point { pos: pos + tangent.rotate(p.pos - center), ..*p })
+                .collect();
+            stamps.push(Curve { control, ..template.duplicate(Vec2::ZERO) });
+            next += spacing;
+        }
+        travelled += length;
+    }
+
+    stamps
+}
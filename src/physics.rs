@@ -0,0 +1,101 @@
+use macroquad::prelude::*;
+
+use crate::curve::Curve;
+
+// Pull towards the rest position and along the polygon, per second squared
+const REST_STIFFNESS: f32 = 60.;
+const NEIGHBOR_STIFFNESS: f32 = 200.;
+// Fraction of the velocity lost per second
+const DAMPING: f32 = 2.5;
+// Longest step integrated at once, stiff springs blow up past it
+const MAX_STEP: f32 = 1. / 240.;
+// Frames longer than this (a dragged window, a hidden tab) don't fling the points
+const MAX_FRAME: f32 = 1. / 20.;
+// How much of the cursor's speed a point right under it picks up
+const IMPULSE_TRANSFER: f32 = 0.6;
+
+/// Springs on the control points of one curve. Each point is a unit mass tied to where it
+/// was when the simulation started and to its neighbours on the control polygon, so
+/// pushing or dragging points makes the whole curve wobble back into shape
+pub struct SoftBody {
+    /// Index of the simulated curve in the document
+    pub curve: usize,
+    rest: Vec<Vec2>,
+    velocity: Vec<Vec2>,
+    // Rest length of the spring from each point to the next one, wrapping for closed curves
+    lengths: Vec<f32>
+}
+
+impl SoftBody {
+    pub fn new(index: usize, curve: &Curve) -> Self {
+        let rest: Vec<Vec2> = curve.control.iter().map(|p| p.pos).collect();
+        let n = rest.len();
+        let links = match curve.closed && n > 2 {
+            true => n,
+            false => n.saturating_sub(1)
+        };
+        let lengths = (0..links).map(|i| rest[i].distance(rest[(i + 1) % n])).collect();
+        Self { curve: index, velocity: vec![Vec2::ZERO; n], rest, lengths }
+    }
+
+    /// Whether `curve` still has the points the simulation was started with
+    pub fn fits(&self, curve: &Curve) -> bool {
+        curve.control.len() == self.rest.len()
+    }
+
+    /// Push the points within `radius` of `center` towards the cursor's `velocity`, the
+    /// closer the harder
+    pub fn impulse(&mut self, curve: &Curve, center: Vec2, radius: f32, velocity: Vec2) {
+        for (p, v) in curve.control.iter().zip(&mut self.velocity) {
+            let closeness = 1. - p.pos.distance(center) / radius;
+            if closeness > 0. {
+                *v += (velocity - *v) * closeness * IMPULSE_TRANSFER;
+            }
+        }
+    }
+
+    /// Advance by `dt` seconds. Locked points and `held`, the one being dragged, stay put
+    pub fn step(&mut self, curve: &mut Curve, dt: f32, held: Option<usize>) {
+        if !self.fits(curve) { return };
+        let dt = dt.min(MAX_FRAME);
+        let steps = (dt / MAX_STEP).ceil().max(1.) as usize;
+        let h = dt / steps as f32;
+        let n = self.rest.len();
+
+        let mut positions: Vec<Vec2> = curve.control.iter().map(|p| p.pos).collect();
+        let pinned: Vec<bool> = (0..n).map(|i| curve.control[i].locked || held == Some(i)).collect();
+        for _ in 0..steps {
+            let mut force: Vec<Vec2> = (0..n).map(|i| (self.rest[i] - positions[i]) * REST_STIFFNESS).collect();
+            for (i, &length) in self.lengths.iter().enumerate() {
+                let j = (i + 1) % n;
+                let d = positions[j] - positions[i];
+                let pull = d.normalize_or_zero() * (d.length() - length) * NEIGHBOR_STIFFNESS;
+                force[i] += pull;
+                force[j] -= pull;
+            }
+
+            // Semi-implicit Euler, the velocity is updated first so the springs stay stable
+            for i in (0..n).filter(|&i| !pinned[i]) {
+                self.velocity[i] = (self.velocity[i] + force[i] * h) * (1. - DAMPING * h);
+                positions[i] += self.velocity[i] * h;
+            }
+        }
+
+        for (i, p) in curve.control.iter_mut().enumerate() {
+            match pinned[i] {
+                true => self.velocity[i] = Vec2::ZERO,
+                false => p.pos = positions[i]
+            }
+        }
+        curve.modified = true;
+    }
+
+    /// Put the points back where the simulation started
+    pub fn restore(&self, curve: &mut Curve) {
+        if !self.fits(curve) { return };
+        for (p, &rest) in curve.control.iter_mut().zip(&self.rest) {
+            p.pos = rest;
+        }
+        curve.modified = true;
+    }
+}
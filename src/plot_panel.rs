@@ -0,0 +1,278 @@
+use macroquad::prelude::*;
+
+use crate::coords::CoordinateSystem;
+use crate::curve::Curve;
+use crate::math::{arc_length, arc_length_between, curvature, param_at_length, velocity};
+
+// Right of the window below the elevation profile
+const PANEL_TOP: f32 = 310.0;
+const PANEL_RIGHT_MARGIN: f32 = 10.0;
+const PANEL_WIDTH: f32 = 340.0;
+const PANEL_HEIGHT: f32 = 170.0;
+const HEADER_HEIGHT: f32 = 20.0;
+const GRAPH_PADDING: f32 = 10.0;
+const SAMPLES_PER_SEGMENT: usize = 64;
+// Steps of the uniform grid in s mapped back to t
+const LENGTH_STEPS: usize = 12;
+
+/// What the plot panel shows about the active curve
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PlotKind {
+    /// Signed curvature κ(s) over arc length, the usual way to judge a curve's fairness
+    Curvature,
+    /// Parametric speed |B'(t)| of each segment, low where uniform samples in t bunch up
+    Speed,
+    /// Arc length s(t) and its inverse t(s), with a uniform grid in s mapped back to t
+    ArcLength
+}
+
+impl PlotKind {
+    /// The one after this, None after the last
+    pub fn next(self) -> Option<Self> {
+        match self {
+            PlotKind::Curvature => Some(PlotKind::Speed),
+            PlotKind::Speed => Some(PlotKind::ArcLength),
+            PlotKind::ArcLength => None
+        }
+    }
+}
+
+/// Panel on the right of the window plotting a property of the active curve. Recomputed
+/// every frame, so it follows handles while they're dragged
+#[derive(Default)]
+pub struct PlotPanel {
+    kind: Option<PlotKind>
+}
+
+fn panel_rect() -> Rect {
+    Rect::new(screen_width() - PANEL_WIDTH - PANEL_RIGHT_MARGIN, PANEL_TOP, PANEL_WIDTH, PANEL_HEIGHT)
+}
+
+fn graph_rect() -> Rect {
+    let panel = panel_rect();
+    Rect::new(
+        panel.x + GRAPH_PADDING,
+        panel.y + HEADER_HEIGHT + GRAPH_PADDING,
+        panel.w - 2. * GRAPH_PADDING,
+        panel.h - HEADER_HEIGHT - 2. * GRAPH_PADDING
+    )
+}
+
+/// Signed curvature in 1 / units at arc lengths in units along every segment of `curve`,
+/// positive turning counterclockwise in `coords`. Also the arc length where each segment ends
+pub fn curvature_profile(curve: &Curve, coords: &CoordinateSystem) -> (Vec<Vec2>, Vec<f32>) {
+    // Screen space turns the other way round when the coordinates are y up
+    let flip = match coords.y_up {
+        true => -1.,
+        false => 1.
+    };
+    let mut samples = Vec::new();
+    let mut joints = Vec::new();
+    let mut travelled = 0.;
+    for segment in curve.segments() {
+        for i in 0..=SAMPLES_PER_SEGMENT {
+            let t = i as f32 / SAMPLES_PER_SEGMENT as f32;
+            let s = travelled + coords.length(arc_length_between(segment, 0., t));
+            samples.push(vec2(s, flip * curvature(segment, t) * coords.pixels_per_unit));
+        }
+        travelled = samples[samples.len() - 1].x;
+        joints.push(travelled);
+    }
+
+    (samples, joints)
+}
+
+/// Speed in units per unit of t at t from 0 to the number of segments, one t per segment
+pub fn speed_profile(curve: &Curve, coords: &CoordinateSystem) -> Vec<Vec2> {
+    curve.segments()
+        .into_iter()
+        .enumerate()
+        .flat_map(|(s, segment)| (0..=SAMPLES_PER_SEGMENT).map(move |i| {
+            let t = i as f32 / SAMPLES_PER_SEGMENT as f32;
+            vec2(s as f32 + t, coords.length(velocity(segment, t).length()))
+        }))
+        .collect()
+}
+
+fn draw_curvature(curve: &Curve, coords: &CoordinateSystem) {
+    let panel = panel_rect();
+    let (samples, joints) = curvature_profile(curve, coords);
+    let length = joints.last().copied().unwrap_or(0.);
+    if samples.len() < 2 || length <= 0. {
+        draw_text("curvature k(s) - no complete segment", panel.x + GRAPH_PADDING, panel.y + 14., 16., LIGHTGRAY);
+        return;
+    }
+
+    // Near a cusp the curvature shoots off, scaling to the largest value would flatten the
+    // rest of the plot. Most of it fits and the spikes run off the edge instead
+    let mut magnitudes: Vec<f32> = samples.iter().map(|p| p.y.abs()).collect();
+    magnitudes.sort_by(f32::total_cmp);
+    let largest = magnitudes[magnitudes.len() - 1];
+    let scale = (magnitudes[magnitudes.len() * 95 / 100] * 1.25).min(largest * 1.1).max(1e-3);
+
+    let label = format!("curvature k(s) over {:.2} units, |k| up to {:.3}", length, largest);
+    draw_text(&label, panel.x + GRAPH_PADDING, panel.y + 14., 16., LIGHTGRAY);
+
+    let rect = graph_rect();
+    let to_screen = |p: Vec2| vec2(rect.x + p.x / length * rect.w, rect.y + rect.h / 2. - (p.y / scale).clamp(-1., 1.) * rect.h / 2.);
+    draw_line(rect.x, rect.y + rect.h / 2., rect.x + rect.w, rect.y + rect.h / 2., 1., DARKGRAY);
+    for &s in &joints[..joints.len() - 1] {
+        let x = to_screen(vec2(s, 0.)).x;
+        draw_line(x, rect.y, x, rect.y + rect.h, 1., DARKGRAY);
+    }
+    for pair in samples.windows(2) {
+        let (a, b) = (to_screen(pair[0]), to_screen(pair[1]));
+        draw_line(a.x, a.y, b.x, b.y, 2., SKYBLUE);
+    }
+    draw_readout(&samples, rect, to_screen, "s", "k");
+}
+
+fn draw_speed(curve: &Curve, coords: &CoordinateSystem) {
+    let panel = panel_rect();
+    let samples = speed_profile(curve, coords);
+    let segments = curve.segments();
+    if samples.len() < 2 {
+        draw_text("speed |B'(t)| - no complete segment", panel.x + GRAPH_PADDING, panel.y + 14., 16., LIGHTGRAY);
+        return;
+    }
+
+    let (slowest, fastest) = samples.iter().fold((f32::MAX, 0f32), |(min, max), p| (min.min(p.y), max.max(p.y)));
+    let label = match slowest > 0. {
+        true => format!("speed |B'(t)| from {:.2} to {:.2}, {:.1}x apart", slowest, fastest, fastest / slowest),
+        false => format!("speed |B'(t)| up to {:.2}, stops where it touches 0", fastest)
+    };
+    draw_text(&label, panel.x + GRAPH_PADDING, panel.y + 14., 16., LIGHTGRAY);
+
+    let rect = graph_rect();
+    let (t_max, top) = (segments.len() as f32, fastest.max(1e-3) * 1.1);
+    let to_screen = |p: Vec2| vec2(rect.x + p.x / t_max * rect.w, rect.y + rect.h - p.y / top * rect.h);
+    draw_line(rect.x, rect.y + rect.h, rect.x + rect.w, rect.y + rect.h, 1., DARKGRAY);
+    // A constant speed parameterization would run at the segment's length per unit of t
+    for (s, &segment) in segments.iter().enumerate() {
+        let (a, b) = (s as f32, s as f32 + 1.);
+        let average = coords.length(arc_length(segment));
+        let (left, right) = (to_screen(vec2(a, average)), to_screen(vec2(b, average)));
+        draw_line(left.x, rect.y, left.x, rect.y + rect.h, 1., DARKGRAY);
+        draw_line(left.x, left.y, right.x, right.y, 1., GRAY);
+    }
+    for pair in samples.windows(2) {
+        let (a, b) = (to_screen(pair[0]), to_screen(pair[1]));
+        draw_line(a.x, a.y, b.x, b.y, 2., ORANGE);
+    }
+    draw_readout(&samples, rect, to_screen, "t", "speed");
+}
+
+/// Arc length in units from the start at t from 0 to the number of segments
+pub fn arc_length_profile(curve: &Curve, coords: &CoordinateSystem) -> Vec<Vec2> {
+    let mut samples = Vec::new();
+    let mut travelled = 0.;
+    for (s, segment) in curve.segments().into_iter().enumerate() {
+        for i in 0..=SAMPLES_PER_SEGMENT {
+            let t = i as f32 / SAMPLES_PER_SEGMENT as f32;
+            samples.push(vec2(s as f32 + t, travelled + coords.length(arc_length_between(segment, 0., t))));
+        }
+        travelled += coords.length(arc_length(segment));
+    }
+
+    samples
+}
+
+/// Parameter, from 0 to the number of segments, at `count` + 1 evenly spaced arc lengths
+/// from the start to the end of `curve`
+pub fn params_at_even_lengths(curve: &Curve, count: usize) -> Vec<f32> {
+    let segments = curve.segments();
+    let lengths: Vec<f32> = segments.iter().map(|&segment| arc_length(segment)).collect();
+    let total: f32 = lengths.iter().sum();
+    (0..=count)
+        .map(|i| {
+            let mut length = total * i as f32 / count as f32;
+            for (s, &segment_length) in lengths.iter().enumerate() {
+                if length <= segment_length || s + 1 == lengths.len() {
+                    return s as f32 + param_at_length(segments[s], length);
+                }
+                length -= segment_length;
+            }
+            0.
+        })
+        .collect()
+}
+
+fn draw_arc_length(curve: &Curve, coords: &CoordinateSystem) {
+    let panel = panel_rect();
+    let samples = arc_length_profile(curve, coords);
+    let Some(&end) = samples.last().filter(|end| end.y > 0.) else {
+        draw_text("arc length s(t) - no complete segment", panel.x + GRAPH_PADDING, panel.y + 14., 16., LIGHTGRAY);
+        return;
+    };
+    let label = format!("arc length s(t) over {:.2} units, inverse t(s) in green", end.y);
+    draw_text(&label, panel.x + GRAPH_PADDING, panel.y + 14., 16., LIGHTGRAY);
+
+    // Both axes go from 0 to their end, so the inverse is the mirror image across the diagonal
+    // and a constant speed curve would be the diagonal itself
+    let rect = graph_rect();
+    let to_screen = |p: Vec2| vec2(rect.x + p.x / end.x * rect.w, rect.y + rect.h - p.y / end.y * rect.h);
+    let inverse = |p: Vec2| to_screen(vec2(p.y / end.y * end.x, p.x / end.x * end.y));
+    let (origin, corner) = (to_screen(Vec2::ZERO), to_screen(end));
+    draw_line(origin.x, origin.y, corner.x, corner.y, 1., DARKGRAY);
+
+    // Each step of the grid in s runs across to s(t) and down to the t it maps back to,
+    // the ticks along the bottom bunch up where the curve is fast
+    for (i, t) in params_at_even_lengths(curve, LENGTH_STEPS).into_iter().enumerate() {
+        let hit = to_screen(vec2(t, end.y * i as f32 / LENGTH_STEPS as f32));
+        draw_line(rect.x, hit.y, hit.x, hit.y, 1., Color::new(1., 1., 1., 0.25));
+        draw_line(hit.x, hit.y, hit.x, rect.y + rect.h, 1., Color::new(1., 1., 1., 0.25));
+        draw_line(hit.x, rect.y + rect.h - 6., hit.x, rect.y + rect.h, 2., YELLOW);
+    }
+    for pair in samples.windows(2) {
+        let (a, b) = (inverse(pair[0]), inverse(pair[1]));
+        draw_line(a.x, a.y, b.x, b.y, 1., GREEN);
+    }
+    for pair in samples.windows(2) {
+        let (a, b) = (to_screen(pair[0]), to_screen(pair[1]));
+        draw_line(a.x, a.y, b.x, b.y, 2., SKYBLUE);
+    }
+    draw_readout(&samples, rect, to_screen, "t", "s");
+}
+
+// Vertical cursor and the value of the sample under the mouse
+fn draw_readout(samples: &[Vec2], rect: Rect, to_screen: impl Fn(Vec2) -> Vec2, x_name: &str, y_name: &str) {
+    let (mx, my) = mouse_position();
+    if !rect.contains(vec2(mx, my)) { return };
+    let nearest = samples.iter().min_by(|a, b| (to_screen(**a).x - mx).abs().total_cmp(&(to_screen(**b).x - mx).abs()));
+    if let Some(nearest) = nearest {
+        let pos = to_screen(*nearest);
+        draw_line(pos.x, rect.y, pos.x, rect.y + rect.h, 1., GRAY);
+        draw_circle(pos.x, pos.y, 3., WHITE);
+        draw_text(&format!("{} {:.2}  {} {:.3}", x_name, nearest.x, y_name, nearest.y), rect.x + 4., rect.y + 12., 16., WHITE);
+    }
+}
+
+impl PlotPanel {
+    pub fn kind(&self) -> Option<PlotKind> {
+        self.kind
+    }
+
+    /// Show the next kind of plot, closing after the last one
+    pub fn cycle(&mut self) {
+        self.kind = match self.kind {
+            Some(kind) => kind.next(),
+            None => Some(PlotKind::Curvature)
+        };
+    }
+
+    /// Whether the mouse is over the open panel, clicks there shouldn't reach the canvas
+    pub fn hovered(&self) -> bool {
+        self.kind.is_some() && panel_rect().contains(mouse_position().into())
+    }
+
+    pub fn draw(&self, curve: &Curve, coords: &CoordinateSystem) {
+        let Some(kind) = self.kind else { return };
+        let panel = panel_rect();
+        draw_rectangle(panel.x, panel.y, panel.w, panel.h, Color::new(0., 0., 0., 0.8));
+        match kind {
+            PlotKind::Curvature => draw_curvature(curve, coords),
+            PlotKind::Speed => draw_speed(curve, coords),
+            PlotKind::ArcLength => draw_arc_length(curve, coords)
+        }
+    }
+}
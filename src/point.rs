@@ -0,0 +1,66 @@
+use std::ops::{Add, Mul, Sub};
+
+use macroquad::prelude::*;
+
+pub const CONTROLPOINT_RADIUS: f32 = 10.0;
+#[derive(Clone, Copy, Debug)]
+pub struct Point {
+    pub pos: Vec2,
+    pub color: Color,
+    /// Locked control points can't be dragged or deleted
+    pub locked: bool
+}
+
+impl Point {
+    pub fn new(pos: Vec2, color: Color) -> Self { Self { pos, color, locked: false } }
+
+    pub fn draw_control(&self) {
+        draw_circle(self.pos.x, self.pos.y, CONTROLPOINT_RADIUS, self.color);
+        if self.locked {
+            draw_circle_lines(self.pos.x, self.pos.y, CONTROLPOINT_RADIUS + 3., 2., WHITE);
+        }
+    }
+
+    pub fn draw(&self) {
+        draw_rectangle(self.pos.x, self.pos.y, 1.0, 1.0, self.color);
+    }
+
+    // Position and color from their sums, the result is never locked
+    fn combine(pos: Vec2, color: Vec4) -> Self {
+        Self { pos, color: Color::from_vec(color), locked: false }
+    }
+
+    pub fn lerp(&self, other: &Self, t: f32) -> Point {
+        let pos = self.pos.lerp(other.pos, t);
+        let color = Color::from_vec(self.color.to_vec().lerp(other.color.to_vec(), t));
+
+        Self { pos , color, locked: false }
+    }
+}
+
+// Position and color add and scale together, so the core crate's evaluators blend the
+// colors along the curve the way they place the positions
+
+impl Add for Point {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Point::combine(self.pos + other.pos, self.color.to_vec() + other.color.to_vec())
+    }
+}
+
+impl Sub for Point {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Point::combine(self.pos - other.pos, self.color.to_vec() - other.color.to_vec())
+    }
+}
+
+impl Mul<f32> for Point {
+    type Output = Self;
+
+    fn mul(self, k: f32) -> Self {
+        Point::combine(self.pos * k, self.color.to_vec() * k)
+    }
+}
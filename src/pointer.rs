@@ -0,0 +1,51 @@
+use macroquad::prelude::*;
+
+const WHEEL_ZOOM: f32 = 1.1;
+
+/// Where the user points on screen and what they pressed this frame, read from the mouse
+/// or built by another input device standing in for it
+#[derive(Clone, Debug)]
+pub struct Pointer {
+    pub position: Vec2,
+    /// Left button, adds and drags points
+    pub primary_pressed: bool,
+    pub primary_down: bool,
+    /// Right button, deletes points
+    pub secondary_pressed: bool,
+    /// Screen pixels to pan the view by
+    pub pan: Vec2,
+    /// Factor to zoom the view by around `position`, 1 leaves it alone
+    pub zoom: f32,
+    /// Keymap actions triggered by the device's own buttons
+    pub actions: Vec<&'static str>
+}
+
+impl Pointer {
+    /// The mouse this frame, `last_position` is where it was on the previous one
+    pub fn from_mouse(last_position: Vec2) -> Self {
+        let position: Vec2 = mouse_position().into();
+        let (_, wheel) = mouse_wheel();
+        let zoom = match wheel {
+            w if w > 0. => WHEEL_ZOOM,
+            w if w < 0. => 1. / WHEEL_ZOOM,
+            _ => 1.
+        };
+
+        Self {
+            position,
+            primary_pressed: is_mouse_button_pressed(MouseButton::Left),
+            primary_down: is_mouse_button_down(MouseButton::Left),
+            secondary_pressed: is_mouse_button_pressed(MouseButton::Right),
+            pan: match is_mouse_button_down(MouseButton::Middle) {
+                true => position - last_position,
+                false => Vec2::ZERO
+            },
+            zoom,
+            actions: Vec::new()
+        }
+    }
+
+    pub fn triggered(&self, action: &str) -> bool {
+        self.actions.contains(&action)
+    }
+}
@@ -0,0 +1,69 @@
+use macroquad::prelude::*;
+
+use crate::curve::Curve;
+use crate::math::decasteljau_n;
+use crate::point::{Point, CONTROLPOINT_RADIUS};
+use crate::settings::RenderSettings;
+use crate::stroke::{draw_polyline, LineJoin};
+
+const PREVIEW_SAMPLES: usize = 64;
+const PREVIEW_ALPHA: f32 = 0.5;
+
+/// Control points after the last complete segment, starting at its end anchor. Empty for
+/// B-splines, which draw every point they have
+pub fn trailing_points(curve: &Curve) -> &[Point] {
+    if curve.knots.is_some() || curve.control.is_empty() { return &[] };
+    let last_anchor = (curve.control.len() - 1) / 3 * 3;
+    &curve.control[last_anchor..]
+}
+
+/// Faded line, quadratic or cubic through the points of an unfinished segment and the
+/// cursor, showing what the next click will make
+pub fn draw_partial_segment(curve: &Curve, cursor: Vec2, settings: &RenderSettings) {
+    let trailing = trailing_points(curve);
+    if trailing.is_empty() || curve.points_needed() == 0 { return };
+
+    let mut points = trailing.to_vec();
+    points.push(Point::new(cursor, WHITE));
+    draw_preview(&points, settings);
+}
+
+/// Segment the pen would add from the curve's last anchor to the cursor. The first handle
+/// keeps going in the direction the curve arrives at the anchor and the second one eases
+/// into the cursor, both a third of the way long. None while a segment is unfinished
+pub fn next_segment(curve: &Curve, cursor: Vec2) -> Option<[Point; 4]> {
+    let trailing = trailing_points(curve);
+    if trailing.len() != 1 { return None };
+
+    let anchor = trailing[0];
+    let reach = anchor.pos.distance(cursor) / 3.;
+    let incoming = match curve.control.len() {
+        1 => cursor - anchor.pos,
+        n => anchor.pos - curve.control[n - 2].pos
+    };
+    let first = anchor.pos + incoming.normalize_or_zero() * reach;
+    let second = cursor + (first - cursor).normalize_or_zero() * reach;
+
+    Some([anchor, Point::new(first, WHITE), Point::new(second, WHITE), Point::new(cursor, WHITE)])
+}
+
+/// Faded ghost of `next_segment` with its handles
+pub fn draw_next_segment(curve: &Curve, cursor: Vec2, settings: &RenderSettings) {
+    let Some(segment) = next_segment(curve, cursor) else { return };
+
+    draw_preview(&segment, settings);
+    let handle = Color { a: PREVIEW_ALPHA, ..GRAY };
+    for (a, b) in [(segment[0], segment[1]), (segment[3], segment[2])] {
+        draw_polyline(&[Point::new(a.pos, handle), Point::new(b.pos, handle)], settings.world_line_width(), LineJoin::Miter);
+        draw_circle(b.pos.x, b.pos.y, CONTROLPOINT_RADIUS / 2., handle);
+    }
+}
+
+// Sample the bezier of any degree defined by `points` and draw it see-through
+fn draw_preview(points: &[Point], settings: &RenderSettings) {
+    let samples: Vec<Point> = (0..=PREVIEW_SAMPLES)
+        .map(|i| decasteljau_n(points, i as f32 / PREVIEW_SAMPLES as f32))
+        .map(|p| Point::new(p.pos, Color { a: PREVIEW_ALPHA, ..p.color }))
+        .collect();
+    draw_polyline(&samples, settings.world_line_width(), LineJoin::Round);
+}
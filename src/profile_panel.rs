@@ -0,0 +1,189 @@
+use macroquad::prelude::*;
+
+use crate::coords::CoordinateSystem;
+use crate::curve::Curve;
+use crate::math::cubic_bezier;
+use crate::point::Point;
+
+const PANEL_LEFT: f32 = 10.0;
+// Below the history timeline at its tallest
+const PANEL_TOP: f32 = 170.0;
+const PANEL_WIDTH: f32 = 320.0;
+const GRAPH_HEIGHT: f32 = 110.0;
+const HEADER_HEIGHT: f32 = 20.0;
+const GRAPH_PADDING: f32 = 8.0;
+const DOT_RADIUS: f32 = 5.0;
+const SAMPLES_PER_SEGMENT: usize = 24;
+const AXIS_NAMES: [&str; 2] = ["x(t)", "y(t)"];
+
+/// Panel on the left of the window plotting the active curve's x(t) and y(t) as separate
+/// graphs, t going from 0 to the number of segments. On bezier curves every control point
+/// is a dot at t = i / 3, dragging one up or down edits that one coordinate alone
+#[derive(Default)]
+pub struct ProfilePanel {
+    open: bool,
+    // Control point and axis (0 for x, 1 for y) being dragged, and the graph's value range
+    // when the drag started so it doesn't rescale under the mouse
+    dragging: Option<(usize, usize, (f32, f32))>
+}
+
+fn panel_rect() -> Rect {
+    Rect::new(PANEL_LEFT, PANEL_TOP, PANEL_WIDTH, 2. * (HEADER_HEIGHT + GRAPH_HEIGHT))
+}
+
+fn graph_rect(axis: usize) -> Rect {
+    let y = PANEL_TOP + axis as f32 * (HEADER_HEIGHT + GRAPH_HEIGHT) + HEADER_HEIGHT;
+    Rect::new(PANEL_LEFT, y, PANEL_WIDTH, GRAPH_HEIGHT)
+}
+
+// Smallest and largest value of a coordinate over the control points, which bound the curve,
+// padded so the extremes aren't on the edges
+fn value_range(segments: &[[Point; 4]], coords: &CoordinateSystem, axis: usize) -> (f32, f32) {
+    let (min, max) = segments.iter()
+        .flatten()
+        .map(|point| coords.to_math(point.pos)[axis])
+        .fold((f32::MAX, f32::MIN), |(min, max), v| (min.min(v), max.max(v)));
+    let pad = match max - min > 1e-3 {
+        true => (max - min) * 0.1,
+        false => 1.
+    };
+    (min - pad, max + pad)
+}
+
+fn to_screen(t: f32, value: f32, rect: Rect, t_max: f32, (min, max): (f32, f32)) -> Vec2 {
+    let (width, height) = (rect.w - 2. * GRAPH_PADDING, rect.h - 2. * GRAPH_PADDING);
+    vec2(rect.x + GRAPH_PADDING + t / t_max * width, rect.y + rect.h - GRAPH_PADDING - (value - min) / (max - min) * height)
+}
+
+fn value_at(y: f32, rect: Rect, (min, max): (f32, f32)) -> f32 {
+    let height = rect.h - 2. * GRAPH_PADDING;
+    min + (rect.y + rect.h - GRAPH_PADDING - y) / height * (max - min)
+}
+
+// Dots of the control points that shape complete segments, with their parameter
+fn dots(curve: &Curve, t_max: f32) -> impl Iterator<Item = (usize, f32)> + '_ {
+    curve.control.iter()
+        .enumerate()
+        .map(|(i, _)| (i, i as f32 / 3.))
+        .filter(move |&(_, t)| curve.knots.is_none() && t <= t_max)
+}
+
+impl ProfilePanel {
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    pub fn toggle(&mut self) {
+        self.open = !self.open;
+        self.dragging = None;
+    }
+
+    /// Whether the mouse is over the open panel, clicks there shouldn't reach the canvas
+    pub fn hovered(&self) -> bool {
+        self.open && panel_rect().contains(mouse_position().into())
+    }
+
+    /// Handle dragging the dots, returns true while the panel is using the mouse
+    pub fn update(&mut self, curve: &mut Curve, coords: &CoordinateSystem) -> bool {
+        if !self.open { return false };
+        if !is_mouse_button_down(MouseButton::Left) {
+            self.dragging = None;
+        }
+
+        let mouse: Vec2 = mouse_position().into();
+        let segments = curve.segments();
+        let t_max = segments.len() as f32;
+        if is_mouse_button_pressed(MouseButton::Left) && !segments.is_empty() {
+            self.dragging = (0..2).find_map(|axis| {
+                let (rect, range) = (graph_rect(axis), value_range(&segments, coords, axis));
+                dots(curve, t_max)
+                    .find(|&(i, t)| {
+                        let value = coords.to_math(curve.control[i].pos)[axis];
+                        to_screen(t, value, rect, t_max, range).distance(mouse) <= DOT_RADIUS
+                    })
+                    .map(|(i, _)| (i, axis, range))
+            });
+        }
+
+        if let Some((id, axis, range)) = self.dragging {
+            match id < curve.control.len() {
+                true => {
+                    let mut math = coords.to_math(curve.control[id].pos);
+                    math[axis] = value_at(mouse.y, graph_rect(axis), range);
+                    curve.move_point(id, coords.from_math(math), false);
+                },
+                false => self.dragging = None
+            }
+        }
+
+        self.dragging.is_some() || self.hovered()
+    }
+
+    /// Both graphs, the control point picked on the canvas highlighted on them
+    pub fn draw(&self, curve: &Curve, coords: &CoordinateSystem, selected: Option<usize>) {
+        if !self.open { return };
+        let panel = panel_rect();
+        draw_rectangle(panel.x, panel.y, panel.w, panel.h, Color::new(0., 0., 0., 0.8));
+
+        let segments = curve.segments();
+        let t_max = segments.len() as f32;
+        for (axis, name) in AXIS_NAMES.iter().enumerate() {
+            let rect = graph_rect(axis);
+            let range = match self.dragging {
+                Some((_, dragged, range)) if dragged == axis => range,
+                _ => value_range(&segments, coords, axis)
+            };
+            let label = match segments.is_empty() {
+                true => format!("{} - no complete segment", name),
+                false => format!("{} from {:.2} to {:.2}, t from 0 to {}", name, range.0, range.1, segments.len())
+            };
+            draw_text(&label, rect.x + GRAPH_PADDING, rect.y - 6., 16., LIGHTGRAY);
+            if segments.is_empty() { continue };
+
+            // Segment boundaries, and zero when it's in view
+            for i in 0..=segments.len() {
+                let x = to_screen(i as f32, 0., rect, t_max, range).x;
+                draw_line(x, rect.y, x, rect.y + rect.h, 1., DARKGRAY);
+            }
+            if range.0 < 0. && range.1 > 0. {
+                let zero = to_screen(0., 0., rect, t_max, range);
+                draw_line(zero.x, zero.y, rect.x + rect.w - GRAPH_PADDING, zero.y, 1., DARKGRAY);
+            }
+
+            // Each coordinate of a bezier segment is a 1D bezier of the same coordinate of
+            // its control points, at t = 0, 1/3, 2/3 and 1, so the dots form its control polygon
+            let dot_positions: Vec<(usize, Vec2)> = dots(curve, t_max)
+                .map(|(i, t)| (i, to_screen(t, coords.to_math(curve.control[i].pos)[axis], rect, t_max, range)))
+                .collect();
+            for pair in dot_positions.windows(2) {
+                let (a, b) = (pair[0].1, pair[1].1);
+                draw_line(a.x, a.y, b.x, b.y, 1., GRAY);
+            }
+
+            let samples: Vec<Vec2> = segments.iter()
+                .enumerate()
+                .flat_map(|(s, segment)| (0..=SAMPLES_PER_SEGMENT).map(move |k| (s, k, segment)))
+                .map(|(s, k, segment)| {
+                    let t = k as f32 / SAMPLES_PER_SEGMENT as f32;
+                    let value = coords.to_math(cubic_bezier(t, segment))[axis];
+                    to_screen(s as f32 + t, value, rect, t_max, range)
+                })
+                .collect();
+            for pair in samples.windows(2) {
+                draw_line(pair[0].x, pair[0].y, pair[1].x, pair[1].y, 2., WHITE);
+            }
+
+            for (i, pos) in dot_positions {
+                let highlighted = selected == Some(i) || self.dragging.is_some_and(|(id, _, _)| id == i);
+                let radius = match i % 3 {
+                    0 => DOT_RADIUS,
+                    _ => DOT_RADIUS * 0.7
+                };
+                draw_circle(pos.x, pos.y, radius, curve.control[i].color);
+                if highlighted {
+                    draw_circle_lines(pos.x, pos.y, radius + 3., 2., YELLOW);
+                }
+            }
+        }
+    }
+}
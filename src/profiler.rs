@@ -0,0 +1,88 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use macroquad::prelude::*;
+
+use crate::hud::draw_text_box;
+
+// Weight of the newest frame in the smoothed timings
+const SMOOTHING: f64 = 0.1;
+
+// Shapes handed to macroquad by the curve renderer this frame, before it batches them
+static DRAW_CALLS: AtomicUsize = AtomicUsize::new(0);
+
+/// Record `count` draws issued to macroquad, shown by the profiling overlay
+pub fn count_draw_calls(count: usize) {
+    DRAW_CALLS.fetch_add(count, Ordering::Relaxed);
+}
+
+/// The parts of a frame that get timed separately
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Phase {
+    HitTesting,
+    Sampling,
+    Drawing
+}
+
+impl Phase {
+    const ALL: [Phase; 3] = [Phase::HitTesting, Phase::Sampling, Phase::Drawing];
+
+    fn label(self) -> &'static str {
+        match self {
+            Phase::HitTesting => "hit testing",
+            Phase::Sampling => "sampling",
+            Phase::Drawing => "drawing"
+        }
+    }
+}
+
+/// Frame timings smoothed over the last frames, plus counters of the current one
+#[derive(Default)]
+pub struct Profiler {
+    pub enabled: bool,
+    /// Seconds spent in each phase, indexed like `Phase::ALL`
+    timings: [f64; 3],
+    started: Option<(Phase, f64)>,
+    /// Curve samples drawn this frame
+    pub samples: usize,
+    draw_calls: usize
+}
+
+impl Profiler {
+    /// Start timing `phase`, finishing whichever one was running
+    pub fn begin(&mut self, phase: Phase) {
+        self.end();
+        self.started = Some((phase, get_time()));
+    }
+
+    /// Stop timing the running phase, if any
+    pub fn end(&mut self) {
+        if let Some((phase, start)) = self.started.take() {
+            let index = Phase::ALL.iter().position(|&p| p == phase).unwrap();
+            let elapsed = get_time() - start;
+            self.timings[index] += (elapsed - self.timings[index]) * SMOOTHING;
+        }
+    }
+
+    /// Close the frame: keep its draw call count and reset the counters for the next one
+    pub fn finish_frame(&mut self) {
+        self.end();
+        self.draw_calls = DRAW_CALLS.swap(0, Ordering::Relaxed);
+    }
+
+    /// Overlay on the top right, below the sampling status
+    pub fn draw(&self) {
+        if !self.enabled { return };
+
+        let mut lines = vec![
+            format!("fps: {}  frame: {:.2} ms", get_fps(), get_frame_time() * 1000.),
+        ];
+        for (phase, seconds) in Phase::ALL.iter().zip(self.timings) {
+            lines.push(format!("{}: {:.3} ms", phase.label(), seconds * 1000.));
+        }
+        lines.push(format!("samples: {}", self.samples));
+        lines.push(format!("draw calls: {}", self.draw_calls));
+
+        let width = lines.iter().map(|l| measure_text(l, None, 20, 1.).width).fold(0., f32::max);
+        draw_text_box(&lines, vec2(screen_width() - width - 30., 30.));
+    }
+}
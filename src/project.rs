@@ -0,0 +1,156 @@
+use std::fmt;
+
+use macroquad::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::continuity::Continuity;
+use crate::curve::Curve;
+use crate::document::{Document, Layer};
+use crate::elevation::Elevation;
+use crate::point::Point;
+
+/// Suggested name for saved projects
+pub const PROJECT_FILE: &str = "curves.json";
+// Bumped when the format changes in a way older files can't be read with
+const PROJECT_VERSION: u32 = 1;
+
+// The document as written, only what can't be recomputed from the control points
+#[derive(Serialize, Deserialize)]
+struct ProjectFile {
+    version: u32,
+    #[serde(default)]
+    active: usize,
+    curves: Vec<CurveFile>,
+    // Older projects have no layers, every curve opens on a single default one
+    #[serde(default)]
+    layers: Vec<LayerFile>,
+    #[serde(default)]
+    active_layer: usize
+}
+
+#[derive(Serialize, Deserialize)]
+struct LayerFile {
+    name: String,
+    #[serde(default = "visible_by_default")]
+    visible: bool,
+    #[serde(default)]
+    locked: bool
+}
+
+fn visible_by_default() -> bool {
+    true
+}
+
+#[derive(Serialize, Deserialize)]
+struct CurveFile {
+    points: Vec<PointFile>,
+    #[serde(default)]
+    closed: bool,
+    #[serde(default)]
+    knots: Option<Vec<f32>>,
+    #[serde(default)]
+    continuity: Vec<Continuity>,
+    #[serde(default)]
+    layer: usize,
+    #[serde(default)]
+    group: Option<usize>,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    text: String,
+    #[serde(default)]
+    text_offset: f32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    elevation: Option<Elevation>
+}
+
+#[derive(Serialize, Deserialize)]
+struct PointFile {
+    x: f32,
+    y: f32,
+    // rgba in [0, 1]
+    color: [f32; 4],
+    #[serde(default)]
+    locked: bool
+}
+
+#[derive(Debug)]
+pub enum ProjectError {
+    Parse(serde_json::Error),
+    UnsupportedVersion(u32)
+}
+
+impl fmt::Display for ProjectError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ProjectError::Parse(error) => write!(f, "invalid project: {}", error),
+            ProjectError::UnsupportedVersion(version) => write!(f, "project version {} is newer than this editor", version)
+        }
+    }
+}
+
+impl std::error::Error for ProjectError {}
+
+/// Every curve of `doc` as pretty printed JSON, in world coordinates
+pub fn to_json(doc: &Document) -> String {
+    let file = ProjectFile {
+        version: PROJECT_VERSION,
+        active: doc.active,
+        curves: doc.curves.iter().map(|curve| CurveFile {
+            points: curve.control.iter().map(|p| PointFile {
+                x: p.pos.x,
+                y: p.pos.y,
+                color: [p.color.r, p.color.g, p.color.b, p.color.a],
+                locked: p.locked
+            }).collect(),
+            closed: curve.closed,
+            knots: curve.knots.clone(),
+            continuity: curve.continuity.clone(),
+            layer: curve.layer,
+            group: curve.group,
+            text: curve.text.clone(),
+            text_offset: curve.text_offset,
+            elevation: curve.elevation.clone()
+        }).collect(),
+        layers: doc.layers.iter().map(|layer| LayerFile {
+            name: layer.name.clone(),
+            visible: layer.visible,
+            locked: layer.locked
+        }).collect(),
+        active_layer: doc.active_layer
+    };
+    // Only strings and numbers in there, writing it can't fail
+    serde_json::to_string_pretty(&file).expect("project should serialize")
+}
+
+/// Document saved by `to_json`, a project without curves opens as an empty document
+pub fn from_json(text: &str) -> Result<Document, ProjectError> {
+    let file: ProjectFile = serde_json::from_str(text).map_err(ProjectError::Parse)?;
+    if file.version > PROJECT_VERSION {
+        return Err(ProjectError::UnsupportedVersion(file.version));
+    }
+    let mut layers: Vec<Layer> = file.layers.into_iter()
+        .map(|layer| Layer { name: layer.name, visible: layer.visible, locked: layer.locked })
+        .collect();
+    if layers.is_empty() { layers = Document::default().layers };
+    let active_layer = file.active_layer.min(layers.len() - 1);
+    if file.curves.is_empty() { return Ok(Document { layers, active_layer, ..Document::default() }) };
+
+    let curves: Vec<Curve> = file.curves.into_iter().map(|curve| Curve {
+        control: curve.points.into_iter().map(|p| {
+            let [r, g, b, a] = p.color;
+            Point { locked: p.locked, ..Point::new(vec2(p.x, p.y), Color::new(r, g, b, a)) }
+        }).collect(),
+        closed: curve.closed,
+        knots: curve.knots,
+        continuity: curve.continuity,
+        layer: curve.layer.min(layers.len() - 1),
+        group: curve.group,
+        text: curve.text,
+        text_offset: curve.text_offset,
+        elevation: curve.elevation.filter(Elevation::is_valid),
+        modified: true,
+        ..Default::default()
+    }).collect();
+    let active = file.active.min(curves.len() - 1);
+
+    Ok(Document { curves, active, layers, active_layer, symmetry: None })
+}
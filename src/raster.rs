@@ -0,0 +1,121 @@
+use macroquad::prelude::*;
+
+use crate::curve::Curve;
+use crate::math::flatten;
+
+/// Grid of square cells over the world, each with how much of it a curve covers in [0, 1].
+/// Cell (x, y) spans from origin + (x, y) * cell_size to one cell size past that
+pub struct OccupancyGrid {
+    pub width: usize,
+    pub height: usize,
+    pub origin: Vec2,
+    pub cell_size: f32,
+    /// Row major coverage
+    pub cells: Vec<f32>
+}
+
+impl OccupancyGrid {
+    pub fn new(width: usize, height: usize, origin: Vec2, cell_size: f32) -> Self {
+        Self { width, height, origin, cell_size, cells: vec![0.; width * height] }
+    }
+
+    /// Coverage of a cell, zero outside the grid
+    pub fn get(&self, x: usize, y: usize) -> f32 {
+        match x < self.width && y < self.height {
+            true => self.cells[y * self.width + x],
+            false => 0.
+        }
+    }
+
+    pub fn is_occupied(&self, x: usize, y: usize) -> bool {
+        self.get(x, y) > 0.
+    }
+
+    /// Position in cells, with cell (x, y) covering [x, x + 1) x [y, y + 1)
+    pub fn to_cells(&self, world: Vec2) -> Vec2 {
+        (world - self.origin) / self.cell_size
+    }
+
+    // Cells only ever get more covered, so overlapping lines keep the strongest coverage
+    fn plot(&mut self, x: i64, y: i64, coverage: f32) {
+        if x < 0 || y < 0 || x as usize >= self.width || y as usize >= self.height { return };
+        let cell = &mut self.cells[y as usize * self.width + x as usize];
+        *cell = cell.max(coverage.clamp(0., 1.));
+    }
+
+    // The curve flattened within `tolerance` world units, as polylines in cell coordinates
+    fn polylines(&self, curve: &Curve, tolerance: f32) -> Vec<Vec<Vec2>> {
+        curve.segments().iter()
+            .map(|segment| flatten(segment, tolerance).iter().map(|p| self.to_cells(p.pos)).collect())
+            .collect()
+    }
+
+    /// Fully occupy every cell the flattened curve passes through, with Bresenham's lines
+    /// between the samples
+    pub fn stamp(&mut self, curve: &Curve, tolerance: f32) {
+        for polyline in self.polylines(curve, tolerance) {
+            for pair in polyline.windows(2) {
+                self.bresenham(pair[0], pair[1]);
+            }
+        }
+    }
+
+    /// Stamp the curve anti-aliased with Wu's lines, splitting each column's (or row's)
+    /// coverage between the two cells nearest the line
+    pub fn stamp_antialiased(&mut self, curve: &Curve, tolerance: f32) {
+        for polyline in self.polylines(curve, tolerance) {
+            for pair in polyline.windows(2) {
+                self.wu(pair[0], pair[1]);
+            }
+        }
+    }
+
+    fn bresenham(&mut self, a: Vec2, b: Vec2) {
+        let (mut x, mut y) = (a.x.floor() as i64, a.y.floor() as i64);
+        let (x1, y1) = (b.x.floor() as i64, b.y.floor() as i64);
+        let (dx, dy) = ((x1 - x).abs(), -(y1 - y).abs());
+        let (sx, sy) = ((x1 - x).signum(), (y1 - y).signum());
+        let mut error = dx + dy;
+
+        loop {
+            self.plot(x, y, 1.);
+            if x == x1 && y == y1 { break };
+            let doubled = 2 * error;
+            if doubled >= dy {
+                error += dy;
+                x += sx;
+            }
+            if doubled <= dx {
+                error += dx;
+                y += sy;
+            }
+        }
+    }
+
+    fn wu(&mut self, a: Vec2, b: Vec2) {
+        // Centered on the cells, and walking along the longer axis
+        let (a, b) = (a - 0.5, b - 0.5);
+        let steep = (b.y - a.y).abs() > (b.x - a.x).abs();
+        let swap = |p: Vec2| match steep {
+            true => vec2(p.y, p.x),
+            false => p
+        };
+        let (mut a, mut b) = (swap(a), swap(b));
+        if a.x > b.x { std::mem::swap(&mut a, &mut b) };
+
+        let gradient = match b.x - a.x {
+            dx if dx.abs() < f32::EPSILON => 0.,
+            dx => (b.y - a.y) / dx
+        };
+        for x in a.x.round() as i64..=b.x.round() as i64 {
+            let y = a.y + gradient * (x as f32 - a.x);
+            let (row, fract) = (y.floor() as i64, y - y.floor());
+            for (row, coverage) in [(row, 1. - fract), (row + 1, fract)] {
+                match steep {
+                    true => self.plot(row, x, coverage),
+                    false => self.plot(x, row, coverage)
+                }
+            }
+        }
+    }
+}
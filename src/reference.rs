@@ -0,0 +1,99 @@
+use std::fmt;
+
+use image::RgbaImage;
+use macroquad::prelude::*;
+
+// Opacity and scale never go past these, so the image can't vanish or swallow the canvas
+const MIN_OPACITY: f32 = 0.05;
+const MIN_SCALE: f32 = 1e-4;
+const MAX_SCALE: f32 = 1e4;
+
+/// A PNG or JPEG drawn under the curves to trace over. It lives in world space, so it pans
+/// and zooms with the curves, and isn't saved with the project
+pub struct ReferenceImage {
+    texture: Texture2D,
+    // Kept on the CPU side for tracing, the texture can't be read back everywhere
+    pixels: RgbaImage,
+    /// Size of the image in pixels
+    pub size: Vec2,
+    /// World position of the image's top left corner
+    pub position: Vec2,
+    /// World units per image pixel
+    pub scale: f32,
+    pub opacity: f32
+}
+
+impl ReferenceImage {
+    /// Decode `bytes` as a PNG or JPEG centered on `center`, `height` world units tall
+    pub fn decode(bytes: &[u8], center: Vec2, height: f32) -> Result<Self, ReferenceError> {
+        let image = image::load_from_memory(bytes).map_err(ReferenceError::Decode)?.to_rgba8();
+        let (Ok(width), Ok(rows)) = (u16::try_from(image.width()), u16::try_from(image.height())) else {
+            return Err(ReferenceError::TooLarge(image.width(), image.height()));
+        };
+        let texture = Texture2D::from_rgba8(width, rows, image.as_raw());
+        texture.set_filter(FilterMode::Linear);
+
+        let size = vec2(width as f32, rows as f32);
+        let scale = (height / size.y.max(1.)).clamp(MIN_SCALE, MAX_SCALE);
+        Ok(Self { texture, pixels: image, size, position: center - size * scale / 2., scale, opacity: 0.5 })
+    }
+
+    pub fn pixels(&self) -> &RgbaImage {
+        &self.pixels
+    }
+
+    /// World position of a point given in image pixels
+    pub fn to_world(&self, pixel: Vec2) -> Vec2 {
+        self.position + pixel * self.scale
+    }
+
+    /// World size of the image
+    pub fn world_size(&self) -> Vec2 {
+        self.size * self.scale
+    }
+
+    pub fn contains(&self, point: Vec2) -> bool {
+        Rect::new(self.position.x, self.position.y, self.world_size().x, self.world_size().y).contains(point)
+    }
+
+    /// Scale by `factor` keeping the point under `anchor` in place
+    pub fn scale_about(&mut self, anchor: Vec2, factor: f32) {
+        let scale = (self.scale * factor).clamp(MIN_SCALE, MAX_SCALE);
+        self.position = anchor + (self.position - anchor) * (scale / self.scale);
+        self.scale = scale;
+    }
+
+    pub fn change_opacity(&mut self, by: f32) {
+        self.opacity = (self.opacity + by).clamp(MIN_OPACITY, 1.);
+    }
+
+    /// Draw with the world camera set
+    pub fn draw(&self) {
+        let params = DrawTextureParams { dest_size: Some(self.world_size()), ..Default::default() };
+        draw_texture_ex(self.texture, self.position.x, self.position.y, Color::new(1., 1., 1., self.opacity), params);
+    }
+}
+
+impl Drop for ReferenceImage {
+    // macroquad textures are handles, the GPU side has to be freed by hand
+    fn drop(&mut self) {
+        self.texture.delete();
+    }
+}
+
+#[derive(Debug)]
+pub enum ReferenceError {
+    Decode(image::ImageError),
+    TooLarge(u32, u32)
+}
+
+impl fmt::Display for ReferenceError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ReferenceError::Decode(error) => write!(f, "couldn't read the image: {}", error),
+            ReferenceError::TooLarge(width, height) => write!(f, "the image is too large ({}x{} pixels)", width, height)
+        }
+    }
+}
+
+impl std::error::Error for ReferenceError {}
@@ -0,0 +1,96 @@
+use macroquad::prelude::*;
+
+use crate::bvh::Bvh;
+use crate::curve::Curve;
+use crate::math::{arc_length, arc_length_between};
+
+/// Particles in a new rope
+pub const ROPE_PARTICLES: usize = 24;
+// World units per second squared, straight down the screen
+const GRAVITY: Vec2 = Vec2::new(0., 900.);
+// Fraction of the velocity lost per second
+const DAMPING: f32 = 0.6;
+// Longest step integrated at once
+const MAX_STEP: f32 = 1. / 120.;
+// Frames longer than this (a dragged window, a hidden tab) don't fling the rope
+const MAX_FRAME: f32 = 1. / 20.;
+// Rounds of keeping the links' length and projecting back onto the curve per step
+const CONSTRAINT_ITERATIONS: usize = 4;
+
+/// A verlet rope whose particles slide along a curve. Each step they fall, the links
+/// between them are pulled back to their length, and every particle is projected onto
+/// the closest point of the curve, so it bunches up in the curve's valleys
+pub struct Rope {
+    /// Index of the curve the rope is on in the document
+    pub curve: usize,
+    pub particles: Vec<Vec2>,
+    previous: Vec<Vec2>,
+    // Distance kept between neighbouring particles
+    link: f32
+}
+
+impl Rope {
+    /// `count` particles along `curve` from its point closest to `start`, spread over
+    /// `length` world units of it or the whole curve when it's shorter. None when the
+    /// curve has no complete segment
+    pub fn new(index: usize, curve: &Curve, start: Vec2, count: usize, length: f32) -> Option<Self> {
+        let hit = Bvh::new(std::slice::from_ref(curve)).closest(start)?;
+        let segments = curve.segments();
+        let total: f32 = segments.iter().map(|&s| arc_length(s)).sum();
+        let before: f32 = segments[..hit.segment.segment].iter().map(|&s| arc_length(s)).sum();
+        let along = before + arc_length_between(segments[hit.segment.segment], 0., hit.t);
+
+        // Started near the end it runs back so all of it fits
+        let length = length.min(total);
+        let from = along.min(total - length).max(0.);
+        let count = count.max(2);
+        let link = length / (count - 1) as f32;
+        let particles: Vec<Vec2> = (0..count)
+            .map(|i| curve.point_at_length((from + i as f32 * link).min(total)).map_or(hit.pos, |(pos, _)| pos))
+            .collect();
+
+        Some(Self { curve: index, previous: particles.clone(), particles, link })
+    }
+
+    /// Advance by `dt` seconds on `curve`, which may have been edited since the last step
+    pub fn step(&mut self, curve: &Curve, dt: f32) {
+        let bvh = Bvh::new(std::slice::from_ref(curve));
+        if bvh.is_empty() { return };
+        let dt = dt.min(MAX_FRAME);
+        let steps = (dt / MAX_STEP).ceil().max(1.) as usize;
+        let h = dt / steps as f32;
+
+        for _ in 0..steps {
+            // Verlet integration, the velocity is what the last step moved
+            for (p, previous) in self.particles.iter_mut().zip(&mut self.previous) {
+                let moved = (*p - *previous) * (1. - DAMPING * h);
+                *previous = *p;
+                *p += moved + GRAVITY * h * h;
+            }
+
+            for _ in 0..CONSTRAINT_ITERATIONS {
+                for i in 0..self.particles.len() - 1 {
+                    let d = self.particles[i + 1] - self.particles[i];
+                    let correction = d.normalize_or_zero() * (d.length() - self.link) / 2.;
+                    self.particles[i] += correction;
+                    self.particles[i + 1] -= correction;
+                }
+                for p in &mut self.particles {
+                    if let Some(hit) = bvh.closest(*p) {
+                        *p = hit.pos;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Links as lines and particles as dots, with the world camera set
+    pub fn draw(&self, width: f32, color: Color) {
+        for pair in self.particles.windows(2) {
+            draw_line(pair[0].x, pair[0].y, pair[1].x, pair[1].y, width, color);
+        }
+        for p in &self.particles {
+            draw_circle(p.x, p.y, width * 1.5, color);
+        }
+    }
+}
@@ -0,0 +1,69 @@
+use macroquad::prelude::*;
+
+use crate::coords::{nice_step, tick_label, CoordinateSystem};
+use crate::view::View;
+
+pub const RULER_SIZE: f32 = 22.0;
+// Labelled ticks are at least this many pixels apart
+const MIN_TICK_SPACING: f32 = 70.0;
+const MINOR_TICKS: usize = 5;
+const RULER_BACKGROUND: Color = Color::new(0.1, 0.1, 0.1, 0.9);
+
+/// Tick positions in units from `start` to `end` every `step`
+fn ticks(start: f32, end: f32, step: f32) -> impl Iterator<Item = f32> {
+    let first = (start.min(end) / step).floor() as i64;
+    let last = (start.max(end) / step).ceil() as i64;
+    (first..=last).map(move |i| i as f32 * step)
+}
+
+/// Rulers along the top and left edges with ticks in units, marking the cursor, plus a
+/// readout of its coordinates in the corner
+pub fn draw_rulers(view: &View, coords: &CoordinateSystem, cursor: Vec2) {
+    let pixels_per_unit = coords.pixels_per_unit * view.scale;
+    let step = nice_step(MIN_TICK_SPACING / pixels_per_unit);
+    let minor = step / MINOR_TICKS as f32;
+    let to_screen = |math: Vec2| view.world_to_screen(coords.from_math(math));
+
+    let top_left = coords.to_math(view.screen_to_world(Vec2::ZERO));
+    let bottom_right = coords.to_math(view.screen_to_world(vec2(screen_width(), screen_height())));
+
+    draw_rectangle(0., 0., screen_width(), RULER_SIZE, RULER_BACKGROUND);
+    draw_rectangle(0., 0., RULER_SIZE, screen_height(), RULER_BACKGROUND);
+
+    for x in ticks(top_left.x, bottom_right.x, minor) {
+        let sx = to_screen(vec2(x, 0.)).x;
+        let major = (x / step).round() * step;
+        let length = match (x - major).abs() < minor / 2. {
+            true => {
+                draw_text(&tick_label(major, step), sx + 2., 11., 12., GRAY);
+                RULER_SIZE
+            },
+            false => RULER_SIZE / 4.
+        };
+        draw_line(sx, RULER_SIZE - length, sx, RULER_SIZE, 1., GRAY);
+    }
+
+    for y in ticks(top_left.y, bottom_right.y, minor) {
+        let sy = to_screen(vec2(0., y)).y;
+        let major = (y / step).round() * step;
+        let length = match (y - major).abs() < minor / 2. {
+            true => {
+                draw_text(&tick_label(major, step), 2., sy - 2., 12., GRAY);
+                RULER_SIZE
+            },
+            false => RULER_SIZE / 4.
+        };
+        draw_line(RULER_SIZE - length, sy, RULER_SIZE, sy, 1., GRAY);
+    }
+
+    // Cursor marks on both rulers
+    let screen_cursor = view.world_to_screen(cursor);
+    draw_line(screen_cursor.x, 0., screen_cursor.x, RULER_SIZE, 2., RED);
+    draw_line(0., screen_cursor.y, RULER_SIZE, screen_cursor.y, 2., RED);
+
+    let math = coords.to_math(cursor);
+    let readout = format!("x: {:.2}  y: {:.2}", math.x, math.y);
+    let width = measure_text(&readout, None, 16, 1.).width;
+    draw_rectangle(screen_width() - width - 20., screen_height() - 26., width + 20., 26., RULER_BACKGROUND);
+    draw_text(&readout, screen_width() - width - 10., screen_height() - 8., 16., WHITE);
+}
@@ -0,0 +1,102 @@
+use macroquad::prelude::*;
+
+use crate::analysis::winding_number;
+use crate::bvh::Bvh;
+use crate::curve::Curve;
+
+/// Texture sizes the editor exports at
+pub const SDF_RESOLUTIONS: [usize; 4] = [64, 128, 256, 512];
+
+/// Signed distances from a closed curve sampled at the centers of a square grid of texels,
+/// negative inside. `origin` is the world position of the top left corner of the texture
+pub struct DistanceField {
+    pub size: usize,
+    pub origin: Vec2,
+    pub texel_size: f32,
+    /// Row major, in world units
+    pub values: Vec<f32>
+}
+
+/// Distance field of the closed `curve` in a `size` x `size` texture, with the curve's
+/// bounds centered and `spread` texels of margin around them. None for open curves
+pub fn signed_distance_field(curve: &Curve, size: usize, spread: usize) -> Option<DistanceField> {
+    let [min, max] = curve.bounds().filter(|_| curve.closed && size > 2 * spread)?;
+    let extent = (max - min).max_element().max(f32::EPSILON);
+    let texel_size = extent / (size - 2 * spread) as f32;
+    let origin = (min + max) / 2. - Vec2::splat(texel_size * size as f32 / 2.);
+    let bvh = Bvh::new(std::slice::from_ref(curve));
+    // Gathered once, Curve::winding_number would rebuild the segments for every texel
+    let segments = curve.segments();
+
+    let values = (0..size * size).map(|i| {
+        let p = origin + (vec2((i % size) as f32, (i / size) as f32) + 0.5) * texel_size;
+        let distance = bvh.closest(p).map_or(f32::MAX, |hit| hit.distance);
+        match winding_number(&segments, p) != 0 {
+            true => -distance,
+            false => distance
+        }
+    }).collect();
+
+    Some(DistanceField { size, origin, texel_size, values })
+}
+
+impl DistanceField {
+    /// 8 bit grayscale PNG with the edge at 128, distances of `spread` texels or more
+    /// inside are white and outside black, the usual layout for SDF shaders
+    pub fn to_png(&self, spread: usize) -> Vec<u8> {
+        let range = (spread as f32 * self.texel_size).max(f32::EPSILON);
+        let pixels: Vec<u8> = self.values.iter()
+            .map(|d| ((0.5 - d / range / 2.).clamp(0., 1.) * 255.).round() as u8)
+            .collect();
+
+        let mut bytes = Vec::new();
+        let mut encoder = png::Encoder::new(&mut bytes, self.size as u32, self.size as u32);
+        encoder.set_color(png::ColorType::Grayscale);
+        encoder.set_depth(png::BitDepth::Eight);
+        // Only fails on io errors, which writing to memory doesn't have
+        let mut writer = encoder.write_header().expect("png header should encode");
+        writer.write_image_data(&pixels).expect("png data should encode");
+        writer.finish().expect("png should encode");
+
+        bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::point::Point;
+
+    // Straight sides around [0, 10]², closed
+    fn square(closed: bool) -> Curve {
+        let corners = [vec2(0., 0.), vec2(10., 0.), vec2(10., 10.), vec2(0., 10.), vec2(0., 0.)];
+        let mut positions = vec![corners[0]];
+        for pair in corners.windows(2) {
+            positions.extend([pair[0].lerp(pair[1], 1. / 3.), pair[0].lerp(pair[1], 2. / 3.), pair[1]]);
+        }
+        let control = positions.into_iter().map(|pos| Point::new(pos, WHITE)).collect();
+        Curve { control, closed, ..Curve::default() }
+    }
+
+    #[test]
+    fn negative_inside_positive_outside() {
+        let field = signed_distance_field(&square(true), 16, 3).unwrap();
+        let at = |p: Vec2| {
+            let texel = ((p - field.origin) / field.texel_size).floor();
+            field.values[texel.y as usize * field.size + texel.x as usize]
+        };
+        // 10 units over 10 texels, so texel centers are half a unit off the grid
+        assert!((field.texel_size - 1.).abs() < 1e-4);
+        assert!((at(vec2(5.5, 5.5)) + 4.5).abs() < 1e-2);
+        assert!((at(vec2(0.5, 4.5)) + 0.5).abs() < 1e-2);
+        assert!((at(vec2(-1.5, 4.5)) - 1.5).abs() < 1e-2);
+        assert!(at(vec2(12.5, 12.5)) > 0.);
+    }
+
+    #[test]
+    fn no_field_without_room_or_an_inside() {
+        assert!(signed_distance_field(&square(false), 16, 3).is_none());
+        assert!(signed_distance_field(&square(true), 6, 3).is_none());
+        assert!(signed_distance_field(&square(true), 7, 3).is_some());
+    }
+}
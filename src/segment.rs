@@ -0,0 +1,230 @@
+use macroquad::prelude::*;
+
+use crate::error::SplineError;
+use crate::math::{decasteljau, eval_power_basis, line_crossings, power_basis, split_at_params, GAUSS_LEGENDRE_5};
+use crate::point::Point;
+
+/// Control points of a single cubic bezier piece, validated on construction
+#[derive(Clone, Copy, Debug)]
+pub struct CubicSegment {
+    pub points: [Point; 4]
+}
+
+impl CubicSegment {
+    pub const POINTS: usize = 4;
+
+    pub fn new(points: [Point; 4]) -> Self { Self { points } }
+
+    /// Segment from a slice of exactly four points
+    pub fn try_new(points: &[Point]) -> Result<Self, SplineError> {
+        match points.len() {
+            n if n < Self::POINTS => Err(SplineError::NotEnoughPoints { found: n, needed: Self::POINTS - n }),
+            n if n > Self::POINTS => Err(SplineError::TooManyPoints { found: n, expected: Self::POINTS }),
+            _ => Ok(Self::new([points[0], points[1], points[2], points[3]]))
+        }
+    }
+
+    pub fn eval(&self, t: f32) -> Point {
+        decasteljau(&self.points, t)
+    }
+
+    /// Coefficients [c0, c1, c2, c3] of B(t) = c0 + c1 t + c2 t^2 + c3 t^3, worth keeping
+    /// around to evaluate the segment many times with `eval_power_basis`
+    pub fn coefficients(&self) -> [Vec2; 4] {
+        power_basis(self.points)
+    }
+
+    pub fn start(&self) -> Vec2 { self.points[0].pos }
+
+    pub fn end(&self) -> Vec2 { self.points[3].pos }
+
+    /// `n` evenly spaced points from the start to the end of the segment, worked out one at a
+    /// time by forward differencing as the iterator is walked instead of collected up front
+    pub fn iter_samples(&self, n: usize) -> Samples {
+        let [c0, c1, c2, c3] = self.coefficients();
+        let h = 1. / n.saturating_sub(1).max(1) as f32;
+        let (h2, h3) = (h * h, h * h * h);
+
+        Samples {
+            p: c0,
+            d1: c1 * h + c2 * h2 + c3 * h3,
+            d2: 2. * c2 * h2 + 6. * c3 * h3,
+            d3: 6. * c3 * h3,
+            remaining: n
+        }
+    }
+
+    /// Pieces of the segment inside `rect` (min and max corners), in order along it. The
+    /// segment is split where it crosses the lines through the rectangle's sides, so every
+    /// piece is either in or out, and the ones whose middle is inside are kept
+    pub fn clip_to_rect(&self, rect: [Vec2; 2]) -> Vec<CubicSegment> {
+        let [min, max] = rect;
+        let sides = [(min, Vec2::X), (max, Vec2::X), (min, Vec2::Y), (max, Vec2::Y)];
+        let mut ts: Vec<f32> = sides.iter()
+            .flat_map(|&(origin, dir)| line_crossings(self.points, origin, dir))
+            .filter(|&t| t > 1e-5 && t < 1. - 1e-5)
+            .collect();
+        ts.sort_by(f32::total_cmp);
+        ts.dedup_by(|a, b| (*a - *b).abs() < 1e-5);
+
+        let slack = Vec2::splat(1e-3 * (max - min).max_element().max(1.));
+        split_at_params(self.points, &ts).into_iter()
+            .map(CubicSegment::new)
+            .filter(|piece| {
+                let middle = piece.eval(0.5).pos;
+                (min - slack).cmple(middle).all() && middle.cmple(max + slack).all()
+            })
+            .collect()
+    }
+}
+
+/// Iterator over evenly spaced points of a segment from `CubicSegment::iter_samples`. After
+/// the setup each sample is three additions, with rounding error growing along the segment
+#[derive(Clone, Debug)]
+pub struct Samples {
+    p: Vec2,
+    // First, second and third forward differences of B at the current step
+    d1: Vec2,
+    d2: Vec2,
+    d3: Vec2,
+    remaining: usize
+}
+
+impl Iterator for Samples {
+    type Item = Vec2;
+
+    fn next(&mut self) -> Option<Vec2> {
+        if self.remaining == 0 { return None };
+        self.remaining -= 1;
+
+        let sample = self.p;
+        self.p += self.d1;
+        self.d1 += self.d2;
+        self.d2 += self.d3;
+        Some(sample)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl ExactSizeIterator for Samples {}
+
+// Steps of the arc length table, each integrated with one 5 point Gauss-Legendre rule
+const LENGTH_TABLE_STEPS: usize = 16;
+// Coarse scan before Newton's method when projecting onto the segment
+const PROJECT_SCAN_STEPS: usize = 32;
+
+/// A segment with everything that's costly to work out from its control points, kept
+/// between frames by the curve and redone only for the segments whose points moved
+#[derive(Clone, Debug)]
+pub struct SegmentCache {
+    pub points: [Point; 4],
+    /// Power basis coefficients of B(t)
+    pub coefficients: [Vec2; 4],
+    /// Of B'(t) = d0 + d1 t + d2 t^2
+    pub velocity: [Vec2; 3],
+    /// Of B''(t) = a0 + a1 t
+    pub acceleration: [Vec2; 2],
+    /// Arc length from the start to t = i / LENGTH_TABLE_STEPS, the last one is the length
+    pub lengths: Vec<f32>
+}
+
+impl SegmentCache {
+    pub fn new(points: [Point; 4]) -> Self {
+        let coefficients = power_basis(points);
+        let [_, c1, c2, c3] = coefficients;
+        let mut cache = Self {
+            points,
+            coefficients,
+            velocity: [c1, 2. * c2, 3. * c3],
+            acceleration: [2. * c2, 6. * c3],
+            lengths: Vec::with_capacity(LENGTH_TABLE_STEPS + 1)
+        };
+
+        let step = 1. / LENGTH_TABLE_STEPS as f32;
+        let mut length = 0.;
+        cache.lengths.push(length);
+        for i in 0..LENGTH_TABLE_STEPS {
+            length += cache.length_between(i as f32 * step, (i + 1) as f32 * step);
+            cache.lengths.push(length);
+        }
+        cache
+    }
+
+    /// Whether this was computed for these control points, colors aside
+    pub fn matches(&self, points: &[Point; 4]) -> bool {
+        self.points.iter().zip(points).all(|(a, b)| a.pos == b.pos)
+    }
+
+    pub fn eval(&self, t: f32) -> Vec2 {
+        eval_power_basis(&self.coefficients, t)
+    }
+
+    pub fn velocity(&self, t: f32) -> Vec2 {
+        let [d0, d1, d2] = self.velocity;
+        d0 + t * (d1 + t * d2)
+    }
+
+    pub fn acceleration(&self, t: f32) -> Vec2 {
+        let [a0, a1] = self.acceleration;
+        a0 + t * a1
+    }
+
+    pub fn length(&self) -> f32 {
+        self.lengths[LENGTH_TABLE_STEPS]
+    }
+
+    // One Gauss-Legendre rule over [t0, t1], only accurate over a table step or less
+    fn length_between(&self, t0: f32, t1: f32) -> f32 {
+        let (mid, h) = ((t0 + t1) / 2., t1 - t0);
+        GAUSS_LEGENDRE_5.iter()
+            .map(|&(x, w)| w as f32 * self.velocity(mid + x as f32 * h / 2.).length())
+            .sum::<f32>() * h / 2.
+    }
+
+    /// Parameter where the arc length from the start reaches `length`, interpolated in the
+    /// table and refined with Newton's method inside the step it falls in
+    pub fn param_at_length(&self, length: f32) -> f32 {
+        if length <= 0. { return 0. };
+        if length >= self.length() { return 1. };
+
+        let i = (self.lengths.partition_point(|&l| l <= length) - 1).min(LENGTH_TABLE_STEPS - 1);
+        let step = 1. / LENGTH_TABLE_STEPS as f32;
+        let (low, high) = (i as f32 * step, (i + 1) as f32 * step);
+        let (before, after) = (self.lengths[i], self.lengths[i + 1]);
+        let mut t = match after > before {
+            true => low + step * (length - before) / (after - before),
+            false => low
+        };
+        for _ in 0..3 {
+            let error = before + self.length_between(low, t) - length;
+            let speed = self.velocity(t).length();
+            if error.abs() < 1e-4 || speed < f32::EPSILON { break };
+            t = (t - error / speed).clamp(low, high);
+        }
+
+        t
+    }
+
+    /// Parameter and position of the closest point to `target`, the same coarse scan and
+    /// Newton's method as `closest_param`
+    pub fn project(&self, target: Vec2) -> (f32, Vec2) {
+        let mut t = (0..=PROJECT_SCAN_STEPS)
+            .map(|i| i as f32 / PROJECT_SCAN_STEPS as f32)
+            .min_by(|&a, &b| self.eval(a).distance_squared(target).total_cmp(&self.eval(b).distance_squared(target)))
+            .unwrap_or(0.);
+
+        for _ in 0..8 {
+            let diff = self.eval(t) - target;
+            let vel = self.velocity(t);
+            let denominator = vel.dot(vel) + diff.dot(self.acceleration(t));
+            if denominator.abs() < f32::EPSILON { break };
+
+            t = (t - diff.dot(vel) / denominator).clamp(0., 1.);
+        }
+
+        (t, self.eval(t))
+    }
+}
@@ -0,0 +1,197 @@
+use macroquad::prelude::*;
+
+use crate::curve::Curve;
+use crate::document::Document;
+
+// Pixels the box is drawn outside the selected points, so its grips don't cover them
+const BOX_PADDING: f32 = 8.;
+// Pixels from a grip's center it can be grabbed within
+const GRIP_RADIUS: f32 = 6.;
+// Angle rotations snap to with shift held
+const ROTATION_STEP: f32 = std::f32::consts::PI / 12.;
+
+/// Part of the selection box that can be dragged
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Grip {
+    /// Scales the selection around the pivot, corners clockwise from the top left
+    Corner(usize),
+    /// Rotates the selection around the pivot, edge midpoints clockwise from the top
+    Edge(usize),
+    Pivot
+}
+
+// A grip being dragged, with where the drag started and where everything was then
+#[derive(Clone, Debug)]
+struct Drag {
+    grip: Grip,
+    start: Vec2,
+    pivot: Vec2,
+    original: Vec<Vec2>
+}
+
+/// Control points of one curve picked by dragging a box around them, scaled and rotated
+/// together around a pivot with the grips on their bounding box
+#[derive(Clone, Debug, Default)]
+pub struct Selection {
+    pub curve: usize,
+    pub points: Vec<usize>,
+    /// Where the points scale and rotate around, the middle of their box until it's moved
+    pub pivot: Option<Vec2>,
+    marquee: Option<[Vec2; 2]>,
+    drag: Option<Drag>
+}
+
+impl Selection {
+    /// Box around the selected points of `curve`, `scale` world units per pixel out from them
+    pub fn bounds(&self, curve: &Curve, scale: f32) -> Option<[Vec2; 2]> {
+        let mut positions = self.positions(curve);
+        let first = positions.next()?;
+        let (min, max) = positions.fold((first, first), |(min, max), p| (min.min(p), max.max(p)));
+        let pad = Vec2::splat(BOX_PADDING * scale);
+        Some([min - pad, max + pad])
+    }
+
+    // Ids past the end are left from an older version of the curve, until `update` drops them
+    fn positions<'a>(&'a self, curve: &'a Curve) -> impl Iterator<Item = Vec2> + 'a {
+        self.points.iter().filter_map(|&id| curve.control.get(id)).map(|p| p.pos)
+    }
+
+    fn pivot_in(&self, [min, max]: [Vec2; 2]) -> Vec2 {
+        self.pivot.unwrap_or((min + max) / 2.)
+    }
+
+    // Each grip and where it sits on the box
+    fn grips(&self, [min, max]: [Vec2; 2]) -> Vec<(Grip, Vec2)> {
+        let corners = [min, vec2(max.x, min.y), max, vec2(min.x, max.y)];
+        let mut grips: Vec<_> = corners.iter().enumerate().map(|(k, &c)| (Grip::Corner(k), c)).collect();
+        for k in 0..4 {
+            grips.push((Grip::Edge(k), (corners[k] + corners[(k + 1) % 4]) / 2.));
+        }
+        grips.push((Grip::Pivot, self.pivot_in([min, max])));
+        grips
+    }
+
+    /// Grip of the box around the selection under `cursor`, the pivot winning over the others
+    pub fn grip_at(&self, curve: &Curve, cursor: Vec2, scale: f32) -> Option<Grip> {
+        let bounds = self.bounds(curve, scale)?;
+        self.grips(bounds).into_iter()
+            .rev()
+            .find(|&(_, pos)| pos.distance(cursor) <= GRIP_RADIUS * scale)
+            .map(|(grip, _)| grip)
+    }
+
+    /// Follow the mouse over the active curve: pressing on a grip starts transforming, elsewhere
+    /// it starts a selection box picking the points in it on release. Shift adds to the
+    /// selection, keeps scaling proportional and rotates in 15° steps. `scale` is world
+    /// units per pixel
+    pub fn update(&mut self, doc: &mut Document, cursor: Vec2, pressed: bool, down: bool, shift: bool, scale: f32) {
+        let index = doc.active;
+        let curve = doc.active_mut();
+        // Points of another curve, or deleted since, are no longer selected
+        if index != self.curve || self.points.iter().any(|&id| id >= curve.control.len()) {
+            *self = Selection { curve: index, ..Selection::default() };
+        }
+
+        if pressed {
+            let pivot = self.bounds(curve, scale).map(|bounds| self.pivot_in(bounds));
+            match (self.grip_at(curve, cursor, scale), pivot) {
+                (Some(grip), Some(pivot)) => {
+                    let original = self.points.iter().map(|&id| curve.control[id].pos).collect();
+                    self.drag = Some(Drag { grip, start: cursor, pivot, original });
+                },
+                _ => self.marquee = Some([cursor, cursor])
+            }
+        }
+
+        match self.drag.as_ref().filter(|_| down) {
+            Some(drag) if drag.grip == Grip::Pivot => self.pivot = Some(cursor),
+            Some(drag) => {
+                let from = drag.start - drag.pivot;
+                let to = cursor - drag.pivot;
+                // Every point is scaled and then turned around the pivot
+                let (factor, rotation) = match drag.grip {
+                    Grip::Corner(_) => {
+                        let ratio = |a: f32, b: f32| if a.abs() > 1e-3 { b / a } else { 1. };
+                        let factor = match shift {
+                            true => Vec2::splat(to.dot(from) / from.length_squared().max(1e-6)),
+                            false => vec2(ratio(from.x, to.x), ratio(from.y, to.y))
+                        };
+                        (factor, Vec2::X)
+                    },
+                    _ => {
+                        let angle = to.y.atan2(to.x) - from.y.atan2(from.x);
+                        let angle = if shift { (angle / ROTATION_STEP).round() * ROTATION_STEP } else { angle };
+                        (Vec2::ONE, Vec2::from_angle(angle))
+                    }
+                };
+                for (&id, &original) in self.points.iter().zip(&drag.original) {
+                    curve.move_point(id, drag.pivot + rotation.rotate((original - drag.pivot) * factor), false);
+                }
+            },
+            None => ()
+        }
+
+        if let Some(marquee) = &mut self.marquee {
+            marquee[1] = cursor;
+        }
+        if !down {
+            if let Some([a, b]) = self.marquee.take() {
+                let (min, max) = (a.min(b), a.max(b));
+                if !shift { self.points.clear() };
+                for (id, p) in curve.control.iter().enumerate() {
+                    let inside = p.pos.cmpge(min).all() && p.pos.cmple(max).all();
+                    if inside && !self.points.contains(&id) { self.points.push(id) };
+                }
+                self.pivot = None;
+            }
+            self.drag = None;
+        }
+    }
+
+    /// The selection box being dragged, rings on the selected points and the box around
+    /// them with its grips and pivot. `scale` is world units per pixel
+    pub fn draw(&self, curve: &Curve, scale: f32) {
+        let color = Color::new(0.3, 0.8, 1., 0.9);
+        if let Some([a, b]) = self.marquee {
+            let (min, max) = (a.min(b), a.max(b));
+            draw_rectangle(min.x, min.y, max.x - min.x, max.y - min.y, Color { a: 0.1, ..color });
+            draw_rectangle_lines(min.x, min.y, max.x - min.x, max.y - min.y, scale, color);
+        }
+        for p in self.positions(curve) {
+            draw_circle_lines(p.x, p.y, 9. * scale, 1.5 * scale, color);
+        }
+
+        let Some([min, max]) = self.bounds(curve, scale) else { return };
+        let size = max - min;
+        draw_rectangle_lines(min.x, min.y, size.x, size.y, scale, Color { a: 0.6, ..color });
+        let r = GRIP_RADIUS * scale;
+        for (grip, Vec2 { x, y }) in self.grips([min, max]) {
+            match grip {
+                Grip::Corner(_) => draw_rectangle(x - r / 1.5, y - r / 1.5, r * 1.33, r * 1.33, color),
+                Grip::Edge(_) => draw_circle(x, y, r / 1.5, color),
+                Grip::Pivot => {
+                    draw_circle_lines(x, y, r, 1.5 * scale, color);
+                    draw_line(x - 1.5 * r, y, x + 1.5 * r, y, scale, color);
+                    draw_line(x, y - 1.5 * r, x, y + 1.5 * r, scale, color);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::point::Point;
+
+    #[test]
+    fn bounds_skip_points_the_curve_no_longer_has() {
+        let control = [vec2(0., 0.), vec2(10., 20.)].map(|pos| Point::new(pos, WHITE)).to_vec();
+        let curve = Curve { control, ..Curve::default() };
+        let selection = Selection { points: vec![0, 1, 5], ..Selection::default() };
+        assert_eq!(selection.bounds(&curve, 0.), Some([vec2(0., 0.), vec2(10., 20.)]));
+
+        let stale = Selection { points: vec![7], ..Selection::default() };
+        assert_eq!(stale.bounds(&curve, 1.), None);
+    }
+}
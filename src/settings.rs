@@ -0,0 +1,159 @@
+use macroquad::prelude::Vec2;
+
+use crate::math::{cubic_bezier, cubic_bezier_powers, decasteljau, eval_power_basis, power_basis};
+use crate::point::Point;
+use crate::segment::CubicSegment;
+use crate::theme::Theme;
+
+pub const DEFAULT_SAMPLES_PER_SEGMENT: usize = 2000;
+pub const MIN_SAMPLES_PER_SEGMENT: usize = 2;
+pub const MAX_SAMPLES_PER_SEGMENT: usize = 16000;
+pub const DEFAULT_FLATTEN_TOLERANCE: f32 = 0.25;
+pub const MIN_LINE_WIDTH: f32 = 1.;
+pub const MAX_LINE_WIDTH: f32 = 12.;
+
+/// Form the curves are evaluated in when sampled, they only differ in speed and rounding
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Evaluator {
+    /// Bernstein polynomials nested Horner style, with the colors blended between the
+    /// segment's ends
+    #[default]
+    Horner,
+    /// Bernstein polynomials with every power of t taken on its own, colors as Horner
+    Bernstein,
+    /// Repeated lerps of the points, colors included
+    Casteljau,
+    /// Power basis coefficients M P worked out once per segment, colors as Horner
+    Matrix,
+    /// Streamed along each segment by forward differencing, colors as Horner
+    ForwardDifferences
+}
+
+impl Evaluator {
+    pub fn next(self) -> Self {
+        match self {
+            Evaluator::Horner => Evaluator::Bernstein,
+            Evaluator::Bernstein => Evaluator::Casteljau,
+            Evaluator::Casteljau => Evaluator::Matrix,
+            Evaluator::Matrix => Evaluator::ForwardDifferences,
+            Evaluator::ForwardDifferences => Evaluator::Horner
+        }
+    }
+
+    /// `steps` + 1 evenly spaced points of one segment in this form
+    pub fn samples(self, points: [Point; 4], steps: usize) -> Vec<Vec2> {
+        let ts = (0..=steps).map(|i| i as f32 / steps.max(1) as f32);
+        match self {
+            Evaluator::Horner => ts.map(|t| cubic_bezier(t, &points)).collect(),
+            Evaluator::Bernstein => ts.map(|t| cubic_bezier_powers(t, &points)).collect(),
+            Evaluator::Casteljau => ts.map(|t| decasteljau(&points, t).pos).collect(),
+            Evaluator::Matrix => {
+                let coefficients = power_basis(points);
+                ts.map(|t| eval_power_basis(&coefficients, t)).collect()
+            }
+            Evaluator::ForwardDifferences => CubicSegment::new(points).iter_samples(steps + 1).collect()
+        }
+    }
+
+    /// Largest distance between this form and De Casteljau's over `samples` + 1 points of
+    /// every segment, how much their rounding differs
+    pub fn deviation(self, segments: &[[Point; 4]], samples: usize) -> f32 {
+        segments.iter()
+            .flat_map(|&segment| {
+                let exact = Evaluator::Casteljau.samples(segment, samples);
+                self.samples(segment, samples).into_iter().zip(exact).map(|(p, q)| p.distance(q))
+            })
+            .fold(0., f32::max)
+    }
+}
+
+/// How curves get sampled and which overlays are drawn on top of them
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RenderSettings {
+    /// Points evaluated per segment, more is smoother and slower to re-render
+    pub samples_per_segment: usize,
+    /// When set, segments are flattened into lines that stay within this distance of the
+    /// curve instead of using a fixed sample count
+    pub flatten_tolerance: Option<f32>,
+    /// Pick the sample count from each segment's size on screen instead of a fixed one
+    pub level_of_detail: bool,
+    /// Current zoom of the view, used to size samples and tolerances in pixels
+    pub pixels_per_unit: f32,
+    /// Physical pixels per logical pixel of the display. Sizes are in logical pixels,
+    /// sampling density and tolerances follow the physical ones
+    pub dpi_scale: f32,
+    /// World rectangle on screen as its min and max corners. Segments far off it aren't
+    /// sampled and lines outside it aren't drawn, None draws everything
+    pub visible: Option<[Vec2; 2]>,
+    pub evaluator: Evaluator,
+    /// Draw the curve as a feathered triangle strip instead of single pixel samples
+    pub antialias: bool,
+    /// Width in pixels of the curve when drawn as lines, and of the handles
+    pub line_width: f32,
+    pub draw_bounding: bool,
+    /// Make the tight boxes with interval arithmetic instead of the derivative's roots,
+    /// a little larger but guaranteed to hold the segment
+    pub interval_boxes: bool,
+    pub draw_hull: bool,
+    pub theme: Theme
+}
+
+impl Default for RenderSettings {
+    fn default() -> Self {
+        Self {
+            samples_per_segment: DEFAULT_SAMPLES_PER_SEGMENT,
+            flatten_tolerance: None,
+            level_of_detail: false,
+            pixels_per_unit: 1.,
+            dpi_scale: 1.,
+            visible: None,
+            evaluator: Evaluator::default(),
+            antialias: false,
+            line_width: MIN_LINE_WIDTH,
+            draw_bounding: false,
+            interval_boxes: false,
+            draw_hull: false,
+            theme: Theme::default()
+        }
+    }
+}
+
+// Level of detail aims for one sample per pixel of the control polygon's length
+const MIN_LOD_SAMPLES: usize = 8;
+
+impl RenderSettings {
+    /// Samples for a segment whose control polygon is `polygon_length` world units long
+    pub fn samples_for(&self, polygon_length: f32) -> usize {
+        match self.level_of_detail {
+            true => ((polygon_length * self.pixels_per_unit * self.dpi_scale).ceil() as usize).clamp(MIN_LOD_SAMPLES, MAX_SAMPLES_PER_SEGMENT),
+            false => self.samples_per_segment
+        }
+    }
+
+    /// Flattening tolerance in world units, the configured one is in physical pixels
+    pub fn world_tolerance(&self) -> Option<f32> {
+        self.flatten_tolerance.map(|tolerance| tolerance / (self.pixels_per_unit * self.dpi_scale))
+    }
+
+    /// Tolerance in world units for geometry that's always flattened, like fills, the
+    /// default one while flattening is off
+    pub fn fill_tolerance(&self) -> f32 {
+        self.world_tolerance().unwrap_or(DEFAULT_FLATTEN_TOLERANCE / (self.pixels_per_unit * self.dpi_scale))
+    }
+
+    /// Line width in world units
+    pub fn world_line_width(&self) -> f32 {
+        self.line_width / self.pixels_per_unit
+    }
+
+    /// Change the line width by `delta` pixels, kept within the allowed range
+    pub fn change_line_width(&mut self, delta: f32) {
+        self.line_width = (self.line_width + delta).clamp(MIN_LINE_WIDTH, MAX_LINE_WIDTH);
+    }
+
+    /// Multiply the sample count by `factor`, kept within the allowed range
+    pub fn scale_samples(&mut self, factor: f32) {
+        let samples = (self.samples_per_segment as f32 * factor).round() as usize;
+        self.samples_per_segment = samples.clamp(MIN_SAMPLES_PER_SEGMENT, MAX_SAMPLES_PER_SEGMENT);
+    }
+}
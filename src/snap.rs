@@ -0,0 +1,201 @@
+use macroquad::prelude::*;
+
+use crate::bvh::SegmentRef;
+use crate::continuity::Continuity;
+use crate::document::Document;
+use crate::math::cubic_bezier;
+use crate::point::Point;
+
+/// Pixels from the cursor a dragged point snaps from, unless the config says otherwise
+pub const DEFAULT_SNAP_RADIUS: f32 = 10.;
+// Crossings are located to within this many world units
+const INTERSECTION_TOLERANCE: f32 = 0.01;
+// Crossings this close to a segment's end are where it meets another at an anchor
+const ANCHOR_TOLERANCE: f32 = 0.1;
+
+/// What a dragged point snapped to, each drawn with its own marker
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SnapKind {
+    Anchor,
+    Handle,
+    Midpoint,
+    Intersection
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SnapTarget {
+    pub pos: Vec2,
+    pub kind: SnapKind
+}
+
+// Whether segment `segment` of a curve changes shape while point `id` is dragged, counting
+// the handles carried along with an anchor and mirrored across one for continuity
+fn segment_moves(doc: &Document, curve: usize, segment: usize, dragged: (usize, usize)) -> bool {
+    let (dragged_curve, id) = dragged;
+    if curve != dragged_curve { return false };
+    if doc.curves[curve].knots.is_some() { return true };
+
+    let first = 3 * segment;
+    // The straight segment closing a curve runs from its last anchor back to the first
+    let closing = first + 3 >= doc.curves[curve].control.len();
+    match closing {
+        true => id <= 2 || id + 2 >= first,
+        false => first <= id + 2 && id <= first + 5
+    }
+}
+
+fn near_end(points: &[Point; 4], pos: Vec2) -> bool {
+    [points[0].pos, points[3].pos].iter().any(|end| end.distance(pos) < ANCHOR_TOLERANCE)
+}
+
+// Anchors and handles of the visible curves that stay put while point `id` of `curve` is
+// dragged, which keeps the anchor a dragged handle hangs from
+fn fixed_points(doc: &Document, curve: usize, id: usize) -> Vec<SnapTarget> {
+    let anchor = (id + 1) / 3 * 3;
+    let mut points = Vec::new();
+    for (i, c) in doc.curves.iter().enumerate().filter(|&(i, _)| doc.layer_of(i).visible) {
+        for (j, p) in c.control.iter().enumerate() {
+            let moves = i == curve && (j == id || (j.abs_diff(id) <= 2 && j != anchor));
+            if moves { continue };
+
+            let kind = match j % 3 == 0 {
+                true => SnapKind::Anchor,
+                false => SnapKind::Handle
+            };
+            points.push(SnapTarget { pos: p.pos, kind });
+        }
+    }
+
+    points
+}
+
+/// Every fixed point a dragged point can snap to: the anchors and handles, the middle of
+/// each segment and where segments cross, on visible curves. What moves along with point
+/// `id` of curve `curve` is left out, except the anchor a dragged handle hangs from, so the
+/// targets can be found once when the drag starts and kept until it ends
+pub fn snap_targets(doc: &Document, curve: usize, id: usize) -> Vec<SnapTarget> {
+    let dragged = (curve, id);
+    let visible = |i: usize| doc.layer_of(i).visible;
+    let mut targets = fixed_points(doc, curve, id);
+
+    let fixed = |segment: SegmentRef| visible(segment.curve) && !segment_moves(doc, segment.curve, segment.segment, dragged);
+    let bvh = doc.bvh();
+    for (i, c) in doc.curves.iter().enumerate() {
+        for (j, points) in c.segments().into_iter().enumerate() {
+            let segment = SegmentRef { curve: i, segment: j };
+            if !fixed(segment) { continue };
+
+            targets.push(SnapTarget { pos: cubic_bezier(0.5, &points), kind: SnapKind::Midpoint });
+            // Each crossing is found from both segments, keep it once. Segments of different
+            // curves meeting at an anchor touch there, which is already a target
+            let crossings = bvh.intersections(segment, INTERSECTION_TOLERANCE).into_iter()
+                .filter(|(hit, _)| (hit.segment.curve, hit.segment.segment) > (i, j) && fixed(hit.segment))
+                .filter(|(hit, _)| !near_end(&points, hit.pos) && !bvh.segment(hit.segment).is_some_and(|other| near_end(&other, hit.pos)))
+                .map(|(hit, _)| SnapTarget { pos: hit.pos, kind: SnapKind::Intersection });
+            targets.extend(crossings);
+        }
+    }
+
+    targets
+}
+
+/// Target nearest to `pos` within `radius`, crossings and anchors winning over handles and
+/// midpoints at the same spot
+pub fn nearest_target(targets: &[SnapTarget], pos: Vec2, radius: f32) -> Option<SnapTarget> {
+    let priority = |kind: SnapKind| match kind {
+        SnapKind::Intersection => 0,
+        SnapKind::Anchor => 1,
+        SnapKind::Handle => 2,
+        SnapKind::Midpoint => 3
+    };
+    targets.iter()
+        .filter(|target| target.pos.distance(pos) <= radius)
+        .min_by(|a, b| {
+            let (da, db) = (a.pos.distance(pos), b.pos.distance(pos));
+            match (da - db).abs() < 1e-3 {
+                true => priority(a.kind).cmp(&priority(b.kind)),
+                false => da.total_cmp(&db)
+            }
+        })
+        .copied()
+}
+
+/// `pos` moved onto the nearest of the horizontal, vertical and diagonal lines through
+/// `origin`, for dragging along a fixed direction
+pub fn constrain_to_axes(origin: Vec2, pos: Vec2) -> Vec2 {
+    let offset = pos - origin;
+    let step = std::f32::consts::FRAC_PI_4;
+    let dir = Vec2::from_angle((offset.y.atan2(offset.x) / step).round() * step);
+    origin + dir * offset.dot(dir)
+}
+
+/// Line shown while a dragged point lines up with another, from that point to the dragged one
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Guide {
+    pub from: Vec2,
+    pub to: Vec2
+}
+
+/// Where point `id` of `curve` dragged to `pos` lines up within `radius`: collinear with the
+/// handle across its anchor at a free joint, or else on the vertical and horizontal of fixed
+/// points. Returns the position moved onto the guides, along with them
+pub fn align(doc: &Document, curve: usize, id: usize, pos: Vec2, radius: f32) -> (Vec2, Vec<Guide>) {
+    let c = &doc.curves[curve];
+    let anchor = (id + 1) / 3 * 3;
+    if id != anchor && c.is_joint(anchor) && c.joint_continuity(anchor) == Continuity::C0 {
+        let (a, opposite) = (c.control[anchor].pos, c.control[2 * anchor - id].pos);
+        let dir = (a - opposite).normalize_or_zero();
+        let along = a + dir * (pos - a).dot(dir);
+        if (pos - a).dot(dir) > 0. && along.distance(pos) <= radius {
+            return (along, vec![Guide { from: opposite, to: along }]);
+        }
+    }
+
+    let points = fixed_points(doc, curve, id);
+    // Of the points within `radius` on one axis, the closest on it and then overall
+    let closest_on = |axis: usize| points.iter()
+        .map(|p| p.pos)
+        .filter(|p| (p[axis] - pos[axis]).abs() <= radius)
+        .min_by(|p, q| {
+            let (dp, dq) = ((p[axis] - pos[axis]).abs(), (q[axis] - pos[axis]).abs());
+            dp.total_cmp(&dq).then(p.distance(pos).total_cmp(&q.distance(pos)))
+        });
+
+    let (vertical, horizontal) = (closest_on(0), closest_on(1));
+    let aligned = vec2(vertical.map_or(pos.x, |p| p.x), horizontal.map_or(pos.y, |p| p.y));
+    let guides = [vertical, horizontal].into_iter().flatten().map(|from| Guide { from, to: aligned }).collect();
+    (aligned, guides)
+}
+
+/// A guide as a thin line running a little past both of its points. `scale` is world units
+/// per pixel
+pub fn draw_guide(guide: Guide, scale: f32) {
+    let color = Color::new(1., 0.4, 1., 0.6);
+    let overshoot = (guide.to - guide.from).normalize_or_zero() * 12. * scale;
+    let (from, to) = (guide.from - overshoot, guide.to + overshoot);
+    draw_line(from.x, from.y, to.x, to.y, scale, color);
+    draw_circle(guide.from.x, guide.from.y, 2.5 * scale, color);
+}
+
+/// Marker on the snapped point: a square for anchors, a circle for handles, a triangle for
+/// midpoints and a cross for intersections. `scale` is world units per pixel
+pub fn draw_snap(target: SnapTarget, scale: f32) {
+    let color = Color::new(1., 0.4, 1., 0.9);
+    let (size, width) = (7. * scale, 1.5 * scale);
+    let Vec2 { x, y } = target.pos;
+    match target.kind {
+        SnapKind::Anchor => draw_rectangle_lines(x - size, y - size, 2. * size, 2. * size, width, color),
+        SnapKind::Handle => draw_circle_lines(x, y, size, width, color),
+        SnapKind::Midpoint => {
+            let corners = [vec2(0., -size), vec2(size, size * 0.8), vec2(-size, size * 0.8)].map(|c| target.pos + c);
+            for k in 0..3 {
+                let (a, b) = (corners[k], corners[(k + 1) % 3]);
+                draw_line(a.x, a.y, b.x, b.y, width, color);
+            }
+        },
+        SnapKind::Intersection => {
+            draw_line(x - size, y - size, x + size, y + size, width, color);
+            draw_line(x - size, y + size, x + size, y - size, width, color);
+        }
+    }
+}
@@ -0,0 +1,165 @@
+// Saving and opening files. Natively they're read and written in the working directory
+// and autosaves go to the temp directory, in the browser saving downloads the file,
+// opening shows the file picker and autosaves go to localStorage (see web/storage.js)
+
+// File name in the temp directory natively, key in localStorage in the browser
+const AUTOSAVE_KEY: &str = "numerical-curves.autosave";
+
+/// A file the user opened, its name tells the format
+pub struct OpenedFile {
+    pub name: String,
+    pub data: Vec<u8>
+}
+
+impl OpenedFile {
+    /// The contents as text, with anything that isn't UTF-8 replaced
+    pub fn text(&self) -> String {
+        String::from_utf8_lossy(&self.data).into_owned()
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+mod platform {
+    use std::fs;
+    use std::path::{Path, PathBuf};
+    use std::sync::Mutex;
+
+    use macroquad::prelude::*;
+
+    use super::{OpenedFile, AUTOSAVE_KEY};
+
+    // Natively the file is read as soon as it's asked for, it waits here for `take_opened`
+    static OPENED: Mutex<Option<OpenedFile>> = Mutex::new(None);
+
+    pub fn save_file(name: &str, data: &[u8]) -> Result<(), String> {
+        fs::write(name, data).map_err(|error| format!("couldn't write {}: {}", name, error))
+    }
+
+    pub fn request_open(name: &str) -> Result<(), String> {
+        let data = fs::read(name).map_err(|error| format!("couldn't read {}: {}", name, error))?;
+        let name = Path::new(name).file_name().map_or(name.into(), |file| file.to_string_lossy().into_owned());
+        *OPENED.lock().unwrap() = Some(OpenedFile { name, data });
+        Ok(())
+    }
+
+    pub fn take_opened() -> Option<OpenedFile> {
+        OPENED.lock().unwrap().take()
+    }
+
+    fn autosave_path() -> PathBuf {
+        std::env::temp_dir().join(format!("{}.json", AUTOSAVE_KEY))
+    }
+
+    pub fn autosave(text: &str) {
+        // Written next to it and renamed, so a crash mid-write keeps the previous autosave
+        let (path, partial) = (autosave_path(), autosave_path().with_extension("partial"));
+        if let Err(error) = fs::write(&partial, text).and_then(|()| fs::rename(&partial, &path)) {
+            warn!("couldn't autosave to {}: {}", path.display(), error);
+        }
+    }
+
+    pub fn autosaved() -> Option<String> {
+        fs::read_to_string(autosave_path()).ok()
+    }
+
+    pub fn discard_autosave() {
+        let _ = fs::remove_file(autosave_path());
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+mod platform {
+    use super::{OpenedFile, AUTOSAVE_KEY};
+
+    // Implemented by the miniquad plugin in web/storage.js. Strings are passed as pointer
+    // and length, and read back by asking for their length first and then a buffer that big
+    extern "C" {
+        fn storage_download(name: *const u8, name_len: usize, data: *const u8, data_len: usize);
+        fn storage_pick_file();
+        fn storage_opened_name_len() -> i32;
+        fn storage_opened_len() -> i32;
+        fn storage_take_opened(name: *mut u8, buffer: *mut u8);
+        fn storage_local_set(key: *const u8, key_len: usize, value: *const u8, value_len: usize);
+        fn storage_local_len(key: *const u8, key_len: usize) -> i32;
+        fn storage_local_get(key: *const u8, key_len: usize, buffer: *mut u8);
+        fn storage_local_remove(key: *const u8, key_len: usize);
+    }
+
+    // Fill a buffer of `len` bytes with `read`, negative lengths mean there's nothing
+    fn read_bytes(len: i32, read: impl FnOnce(*mut u8)) -> Option<Vec<u8>> {
+        let mut buffer = vec![0u8; usize::try_from(len).ok()?];
+        read(buffer.as_mut_ptr());
+        Some(buffer)
+    }
+
+    fn read_string(len: i32, read: impl FnOnce(*mut u8)) -> Option<String> {
+        String::from_utf8(read_bytes(len, read)?).ok()
+    }
+
+    pub fn save_file(name: &str, data: &[u8]) -> Result<(), String> {
+        unsafe { storage_download(name.as_ptr(), name.len(), data.as_ptr(), data.len()) };
+        Ok(())
+    }
+
+    pub fn request_open(_name: &str) -> Result<(), String> {
+        unsafe { storage_pick_file() };
+        Ok(())
+    }
+
+    pub fn take_opened() -> Option<OpenedFile> {
+        let name_len = usize::try_from(unsafe { storage_opened_name_len() }).ok()?;
+        let mut name = vec![0u8; name_len];
+        let data = read_bytes(unsafe { storage_opened_len() }, |buffer| unsafe {
+            storage_take_opened(name.as_mut_ptr(), buffer)
+        })?;
+        Some(OpenedFile { name: String::from_utf8(name).ok()?, data })
+    }
+
+    pub fn autosave(text: &str) {
+        let key = AUTOSAVE_KEY;
+        unsafe { storage_local_set(key.as_ptr(), key.len(), text.as_ptr(), text.len()) };
+    }
+
+    pub fn autosaved() -> Option<String> {
+        let key = AUTOSAVE_KEY;
+        let len = unsafe { storage_local_len(key.as_ptr(), key.len()) };
+        read_string(len, |buffer| unsafe { storage_local_get(key.as_ptr(), key.len(), buffer) })
+    }
+
+    pub fn discard_autosave() {
+        let key = AUTOSAVE_KEY;
+        unsafe { storage_local_remove(key.as_ptr(), key.len()) };
+    }
+}
+
+/// Write `data` to the file called `name`, or download it in the browser
+pub fn save_file(name: &str, data: &[u8]) -> Result<(), String> {
+    platform::save_file(name, data)
+}
+
+/// Start opening a file, natively the one called `name` and in the browser whichever the
+/// user picks. It shows up in `take_opened` once it's read, like files dropped on the page
+pub fn request_open(name: &str) -> Result<(), String> {
+    platform::request_open(name)
+}
+
+/// The next file opened since the last call, if any, polled every frame
+pub fn take_opened() -> Option<OpenedFile> {
+    platform::take_opened()
+}
+
+/// Keep `text` around to restore the next time the app starts, in case it's closed or
+/// crashes without saving
+pub fn autosave(text: &str) {
+    platform::autosave(text)
+}
+
+/// The last autosaved text
+pub fn autosaved() -> Option<String> {
+    platform::autosaved()
+}
+
+/// Forget the autosave, for when the user chose not to restore it
+pub fn discard_autosave() {
+    platform::discard_autosave()
+}
@@ -0,0 +1,196 @@
+use std::f32::consts::PI;
+
+use macroquad::prelude::{vec2, vec3, Color, Vec2};
+use macroquad::models::{draw_mesh, Mesh, Vertex};
+
+use crate::point::Point;
+use crate::profiler::count_draw_calls;
+
+// macroquad caps a draw call at 5000 indices, each segment takes 18
+const SEGMENTS_PER_MESH: usize = 256;
+const MAX_INDICES: usize = 4800;
+// Miters longer than this many half widths are beveled instead, like SVG's default
+const MITER_LIMIT: f32 = 4.;
+// Largest angle a single triangle of a round join covers
+const ROUND_STEP: f32 = PI / 8.;
+
+/// How two consecutive segments of a thick polyline are connected
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LineJoin {
+    Miter,
+    Round
+}
+
+/// Draw `points` as a line `width` wide whose edges fade out over `feather`, both in world
+/// units. Colors are taken from the points, so gradients along the curve are kept
+pub fn draw_smooth_polyline(points: &[Point], width: f32, feather: f32) {
+    let points = without_repeats(points);
+    if points.len() < 2 { return };
+
+    let normals = vertex_normals(&points);
+    let (core, outer) = (width / 2., width / 2. + feather);
+
+    let mut start = 0;
+    while start + 1 < points.len() {
+        let end = (start + SEGMENTS_PER_MESH).min(points.len() - 1);
+        let mut vertices = Vec::with_capacity((end - start + 1) * 4);
+        let mut indices = Vec::with_capacity((end - start) * 18);
+
+        for i in start..=end {
+            let (pos, normal, color) = (points[i].pos, normals[i], points[i].color);
+            let clear = Color { a: 0., ..color };
+            vertices.push(vertex(pos + normal * outer, clear));
+            vertices.push(vertex(pos + normal * core, color));
+            vertices.push(vertex(pos - normal * core, color));
+            vertices.push(vertex(pos - normal * outer, clear));
+        }
+
+        for segment in 0..(end - start) as u16 {
+            let (a, b) = (segment * 4, segment * 4 + 4);
+            // Left feather, core and right feather quads between the two cross sections
+            for strip in 0..3 {
+                indices.extend_from_slice(&[a + strip, a + strip + 1, b + strip]);
+                indices.extend_from_slice(&[a + strip + 1, b + strip + 1, b + strip]);
+            }
+        }
+
+        draw_mesh(&Mesh { vertices, indices, texture: None });
+        count_draw_calls(1);
+        start = end;
+    }
+}
+
+/// Draw `points` as connected segments `width` wide, filling the corners with `join` so
+/// there are no gaps between them. A polyline ending where it starts is joined there too
+pub fn draw_polyline(points: &[Point], width: f32, join: LineJoin) {
+    let points = without_repeats(points);
+    if points.len() < 2 { return };
+
+    let half = width / 2.;
+    let closed = points.len() > 2 && points[0].pos.distance_squared(points[points.len() - 1].pos) < 1e-12;
+    let mut batch = Batch::default();
+
+    for pair in points.windows(2) {
+        let (a, b) = (pair[0], pair[1]);
+        let normal = segment_normal(a.pos, b.pos) * half;
+        batch.push(&[
+            vertex(a.pos + normal, a.color), vertex(a.pos - normal, a.color),
+            vertex(b.pos - normal, b.color), vertex(b.pos + normal, b.color)
+        ], &[0, 1, 2, 0, 2, 3]);
+    }
+
+    let last = points.len() - 1;
+    let joints = match closed {
+        true => 0..last,
+        false => 1..last
+    };
+    for i in joints {
+        let before = points[match i { 0 => last - 1, _ => i - 1 }].pos;
+        let (center, after) = (points[i], points[i + 1].pos);
+        add_join(&mut batch, before, center, after, half, join);
+    }
+
+    batch.flush();
+}
+
+// Fill the wedge left on the outside of the turn at `center`
+fn add_join(batch: &mut Batch, before: Vec2, center: Point, after: Vec2, half: f32, join: LineJoin) {
+    let (incoming, outgoing) = (center.pos - before, after - center.pos);
+    let turn = incoming.perp_dot(outgoing);
+    if turn.abs() < 1e-6 * incoming.length() * outgoing.length() { return };
+
+    // The outside is opposite to the direction the polyline turns to
+    let side = -turn.signum();
+    let n0 = segment_normal(before, center.pos) * side;
+    let n1 = segment_normal(center.pos, after) * side;
+    let (pos, color) = (center.pos, center.color);
+
+    match join {
+        LineJoin::Miter => {
+            let bisector = (n0 + n1).normalize_or_zero();
+            let reach = 1. / bisector.dot(n0).max(1e-6);
+            match reach <= MITER_LIMIT {
+                true => batch.push(&[
+                    vertex(pos, color), vertex(pos + n0 * half, color),
+                    vertex(pos + bisector * half * reach, color), vertex(pos + n1 * half, color)
+                ], &[0, 1, 2, 0, 2, 3]),
+                false => batch.push(&[
+                    vertex(pos, color), vertex(pos + n0 * half, color), vertex(pos + n1 * half, color)
+                ], &[0, 1, 2])
+            }
+        },
+        LineJoin::Round => {
+            let angle = n0.angle_between(n1);
+            let steps = ((angle.abs() / ROUND_STEP).ceil() as usize).max(1);
+            let mut vertices = vec![vertex(pos, color)];
+            let mut indices = Vec::with_capacity(steps * 3);
+            for step in 0..=steps {
+                let offset = Vec2::from_angle(angle * step as f32 / steps as f32).rotate(n0);
+                vertices.push(vertex(pos + offset * half, color));
+            }
+            for step in 1..=steps as u16 {
+                indices.extend_from_slice(&[0, step, step + 1]);
+            }
+            batch.push(&vertices, &indices);
+        }
+    }
+}
+
+// Collects small shapes into meshes, drawing one whenever it would get too big
+#[derive(Default)]
+pub(crate) struct Batch {
+    vertices: Vec<Vertex>,
+    indices: Vec<u16>
+}
+
+impl Batch {
+    pub(crate) fn push(&mut self, vertices: &[Vertex], indices: &[u16]) {
+        if self.indices.len() + indices.len() > MAX_INDICES {
+            self.flush();
+        }
+        let base = self.vertices.len() as u16;
+        self.vertices.extend_from_slice(vertices);
+        self.indices.extend(indices.iter().map(|i| base + i));
+    }
+
+    pub(crate) fn flush(&mut self) {
+        if self.indices.is_empty() { return };
+        let mesh = Mesh {
+            vertices: std::mem::take(&mut self.vertices),
+            indices: std::mem::take(&mut self.indices),
+            texture: None
+        };
+        draw_mesh(&mesh);
+        count_draw_calls(1);
+    }
+}
+
+// Drop points closer than floating point noise to the one before, they have no direction
+fn without_repeats(points: &[Point]) -> Vec<Point> {
+    points.iter().fold(Vec::with_capacity(points.len()), |mut kept: Vec<Point>, point| {
+        if kept.last().is_none_or(|last| last.pos.distance_squared(point.pos) > 1e-12) {
+            kept.push(*point);
+        }
+        kept
+    })
+}
+
+fn segment_normal(a: Vec2, b: Vec2) -> Vec2 {
+    let tangent = (b - a).normalize_or_zero();
+    vec2(-tangent.y, tangent.x)
+}
+
+// Unit normal at each point, averaged from the segments on either side
+fn vertex_normals(points: &[Point]) -> Vec<Vec2> {
+    let last = points.len() - 1;
+    (0..=last).map(|i| {
+        let before = points[i.saturating_sub(1)].pos;
+        let after = points[(i + 1).min(last)].pos;
+        let tangent = (after - before).normalize_or_zero();
+        vec2(-tangent.y, tangent.x)
+    }).collect()
+}
+
+pub(crate) fn vertex(pos: Vec2, color: Color) -> Vertex {
+    Vertex { position: vec3(pos.x, pos.y, 0.), uv: Vec2::ZERO, color }
+}
@@ -0,0 +1,267 @@
+use std::fmt::{self, Write};
+
+use macroquad::prelude::*;
+
+use crate::curve::Curve;
+use crate::math::box_contains;
+use crate::point::Point;
+use crate::segment::CubicSegment;
+
+// Up to two decimals, without trailing zeros, which is plenty for pixels
+fn number(value: f32) -> String {
+    let text = format!("{:.2}", value);
+    let text = text.trim_end_matches('0').trim_end_matches('.');
+    match text {
+        "-0" => "0".to_string(),
+        _ => text.to_string()
+    }
+}
+
+/// The curve as the `d` attribute of an SVG path, one `C` per segment and `Z` when it's
+/// closed. In world pixels, already y down like SVG. Empty without a complete segment
+pub fn path_data(curve: &Curve) -> String {
+    let segments = curve.segments();
+    let Some(first) = segments.first() else { return String::new() };
+
+    let mut d = format!("M {} {}", number(first[0].pos.x), number(first[0].pos.y));
+    for [_, c1, c2, end] in &segments {
+        cubic_to(&mut d, [c1.pos, c2.pos, end.pos]);
+    }
+    if curve.closed { d.push_str(" Z") };
+
+    d
+}
+
+fn cubic_to(d: &mut String, [c1, c2, end]: [Vec2; 3]) {
+    let _ = write!(d, " C {} {} {} {} {} {}",
+        number(c1.x), number(c1.y),
+        number(c2.x), number(c2.y),
+        number(end.x), number(end.y));
+}
+
+/// Path data of the parts of the curve inside `rect` (min and max corners), a subpath per
+/// stretch that stays inside. The same as `path_data` when all of the curve is inside
+pub fn clipped_path_data(curve: &Curve, rect: [Vec2; 2]) -> String {
+    if curve.bounds().is_some_and(|bounds| box_contains(rect, bounds)) { return path_data(curve) };
+
+    let mut d = String::new();
+    let mut pen: Option<Vec2> = None;
+    for segment in curve.segments() {
+        for piece in CubicSegment::new(segment).clip_to_rect(rect) {
+            let [start, c1, c2, end] = piece.points.map(|p| p.pos);
+            if pen.is_none_or(|pen| pen.distance(start) > 1e-3) {
+                let separator = if d.is_empty() { "" } else { " " };
+                let _ = write!(d, "{}M {} {}", separator, number(start.x), number(start.y));
+            }
+            cubic_to(&mut d, [c1, c2, end]);
+            pen = Some(end);
+        }
+    }
+
+    d
+}
+
+#[derive(Debug)]
+pub enum SvgError {
+    /// Byte offset into the path data where it stopped making sense
+    Syntax(usize),
+    Unsupported(char),
+    NoPaths
+}
+
+impl fmt::Display for SvgError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SvgError::Syntax(at) => write!(f, "invalid path data at character {}", at),
+            SvgError::Unsupported(command) => write!(f, "path command {} isn't supported", command),
+            SvgError::NoPaths => write!(f, "no paths with a complete segment")
+        }
+    }
+}
+
+impl std::error::Error for SvgError {}
+
+#[derive(Clone, Copy)]
+enum Token {
+    Command(char),
+    Number(f32)
+}
+
+fn tokenize(d: &str) -> Result<Vec<(usize, Token)>, SvgError> {
+    let bytes = d.as_bytes();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let c = bytes[i] as char;
+        if c.is_ascii_whitespace() || c == ',' {
+            i += 1;
+            continue;
+        }
+        if c.is_ascii_alphabetic() && c != 'e' && c != 'E' {
+            tokens.push((i, Token::Command(c)));
+            i += 1;
+            continue;
+        }
+
+        // Numbers end wherever the next can't continue them, so "1.5.5-2" is three numbers
+        let start = i;
+        if matches!(bytes[i], b'+' | b'-') { i += 1 };
+        let mut seen_dot = false;
+        while i < bytes.len() && (bytes[i].is_ascii_digit() || (bytes[i] == b'.' && !seen_dot)) {
+            seen_dot |= bytes[i] == b'.';
+            i += 1;
+        }
+        if i < bytes.len() && matches!(bytes[i], b'e' | b'E') {
+            i += 1;
+            if i < bytes.len() && matches!(bytes[i], b'+' | b'-') { i += 1 };
+            while i < bytes.len() && bytes[i].is_ascii_digit() { i += 1 };
+        }
+        let number = d[start..i].parse::<f32>().map_err(|_| SvgError::Syntax(start))?;
+        tokens.push((start, Token::Number(number)));
+    }
+
+    Ok(tokens)
+}
+
+// The subpath being read, its points are bezier control points in curve order
+struct Subpath {
+    points: Vec<Vec2>,
+    start: Vec2,
+    pos: Vec2,
+    // Second handle of the last cubic or the control of the last quadratic, for S and T
+    last_cubic: Option<Vec2>,
+    last_quad: Option<Vec2>
+}
+
+impl Subpath {
+    fn cubic(&mut self, c1: Vec2, c2: Vec2, end: Vec2) {
+        if self.points.is_empty() { self.points.push(self.pos) };
+        self.points.extend([c1, c2, end]);
+        self.pos = end;
+        (self.last_cubic, self.last_quad) = (Some(c2), None);
+    }
+
+    fn quad(&mut self, control: Vec2, end: Vec2) {
+        let start = self.pos;
+        self.cubic(start.lerp(control, 2. / 3.), end.lerp(control, 2. / 3.), end);
+        (self.last_cubic, self.last_quad) = (None, Some(control));
+    }
+
+    fn line(&mut self, end: Vec2) {
+        let start = self.pos;
+        self.cubic(start.lerp(end, 1. / 3.), start.lerp(end, 2. / 3.), end);
+        self.last_cubic = None;
+    }
+
+    // Turn what was read into a curve and start over at `start`
+    fn finish(&mut self, closed: bool, colors: &mut impl Iterator<Item = Color>, curves: &mut Vec<Curve>) {
+        let points = std::mem::take(&mut self.points);
+        if points.len() >= CubicSegment::POINTS {
+            curves.push(Curve {
+                control: points.into_iter().map(|pos| Point::new(pos, colors.next().unwrap_or(WHITE))).collect(),
+                closed,
+                modified: true,
+                ..Default::default()
+            });
+        }
+        (self.last_cubic, self.last_quad) = (None, None);
+    }
+}
+
+/// Curves drawn by the `d` attribute of an SVG path, one per subpath, with point colors
+/// taken from `colors`. Lines and quadratics become the equivalent cubics, arcs aren't supported
+pub fn parse_path_data(d: &str, colors: &mut impl Iterator<Item = Color>) -> Result<Vec<Curve>, SvgError> {
+    let tokens = tokenize(d)?;
+    let mut curves = Vec::new();
+    let mut path = Subpath { points: Vec::new(), start: Vec2::ZERO, pos: Vec2::ZERO, last_cubic: None, last_quad: None };
+    let mut command: Option<char> = None;
+    let mut i = 0;
+
+    while i < tokens.len() {
+        if let (_, Token::Command(c)) = tokens[i] {
+            command = Some(c);
+            i += 1;
+            if c.eq_ignore_ascii_case(&'z') {
+                path.finish(true, colors, &mut curves);
+                path.pos = path.start;
+                continue;
+            }
+        }
+
+        let at = tokens[i.min(tokens.len() - 1)].0;
+        let c = command.ok_or(SvgError::Syntax(at))?;
+        let arity = match c.to_ascii_uppercase() {
+            'H' | 'V' => 1,
+            'M' | 'L' | 'T' => 2,
+            'S' | 'Q' => 4,
+            'C' => 6,
+            // Numbers after a Z without a new command
+            'Z' => return Err(SvgError::Syntax(at)),
+            _ => return Err(SvgError::Unsupported(c))
+        };
+        let args: Vec<f32> = tokens.get(i..i + arity)
+            .and_then(|args| args.iter().map(|&(_, token)| match token {
+                Token::Number(n) => Some(n),
+                Token::Command(_) => None
+            }).collect())
+            .ok_or(SvgError::Syntax(at))?;
+        i += arity;
+
+        let base = match c.is_ascii_lowercase() {
+            true => path.pos,
+            false => Vec2::ZERO
+        };
+        let p = |k: usize| base + vec2(args[k], args[k + 1]);
+        match c.to_ascii_uppercase() {
+            'M' => {
+                path.finish(false, colors, &mut curves);
+                (path.start, path.pos) = (p(0), p(0));
+                // Pairs after a moveto are implicit linetos
+                command = Some(if c == 'm' { 'l' } else { 'L' });
+            },
+            'L' => path.line(p(0)),
+            'H' => path.line(vec2(base.x + args[0], path.pos.y)),
+            'V' => path.line(vec2(path.pos.x, base.y + args[0])),
+            'C' => path.cubic(p(0), p(2), p(4)),
+            'S' => {
+                let c1 = path.last_cubic.map_or(path.pos, |c2| 2. * path.pos - c2);
+                path.cubic(c1, p(0), p(2));
+            },
+            'Q' => path.quad(p(0), p(2)),
+            _ => {
+                let control = path.last_quad.map_or(path.pos, |q| 2. * path.pos - q);
+                path.quad(control, p(0));
+            }
+        }
+    }
+    path.finish(false, colors, &mut curves);
+
+    Ok(curves)
+}
+
+// Value of the `d` attribute of every path element
+fn path_attributes(svg: &str) -> Vec<&str> {
+    svg.split("<path").skip(1).filter_map(|element| {
+        let element = &element[..element.find('>').unwrap_or(element.len())];
+        let (at, _) = element.match_indices("d=")
+            .find(|(at, _)| element[..*at].ends_with(|c: char| c.is_whitespace()))?;
+        let value = &element[at + 2..];
+        let quote = value.chars().next().filter(|&q| q == '"' || q == '\'')?;
+        let value = &value[1..];
+        Some(&value[..value.find(quote)?])
+    }).collect()
+}
+
+/// Curves of every path in an SVG document, in its user units. Transforms are ignored
+pub fn parse_svg(svg: &str, colors: &mut impl Iterator<Item = Color>) -> Result<Vec<Curve>, SvgError> {
+    let mut curves = Vec::new();
+    for d in path_attributes(svg) {
+        curves.extend(parse_path_data(d, colors)?);
+    }
+
+    match curves.is_empty() {
+        true => Err(SvgError::NoPaths),
+        false => Ok(curves)
+    }
+}
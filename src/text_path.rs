@@ -0,0 +1,112 @@
+use macroquad::prelude::*;
+
+use crate::curve::Curve;
+use crate::hud::draw_text_box;
+use crate::view::View;
+
+pub const PATH_TEXT_SIZE: f32 = 24.0;
+
+/// A glyph placed on a curve, `pos` is the left end of its baseline in world units and
+/// `angle` the tangent's direction there
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PlacedGlyph {
+    pub glyph: char,
+    pub pos: Vec2,
+    pub angle: f32
+}
+
+/// Lay `text` out along `curve` from its start, each glyph `advance(glyph)` world units
+/// wide and turned with the tangent at its middle. The baseline sits `offset` world units
+/// off the curve, positive to the left of its direction. Glyphs past the end are dropped
+pub fn layout(curve: &Curve, text: &str, advance: impl Fn(char) -> f32, offset: f32) -> Vec<PlacedGlyph> {
+    let mut glyphs = Vec::new();
+    let mut along = 0.;
+    for glyph in text.chars() {
+        let width = advance(glyph);
+        let Some((middle, tangent)) = curve.point_at_length(along + width / 2.) else { break };
+        // y grows down in the world, so the left of the direction is the tangent turned by -90 degrees
+        let normal = vec2(tangent.y, -tangent.x);
+        glyphs.push(PlacedGlyph {
+            glyph,
+            pos: middle + normal * offset - tangent * width / 2.,
+            angle: tangent.y.atan2(tangent.x)
+        });
+        along += width;
+    }
+
+    glyphs
+}
+
+/// Draw `text` along `curve` in screen space, so glyphs are rasterized at their size on
+/// screen. `baseline` is the offset from the curve in pixels
+pub fn draw_text_on_path(curve: &Curve, text: &str, baseline: f32, view: &View, color: Color) {
+    let size = PATH_TEXT_SIZE as u16;
+    let advance = |glyph: char| {
+        let mut buffer = [0; 4];
+        measure_text(glyph.encode_utf8(&mut buffer), None, size, 1.).width / view.scale
+    };
+
+    for placed in layout(curve, text, advance, baseline / view.scale) {
+        let pos = view.world_to_screen(placed.pos);
+        let mut buffer = [0; 4];
+        let params = TextParams { font_size: size, rotation: placed.angle, color, ..Default::default() };
+        draw_text_ex(placed.glyph.encode_utf8(&mut buffer), pos.x, pos.y, params);
+    }
+}
+
+/// Field for typing the string drawn along the active curve
+#[derive(Default)]
+pub struct PathTextEntry {
+    open: bool,
+    text: String
+}
+
+impl PathTextEntry {
+    /// Start editing, the field starts with the current string
+    pub fn open(&mut self, text: &str) {
+        self.open = true;
+        self.text = text.to_string();
+        // Drop whatever was typed before the field opened
+        while get_char_pressed().is_some() {}
+    }
+
+    pub fn close(&mut self) {
+        self.open = false;
+    }
+
+    /// While open the keyboard belongs to the field, shortcuts shouldn't fire
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    /// Read typed characters, returns the string when enter applies it. Escape cancels
+    pub fn update(&mut self) -> Option<String> {
+        if !self.open { return None };
+
+        // macroquad hands out the queued characters newest first
+        let mut typed: Vec<char> = std::iter::from_fn(get_char_pressed).collect();
+        typed.reverse();
+        self.text.extend(typed.into_iter().filter(|c| !c.is_control()));
+        if is_key_pressed(KeyCode::Backspace) {
+            self.text.pop();
+        }
+
+        if is_key_pressed(KeyCode::Escape) {
+            self.close();
+        } else if is_key_pressed(KeyCode::Enter) || is_key_pressed(KeyCode::KpEnter) {
+            self.close();
+            return Some(self.text.clone());
+        }
+
+        None
+    }
+
+    pub fn draw(&self) {
+        if !self.open { return };
+        let lines = vec![
+            format!("text along the curve: {}_", self.text),
+            "enter to apply (empty removes it), esc to cancel".to_string()
+        ];
+        draw_text_box(&lines, vec2(10., screen_height() - 200.));
+    }
+}
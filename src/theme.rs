@@ -0,0 +1,57 @@
+use macroquad::prelude::*;
+
+/// Colors of everything that isn't a curve, curves keep the colors of their points
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Theme {
+    pub background: Color,
+    pub grid_major: Color,
+    pub grid_minor: Color,
+    pub axis: Color,
+    pub bounding_box: Color,
+    pub tight_box: Color,
+    pub oriented_box: Color,
+    pub convex_hull: Color
+}
+
+impl Theme {
+    pub fn dark() -> Self {
+        Self {
+            background: BLACK,
+            grid_major: Color::new(0., 0.89, 0.19, 0.6),
+            grid_minor: Color::new(0., 0.89, 0.19, 0.2),
+            axis: YELLOW,
+            bounding_box: BLUE,
+            tight_box: GOLD,
+            oriented_box: MAGENTA,
+            convex_hull: LIME
+        }
+    }
+
+    pub fn light() -> Self {
+        Self {
+            background: Color::new(0.96, 0.96, 0.94, 1.),
+            grid_major: Color::new(0.2, 0.35, 0.6, 0.45),
+            grid_minor: Color::new(0.2, 0.35, 0.6, 0.15),
+            axis: Color::new(0.75, 0.3, 0., 1.),
+            bounding_box: DARKBLUE,
+            tight_box: Color::new(0.7, 0.5, 0., 1.),
+            oriented_box: Color::new(0.6, 0., 0.6, 1.),
+            convex_hull: DARKGREEN
+        }
+    }
+
+    /// Built-in theme by name
+    pub fn named(name: &str) -> Option<Self> {
+        match name {
+            "dark" => Some(Self::dark()),
+            "light" => Some(Self::light()),
+            _ => None
+        }
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::dark()
+    }
+}
@@ -0,0 +1,117 @@
+use macroquad::prelude::*;
+
+use crate::document::Document;
+use crate::history::History;
+
+const PANEL_TOP: f32 = 30.0;
+const PANEL_MARGIN: f32 = 40.0;
+const ROW_HEIGHT: f32 = 14.0;
+// Rows get squeezed together past this height instead of growing the panel
+const MAX_ROWS_HEIGHT: f32 = 112.0;
+const DOT_RADIUS: f32 = 5.0;
+
+/// Strip at the top of the window with every recorded state of the document as a dot,
+/// branches on their own rows. Clicking a dot goes to that state and dragging scrubs along
+/// the branch being looked at
+#[derive(Default)]
+pub struct Timeline {
+    open: bool,
+    scrubbing: bool
+}
+
+fn row_spacing(rows: usize) -> f32 {
+    ROW_HEIGHT.min(MAX_ROWS_HEIGHT / rows as f32)
+}
+
+fn panel_height(rows: usize) -> f32 {
+    24. + row_spacing(rows) * rows as f32
+}
+
+fn dot_position(index: usize, row: usize, history: &History) -> Vec2 {
+    let width = screen_width() - 2. * PANEL_MARGIN;
+    let last = (history.entries().len() - 1).max(1);
+    let spacing = row_spacing(history.rows());
+    vec2(PANEL_MARGIN + index as f32 / last as f32 * width, PANEL_TOP + 20. + spacing * (row as f32 + 0.5))
+}
+
+impl Timeline {
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    pub fn toggle(&mut self) {
+        self.open = !self.open;
+        self.scrubbing = false;
+    }
+
+    /// Whether the mouse is over the open panel, clicks there shouldn't reach the canvas
+    pub fn hovered(&self, history: &History) -> bool {
+        let (_, my) = mouse_position();
+        self.open && (PANEL_TOP..=PANEL_TOP + panel_height(history.rows())).contains(&my)
+    }
+
+    /// Handle clicking and scrubbing, returns the state to show when it changes
+    pub fn update(&mut self, history: &mut History) -> Option<Document> {
+        if !self.open { return None };
+        let mouse: Vec2 = mouse_position().into();
+
+        if !is_mouse_button_down(MouseButton::Left) {
+            self.scrubbing = false;
+        }
+
+        let mut restored = None;
+        if is_mouse_button_pressed(MouseButton::Left) && self.hovered(history) {
+            self.scrubbing = true;
+            let clicked = history.entries().iter().enumerate()
+                .position(|(i, entry)| dot_position(i, entry.row, history).distance(mouse) <= DOT_RADIUS);
+            if let Some(index) = clicked.filter(|&i| i != history.current()) {
+                restored = history.jump(index);
+            }
+        }
+
+        // The state of the branch nearest to the mouse horizontally
+        if self.scrubbing {
+            let nearest = history.branch().into_iter()
+                .min_by(|&a, &b| {
+                    let distance = |i: usize| (dot_position(i, 0, history).x - mouse.x).abs();
+                    distance(a).total_cmp(&distance(b))
+                });
+            if let Some(index) = nearest.filter(|&i| i != history.current()) {
+                restored = history.jump(index);
+            }
+        }
+
+        restored
+    }
+
+    pub fn draw(&self, history: &History) {
+        if !self.open { return };
+        let entries = history.entries();
+        let branch = history.branch();
+
+        draw_rectangle(0., PANEL_TOP, screen_width(), panel_height(history.rows()), Color::new(0., 0., 0., 0.8));
+
+        // Each state is linked to the one it was edited from, with an elbow for branches
+        for (i, entry) in entries.iter().enumerate() {
+            let Some(parent) = entry.parent else { continue };
+            let (a, b) = (dot_position(parent, entries[parent].row, history), dot_position(i, entry.row, history));
+            let color = if branch.contains(&i) { LIGHTGRAY } else { DARKGRAY };
+            draw_line(a.x, a.y, a.x, b.y, 1., color);
+            draw_line(a.x, b.y, b.x, b.y, 1., color);
+        }
+
+        for (i, entry) in entries.iter().enumerate() {
+            let pos = dot_position(i, entry.row, history);
+            let color = match (i == history.current(), branch.contains(&i)) {
+                (true, _) => YELLOW,
+                (false, true) => WHITE,
+                (false, false) => GRAY
+            };
+            draw_circle(pos.x, pos.y, DOT_RADIUS, color);
+        }
+
+        let label = format!("history: state {} of {}, {} branches - click or drag to go back, edit to branch",
+            history.current() + 1, entries.len(), history.rows());
+        draw_text(&label, PANEL_MARGIN, PANEL_TOP + 14., 16., LIGHTGRAY);
+    }
+}
@@ -0,0 +1,279 @@
+use std::collections::HashMap;
+
+use image::RgbaImage;
+use macroquad::prelude::*;
+
+use crate::curve::Curve;
+use crate::point::Point;
+use crate::reference::ReferenceImage;
+
+/// Thresholds shift + the trace key cycles through, from 0 black to 1 white
+pub const TRACE_THRESHOLDS: [f32; 3] = [0.25, 0.5, 0.75];
+// Outlines enclosing at most this many pixels are specks and dropped, like potrace's turdsize
+const SPECK_AREA: f32 = 2.;
+// Fitted segments stay within this many image pixels of the outline
+const FIT_TOLERANCE: f32 = 1.;
+// Points looked ahead and behind when estimating tangents and finding corners
+const TANGENT_REACH: usize = 3;
+// Turns sharper than this between the directions into and out of a point make a corner, ~70 degrees
+const CORNER_ANGLE: f32 = 1.2;
+// Newton steps on the parameters before a poorly fitting segment is split instead
+const REPARAMETERIZE_STEPS: usize = 4;
+
+/// Which pixels of an image are ink, the opaque ones darker than the threshold
+pub struct Bitmap {
+    pub width: usize,
+    pub height: usize,
+    ink: Vec<bool>
+}
+
+impl Bitmap {
+    pub fn from_image(image: &RgbaImage, threshold: f32) -> Self {
+        let ink = image.pixels().map(|pixel| {
+            let [r, g, b, a] = pixel.0.map(|c| c as f32 / 255.);
+            let luma = 0.2126 * r + 0.7152 * g + 0.0722 * b;
+            a > 0.5 && luma < threshold
+        }).collect();
+        Self { width: image.width() as usize, height: image.height() as usize, ink }
+    }
+
+    /// Whether the pixel is ink, everything outside the image is background
+    pub fn get(&self, x: isize, y: isize) -> bool {
+        match (usize::try_from(x), usize::try_from(y)) {
+            (Ok(x), Ok(y)) if x < self.width && y < self.height => self.ink[y * self.width + x],
+            _ => false
+        }
+    }
+}
+
+/// Closed outlines between ink and background along the pixel edges, as the corners they pass
+/// in pixels with y down. They go clockwise on screen around ink, so holes run the other way.
+/// Ink pixels touching only at a corner end up in the same outline
+pub fn contours(bitmap: &Bitmap) -> Vec<Vec<Vec2>> {
+    // Unit edges by the corner they start at, each with ink on its right
+    let mut edges: HashMap<(isize, isize), Vec<(isize, isize)>> = HashMap::new();
+    for y in 0..bitmap.height as isize {
+        for x in 0..bitmap.width as isize {
+            if !bitmap.get(x, y) { continue };
+            let sides = [
+                ((0, -1), (x, y), (x + 1, y)),
+                ((1, 0), (x + 1, y), (x + 1, y + 1)),
+                ((0, 1), (x + 1, y + 1), (x, y + 1)),
+                ((-1, 0), (x, y + 1), (x, y))
+            ];
+            for ((dx, dy), from, to) in sides {
+                if !bitmap.get(x + dx, y + dy) {
+                    edges.entry(from).or_default().push(to);
+                }
+            }
+        }
+    }
+
+    // Sorted so the same image always traces the same way
+    let mut starts: Vec<(isize, isize)> = edges.keys().copied().collect();
+    starts.sort_unstable_by_key(|&(x, y)| (y, x));
+
+    let mut outlines = Vec::new();
+    for start in starts {
+        while let Some(first) = edges.get_mut(&start).and_then(|out| out.pop()) {
+            let mut outline = vec![start];
+            let (mut previous, mut corner) = (start, first);
+            while corner != start {
+                outline.push(corner);
+                // Every corner is left as often as it's entered, so there's always a way on.
+                // Where two edges leave, the left turn keeps diagonal neighbours together
+                let Some(out) = edges.get_mut(&corner).filter(|out| !out.is_empty()) else { break };
+                let incoming = (corner.0 - previous.0, corner.1 - previous.1);
+                let turn = |to: &(isize, isize)| incoming.0 * (to.1 - corner.1) - incoming.1 * (to.0 - corner.0);
+                let next = (0..out.len()).min_by_key(|&i| turn(&out[i])).unwrap_or(0);
+                previous = corner;
+                corner = out.swap_remove(next);
+            }
+            outlines.push(outline.into_iter().map(|(x, y)| vec2(x as f32, y as f32)).collect());
+        }
+    }
+
+    outlines
+}
+
+// Shoelace formula, positive for clockwise loops on screen
+fn signed_area(outline: &[Vec2]) -> f32 {
+    let n = outline.len();
+    (0..n).map(|i| outline[i].perp_dot(outline[(i + 1) % n])).sum::<f32>() / 2.
+}
+
+fn bernstein(t: f32) -> [f32; 4] {
+    let s = 1. - t;
+    [s * s * s, 3. * s * s * t, 3. * s * t * t, t * t * t]
+}
+
+fn evaluate(bezier: [Vec2; 4], t: f32) -> Vec2 {
+    let [b0, b1, b2, b3] = bernstein(t);
+    bezier[0] * b0 + bezier[1] * b1 + bezier[2] * b2 + bezier[3] * b3
+}
+
+// Parameters proportional to the distance travelled along the points
+fn chord_params(points: &[Vec2]) -> Vec<f32> {
+    let mut params = vec![0.];
+    for pair in points.windows(2) {
+        params.push(params[params.len() - 1] + pair[0].distance(pair[1]));
+    }
+    let total = params[params.len() - 1];
+    if total > 0. {
+        params.iter_mut().for_each(|u| *u /= total);
+    }
+    params
+}
+
+// The cubic from the first to the last point with handles along the given tangents whose
+// lengths best fit the points in the least squares sense. `end_tangent` points back from
+// the last point into the curve
+fn least_squares(points: &[Vec2], params: &[f32], start_tangent: Vec2, end_tangent: Vec2) -> [Vec2; 4] {
+    let (first, last) = (points[0], points[points.len() - 1]);
+    let (mut c, mut x) = ([[0f32; 2]; 2], [0f32; 2]);
+    for (&p, &u) in points.iter().zip(params) {
+        let [b0, b1, b2, b3] = bernstein(u);
+        let (a0, a1) = (start_tangent * b1, end_tangent * b2);
+        c[0][0] += a0.dot(a0);
+        c[0][1] += a0.dot(a1);
+        c[1][1] += a1.dot(a1);
+        let rest = p - (first * (b0 + b1) + last * (b2 + b3));
+        x[0] += a0.dot(rest);
+        x[1] += a1.dot(rest);
+    }
+
+    let det = c[0][0] * c[1][1] - c[0][1] * c[0][1];
+    let (alpha_start, alpha_end) = match det.abs() > 1e-12 {
+        true => ((x[0] * c[1][1] - x[1] * c[0][1]) / det, (c[0][0] * x[1] - c[0][1] * x[0]) / det),
+        false => (0., 0.)
+    };
+    // Handles pointing backwards or vanishing make loops and cusps, a third of the chord is safe
+    let chord = first.distance(last);
+    let (alpha_start, alpha_end) = match alpha_start < chord * 1e-3 || alpha_end < chord * 1e-3 {
+        true => (chord / 3., chord / 3.),
+        false => (alpha_start, alpha_end)
+    };
+    [first, first + start_tangent * alpha_start, last + end_tangent * alpha_end, last]
+}
+
+// Squared distance of the point farthest from the cubic at its parameter, and its index
+fn max_error(points: &[Vec2], params: &[f32], bezier: [Vec2; 4]) -> (f32, usize) {
+    points.iter().zip(params).enumerate()
+        .map(|(i, (&p, &u))| (evaluate(bezier, u).distance_squared(p), i))
+        .fold((0., points.len() / 2), |best, error| if error.0 > best.0 { error } else { best })
+}
+
+// One Newton step towards the parameter of the closest point on the cubic to each point
+fn reparameterize(points: &[Vec2], params: &mut [f32], bezier: [Vec2; 4]) {
+    let [p0, p1, p2, p3] = bezier;
+    for (&p, u) in points.iter().zip(params) {
+        let (t, s) = (*u, 1. - *u);
+        let d1 = 3. * ((p1 - p0) * s * s + (p2 - p1) * 2. * s * t + (p3 - p2) * t * t);
+        let d2 = 6. * ((p2 - 2. * p1 + p0) * s + (p3 - 2. * p2 + p1) * t);
+        let off = evaluate(bezier, t) - p;
+        let denominator = d1.dot(d1) + off.dot(d2);
+        if denominator.abs() > 1e-12 {
+            *u = (t - off.dot(d1) / denominator).clamp(0., 1.);
+        }
+    }
+}
+
+// Schneider's algorithm, fit one cubic and split at the worst point until within tolerance
+fn fit_cubic(points: &[Vec2], start_tangent: Vec2, end_tangent: Vec2, tolerance: f32, segments: &mut Vec<[Vec2; 4]>) {
+    let (first, last) = (points[0], points[points.len() - 1]);
+    if points.len() <= 2 {
+        let third = first.distance(last) / 3.;
+        segments.push([first, first + start_tangent * third, last + end_tangent * third, last]);
+        return;
+    }
+
+    let mut params = chord_params(points);
+    let mut bezier = least_squares(points, &params, start_tangent, end_tangent);
+    let (mut error, mut worst) = max_error(points, &params, bezier);
+    for _ in 0..REPARAMETERIZE_STEPS {
+        if error <= tolerance * tolerance { break };
+        reparameterize(points, &mut params, bezier);
+        bezier = least_squares(points, &params, start_tangent, end_tangent);
+        (error, worst) = max_error(points, &params, bezier);
+    }
+    if error <= tolerance * tolerance {
+        segments.push(bezier);
+        return;
+    }
+
+    let split = worst.clamp(1, points.len() - 2);
+    let center = (points[split - 1] - points[split + 1]).normalize_or_zero();
+    fit_cubic(&points[..=split], start_tangent, center, tolerance, segments);
+    fit_cubic(&points[split..], -center, end_tangent, tolerance, segments);
+}
+
+/// Cubic segments within `tolerance` of the closed loop through `points`, the last one ends
+/// where the first starts. The loop is cut at its corners, which keep their sharp turn,
+/// and everywhere else the segments join smoothly
+pub fn fit_closed(points: &[Vec2], tolerance: f32) -> Vec<[Vec2; 4]> {
+    let n = points.len();
+    if n < 3 { return Vec::new() };
+    let reach = TANGENT_REACH.min((n - 1) / 2).max(1);
+    let at = |i: usize| points[i % n];
+    // Directions into and out of each point, looking a few points away to skip pixel steps
+    let into = |i: usize| (at(i + n) - at(i + n - reach)).normalize_or_zero();
+    let out_of = |i: usize| (at(i + reach) - at(i)).normalize_or_zero();
+    let through = |i: usize| (at(i + reach) - at(i + n - reach)).normalize_or_zero();
+    let turn = |i: usize| into(i).angle_between(out_of(i)).abs();
+
+    // The sharpest turn around stands for the whole bend
+    let mut cuts: Vec<usize> = (0..n)
+        .filter(|&i| {
+            let angle = turn(i);
+            angle > CORNER_ANGLE && (1..=reach).all(|d| angle >= turn(i + d) && angle > turn(i + n - d))
+        })
+        .collect();
+    let corners = cuts.clone();
+    // Loops without enough corners are cut in halves, those cuts join smoothly
+    match cuts.len() {
+        0 => cuts = vec![0, n / 2],
+        1 => cuts.push((cuts[0] + n / 2) % n),
+        _ => ()
+    }
+    cuts.sort_unstable();
+
+    let mut segments = Vec::new();
+    for (k, &start) in cuts.iter().enumerate() {
+        let end = cuts[(k + 1) % cuts.len()];
+        let length = (end + n - start - 1) % n + 1;
+        let span: Vec<Vec2> = (start..=start + length).map(at).collect();
+
+        let start_tangent = match corners.contains(&start) {
+            true => out_of(start),
+            false => through(start)
+        };
+        let end_tangent = match corners.contains(&end) {
+            true => -into(end),
+            false => -through(end)
+        };
+        fit_cubic(&span, start_tangent, end_tangent, tolerance, &mut segments);
+    }
+
+    segments
+}
+
+/// Closed curves around the parts of `image` darker than `threshold`, in world space
+pub fn trace(image: &ReferenceImage, threshold: f32, colors: &mut impl Iterator<Item = Color>) -> Vec<Curve> {
+    let bitmap = Bitmap::from_image(image.pixels(), threshold);
+    contours(&bitmap).into_iter()
+        .filter(|outline| signed_area(outline).abs() > SPECK_AREA)
+        .filter_map(|outline| {
+            // Edge midpoints cut the staircase corners, so diagonals come out straight
+            let n = outline.len();
+            let midpoints: Vec<Vec2> = (0..n).map(|i| (outline[i] + outline[(i + 1) % n]) / 2.).collect();
+            let segments = fit_closed(&midpoints, FIT_TOLERANCE);
+            let first = segments.first()?[0];
+
+            let mut control = vec![Point::new(image.to_world(first), colors.next().unwrap_or(WHITE))];
+            for segment in &segments {
+                control.extend(segment[1..].iter().map(|&p| Point::new(image.to_world(p), colors.next().unwrap_or(WHITE))));
+            }
+            Some(Curve { control, closed: true, modified: true, ..Default::default() })
+        })
+        .collect()
+}
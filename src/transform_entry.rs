@@ -0,0 +1,122 @@
+use macroquad::prelude::*;
+
+use crate::coords::CoordinateSystem;
+use crate::hud::draw_text_box;
+
+/// An exact transform typed in the user's coordinates
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum NumericTransform {
+    /// By this many units
+    Translate(Vec2),
+    /// By this many degrees, counterclockwise with y up
+    Rotate(f32),
+    /// By these factors along x and y
+    Scale(Vec2)
+}
+
+/// "move x, y", "rotate degrees", "scale factor" or "scale x, y", where the words can be
+/// shortened to their first letter and factors written as percentages like 150%
+pub fn parse_transform(text: &str) -> Option<NumericTransform> {
+    let text = text.trim();
+    let (word, rest) = text.split_once(char::is_whitespace).unwrap_or((text, ""));
+    let number = |part: &str| match part.strip_suffix('%') {
+        Some(percent) => percent.parse::<f32>().ok().map(|p| p / 100.),
+        None => part.strip_suffix('°').unwrap_or(part).parse::<f32>().ok()
+    };
+    let numbers: Vec<f32> = rest.split(|c: char| c == ',' || c == ';' || c.is_whitespace())
+        .filter(|part| !part.is_empty())
+        .map(number)
+        .collect::<Option<_>>()?;
+    if numbers.iter().any(|n| !n.is_finite()) { return None };
+
+    match (word.to_lowercase().as_str(), &numbers[..]) {
+        ("move" | "m" | "translate" | "t", &[x, y]) => Some(NumericTransform::Translate(vec2(x, y))),
+        ("rotate" | "r", &[angle]) => Some(NumericTransform::Rotate(angle)),
+        ("scale" | "s", &[k]) if k != 0. => Some(NumericTransform::Scale(Vec2::splat(k))),
+        ("scale" | "s", &[x, y]) if x != 0. && y != 0. => Some(NumericTransform::Scale(vec2(x, y))),
+        _ => None
+    }
+}
+
+impl NumericTransform {
+    /// The transform in world space, around the world origin
+    pub fn matrix(self, coords: &CoordinateSystem) -> Mat3 {
+        let math = match self {
+            NumericTransform::Translate(offset) => Mat3::from_translation(offset),
+            NumericTransform::Rotate(angle) => Mat3::from_angle(angle.to_radians()),
+            NumericTransform::Scale(factors) => Mat3::from_scale(factors)
+        };
+        // Units to world displacements, which flips y when it points up
+        let to_world = |v: Vec2| coords.from_math(v) - coords.from_math(Vec2::ZERO);
+        let units = Mat3::from_mat2(Mat2::from_cols(to_world(Vec2::X), to_world(Vec2::Y)));
+        units * math * units.inverse()
+    }
+}
+
+/// Text field for transforming the active curve by exact amounts, which dragging can't do
+#[derive(Default)]
+pub struct TransformEntry {
+    open: bool,
+    text: String,
+    error: Option<String>
+}
+
+impl TransformEntry {
+    pub fn open(&mut self) {
+        self.open = true;
+        self.text.clear();
+        self.error = None;
+        // Drop whatever was typed before the field opened
+        while get_char_pressed().is_some() {}
+    }
+
+    pub fn close(&mut self) {
+        self.open = false;
+    }
+
+    /// While open the keyboard belongs to the field, shortcuts shouldn't fire
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    /// Read typed characters, returns the transform when enter applies it. Escape cancels
+    pub fn update(&mut self) -> Option<NumericTransform> {
+        if !self.open { return None };
+
+        // macroquad hands out the queued characters newest first
+        let mut typed: Vec<char> = std::iter::from_fn(get_char_pressed).collect();
+        typed.reverse();
+        self.text.extend(typed.into_iter().filter(|c| !c.is_control()));
+        if is_key_pressed(KeyCode::Backspace) {
+            self.text.pop();
+        }
+
+        if is_key_pressed(KeyCode::Escape) {
+            self.close();
+        } else if is_key_pressed(KeyCode::Enter) || is_key_pressed(KeyCode::KpEnter) {
+            match parse_transform(&self.text) {
+                Some(transform) => {
+                    self.close();
+                    return Some(transform);
+                },
+                None => self.error = Some("expected move x, y or rotate degrees or scale 150% (or x, y)".to_string())
+            }
+        }
+
+        None
+    }
+
+    /// `pivot` says where rotating and scaling happen around
+    pub fn draw(&self, pivot: &str) {
+        if !self.open { return };
+        let mut lines = vec![
+            format!("transform: {}_", self.text),
+            format!("move 2, -1 | rotate 30 | scale 150% or 2, 50%, around the {}", pivot),
+            "enter to apply, esc to cancel".to_string()
+        ];
+        if let Some(error) = &self.error {
+            lines.push(error.clone());
+        }
+        draw_text_box(&lines, vec2(10., screen_height() - 200.));
+    }
+}
@@ -0,0 +1,138 @@
+use macroquad::prelude::*;
+
+use crate::curve::Curve;
+use crate::math::{arc_length, cubic_bezier, curvature, param_at_length, velocity};
+
+// Curvature samples per segment when looking for turns that are too tight
+const LIMIT_SAMPLES: usize = 64;
+
+/// A car-like vehicle following a path with the kinematic bicycle model, its rear axle on
+/// the path and the front wheels steering by atan(wheelbase * curvature)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct VehicleOptions {
+    /// Distance between the axles, in units
+    pub wheelbase: f32,
+    /// Radius of the tightest turn the vehicle can make, in units
+    pub min_turn_radius: f32,
+    /// Units per second
+    pub speed: f32
+}
+
+impl Default for VehicleOptions {
+    fn default() -> Self {
+        Self { wheelbase: 0.5, min_turn_radius: 1., speed: 2. }
+    }
+}
+
+impl VehicleOptions {
+    /// Largest steering angle, the one that makes the tightest turn
+    pub fn max_steering(&self) -> f32 {
+        (self.wheelbase / self.min_turn_radius).atan()
+    }
+}
+
+/// Where the vehicle is on the path and how it has to steer there, in world space
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Pose {
+    pub pos: Vec2,
+    /// Unit direction the vehicle faces
+    pub heading: Vec2,
+    /// Signed curvature of the path, positive turning clockwise on screen
+    pub curvature: f32
+}
+
+impl Pose {
+    /// Steering angle of the front wheels for a `wheelbase` in world units, with the sign
+    /// of the curvature
+    pub fn steering(&self, wheelbase: f32) -> f32 {
+        (wheelbase * self.curvature).atan()
+    }
+}
+
+/// The pose `length` world units along `curve`, None outside of it or without a complete segment
+pub fn pose_at(curve: &Curve, length: f32) -> Option<Pose> {
+    if length < 0. { return None };
+    let mut travelled = 0.;
+    for segment in curve.segments() {
+        let segment_length = arc_length(segment);
+        if length <= travelled + segment_length {
+            let t = param_at_length(segment, length - travelled);
+            let heading = velocity(segment, t).try_normalize().unwrap_or(Vec2::X);
+            return Some(Pose { pos: cubic_bezier(t, &segment), heading, curvature: curvature(segment, t) });
+        }
+        travelled += segment_length;
+    }
+
+    None
+}
+
+/// Stretches of `curve` that turn tighter than `max_curvature`, each as the polyline of its samples
+pub fn tight_stretches(curve: &Curve, max_curvature: f32) -> Vec<Vec<Vec2>> {
+    let mut stretches: Vec<Vec<Vec2>> = Vec::new();
+    let mut open = false;
+    for segment in curve.segments() {
+        for i in 0..=LIMIT_SAMPLES {
+            let t = i as f32 / LIMIT_SAMPLES as f32;
+            let tight = curvature(segment, t).abs() > max_curvature;
+            match stretches.last_mut().filter(|_| open) {
+                Some(stretch) if tight => stretch.push(cubic_bezier(t, &segment)),
+                None if tight => stretches.push(vec![cubic_bezier(t, &segment)]),
+                _ => ()
+            }
+            open = tight;
+        }
+    }
+
+    stretches
+}
+
+/// A vehicle driving along one of the document's curves, starting over at the end
+pub struct Vehicle {
+    /// Index of the curve driven on in the document
+    pub curve: usize,
+    /// World units from the start of the curve
+    pub travelled: f32
+}
+
+impl Vehicle {
+    pub fn new(curve: usize) -> Self {
+        Self { curve, travelled: 0. }
+    }
+
+    /// Drive `distance` world units further, returns the new pose
+    pub fn advance(&mut self, curve: &Curve, distance: f32) -> Option<Pose> {
+        let length: f32 = curve.segments().into_iter().map(arc_length).sum();
+        if length <= 0. { return None };
+        self.travelled = (self.travelled + distance) % length;
+        pose_at(curve, self.travelled)
+    }
+}
+
+/// The body as a box around the axles and the wheels as short lines, the front ones turned
+/// by `steering`. With the world camera set, `wheelbase` in world units
+pub fn draw_vehicle(pose: &Pose, wheelbase: f32, steering: f32, line_width: f32, color: Color) {
+    let forward = pose.heading;
+    let side = forward.perp();
+    let (rear, front) = (pose.pos, pose.pos + forward * wheelbase);
+    let (overhang, half_width) = (wheelbase * 0.3, wheelbase * 0.4);
+
+    let corners = [
+        rear - forward * overhang - side * half_width,
+        front + forward * overhang - side * half_width,
+        front + forward * overhang + side * half_width,
+        rear - forward * overhang + side * half_width
+    ];
+    for i in 0..4 {
+        let (a, b) = (corners[i], corners[(i + 1) % 4]);
+        draw_line(a.x, a.y, b.x, b.y, line_width, color);
+    }
+
+    let wheel = wheelbase * 0.25;
+    let front_direction = Vec2::from_angle(steering).rotate(forward);
+    for (axle, direction) in [(rear, forward), (front, front_direction)] {
+        for offset in [-half_width, half_width] {
+            let (a, b) = (axle + side * offset - direction * wheel / 2., axle + side * offset + direction * wheel / 2.);
+            draw_line(a.x, a.y, b.x, b.y, line_width * 2., color);
+        }
+    }
+}
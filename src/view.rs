@@ -0,0 +1,65 @@
+use macroquad::prelude::*;
+
+/// Maps the world (where curves live) to the screen. `center` is the world position at
+/// the middle of the window and `scale` how many pixels one world unit takes
+pub struct View {
+    pub center: Vec2,
+    pub scale: f32
+}
+
+impl Default for View {
+    fn default() -> Self {
+        Self { center: vec2(screen_width() / 2., screen_height() / 2.), scale: 1. }
+    }
+}
+
+impl View {
+    pub fn camera(&self) -> Camera2D {
+        // A minimized window can report a zero size
+        let size = vec2(screen_width(), screen_height()).max(Vec2::ONE) / self.scale;
+        let origin = self.center - size / 2.;
+
+        Camera2D::from_display_rect(Rect::new(origin.x, origin.y, size.x, size.y))
+    }
+
+    pub fn screen_to_world(&self, point: Vec2) -> Vec2 {
+        self.center + (point - vec2(screen_width(), screen_height()) / 2.) / self.scale
+    }
+
+    pub fn world_to_screen(&self, point: Vec2) -> Vec2 {
+        (point - self.center) * self.scale + vec2(screen_width(), screen_height()) / 2.
+    }
+
+    /// World rectangle covered by the window, as its min and max corners
+    pub fn visible_rect(&self) -> [Vec2; 2] {
+        [self.screen_to_world(Vec2::ZERO), self.screen_to_world(vec2(screen_width(), screen_height()))]
+    }
+
+    /// Keep the world under the window's top left corner in place when the window goes from
+    /// `old_size` to `new_size`, so resizing reveals or hides canvas on the right and bottom
+    pub fn resize(&mut self, old_size: Vec2, new_size: Vec2) {
+        self.center += (new_size - old_size) / 2. / self.scale;
+    }
+
+    /// Zoom by `factor` keeping the world point under `screen_point` in place
+    pub fn zoom_at(&mut self, screen_point: Vec2, factor: f32) {
+        let anchor = self.screen_to_world(screen_point);
+        self.scale = (self.scale * factor).clamp(0.01, 100.);
+        self.center += anchor - self.screen_to_world(screen_point);
+    }
+
+    /// Move the view so the world follows a drag of `screen_delta` pixels
+    pub fn pan(&mut self, screen_delta: Vec2) {
+        self.center -= screen_delta / self.scale;
+    }
+
+    /// Center on `bounds` and zoom so it fills the window leaving `margin` pixels on each side
+    pub fn fit(&mut self, bounds: [Vec2; 2], margin: f32) {
+        let [pmin, pmax] = bounds;
+        let size = (pmax - pmin).max(Vec2::splat(1.));
+        let available = (vec2(screen_width(), screen_height()) - 2. * margin).max(Vec2::splat(1.));
+
+        self.center = (pmin + pmax) / 2.;
+        self.scale = (available / size).min_element();
+    }
+}
@@ -0,0 +1,100 @@
+"use strict";
+
+// Browser side of src/storage.rs, registered as a miniquad plugin. Strings come in as a
+// pointer and a length into the wasm memory, and go out by copying into a buffer from Rust
+(function () {
+    const encoder = new TextEncoder();
+    const decoder = new TextDecoder();
+    // Files picked or dropped on the page, as encoded name and raw bytes, until Rust takes them
+    const opened = [];
+
+    function read(ptr, len) {
+        return decoder.decode(new Uint8Array(wasm_memory.buffer, ptr, len));
+    }
+
+    function write(bytes, ptr) {
+        new Uint8Array(wasm_memory.buffer, ptr, bytes.length).set(bytes);
+    }
+
+    function queue(file) {
+        file.arrayBuffer().then(data => opened.push({ name: encoder.encode(file.name), data: new Uint8Array(data) }));
+    }
+
+    function local_item(key_ptr, key_len) {
+        try {
+            return localStorage.getItem(read(key_ptr, key_len));
+        } catch (error) {
+            return null;
+        }
+    }
+
+    function register_plugin(importObject) {
+        const env = importObject.env;
+
+        env.storage_download = function (name_ptr, name_len, data_ptr, data_len) {
+            // Copied out, the wasm memory can move once Rust carries on
+            const blob = new Blob([new Uint8Array(wasm_memory.buffer, data_ptr, data_len).slice()]);
+            const link = document.createElement("a");
+            link.href = URL.createObjectURL(blob);
+            link.download = read(name_ptr, name_len);
+            link.click();
+            setTimeout(() => URL.revokeObjectURL(link.href), 0);
+        };
+
+        env.storage_pick_file = function () {
+            const input = document.createElement("input");
+            input.type = "file";
+            input.accept = ".json,.svg,.csv,.txt,.png,.jpg,.jpeg";
+            input.onchange = () => Array.from(input.files).forEach(queue);
+            input.click();
+        };
+
+        env.storage_opened_name_len = function () {
+            return opened.length === 0 ? -1 : opened[0].name.length;
+        };
+
+        env.storage_opened_len = function () {
+            return opened.length === 0 ? -1 : opened[0].data.length;
+        };
+
+        env.storage_take_opened = function (name_ptr, ptr) {
+            const file = opened.shift();
+            write(file.name, name_ptr);
+            write(file.data, ptr);
+        };
+
+        env.storage_local_set = function (key_ptr, key_len, value_ptr, value_len) {
+            try {
+                localStorage.setItem(read(key_ptr, key_len), read(value_ptr, value_len));
+            } catch (error) {
+                console.warn("couldn't autosave: " + error);
+            }
+        };
+
+        env.storage_local_len = function (key_ptr, key_len) {
+            const value = local_item(key_ptr, key_len);
+            return value === null ? -1 : encoder.encode(value).length;
+        };
+
+        env.storage_local_get = function (key_ptr, key_len, ptr) {
+            write(encoder.encode(local_item(key_ptr, key_len)), ptr);
+        };
+
+        env.storage_local_remove = function (key_ptr, key_len) {
+            try {
+                localStorage.removeItem(read(key_ptr, key_len));
+            } catch (error) {
+                console.warn("couldn't discard the autosave: " + error);
+            }
+        };
+    }
+
+    // miniquad has its own drop handling, this one reads the files' contents
+    window.addEventListener("dragover", event => event.preventDefault());
+    window.addEventListener("drop", function (event) {
+        event.preventDefault();
+        Array.from(event.dataTransfer.files).forEach(queue);
+    });
+
+    miniquad_add_plugin({ register_plugin, name: "numerical_curves_storage", version: 1 });
+})();